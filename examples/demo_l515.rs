@@ -40,16 +40,44 @@ fn main() -> Result<()> {
         config
             .enable_device_from_serial(devices[0].info(Rs2CameraInfo::SerialNumber).unwrap())?
             .disable_all_streams()?
-            .enable_stream(Rs2StreamKind::Depth, None, 640, 0, Rs2Format::Z16, 30)?
-            .enable_stream(Rs2StreamKind::Infrared, None, 640, 0, Rs2Format::Y8, 30)?
-            .enable_stream(Rs2StreamKind::Gyro, None, 0, 0, Rs2Format::Any, 0)?;
+            .enable_stream(
+                Rs2StreamKind::Depth,
+                None,
+                Some(640),
+                None,
+                Rs2Format::Z16,
+                30,
+            )?
+            .enable_stream(
+                Rs2StreamKind::Infrared,
+                None,
+                Some(640),
+                None,
+                Rs2Format::Y8,
+                30,
+            )?
+            .enable_stream(Rs2StreamKind::Gyro, None, None, None, Rs2Format::Any, 0)?;
     } else {
         config
             .enable_device_from_serial(devices[0].info(Rs2CameraInfo::SerialNumber).unwrap())?
             .disable_all_streams()?
-            .enable_stream(Rs2StreamKind::Depth, None, 320, 0, Rs2Format::Z16, 30)?
-            .enable_stream(Rs2StreamKind::Infrared, None, 320, 0, Rs2Format::Y8, 30)?
-            .enable_stream(Rs2StreamKind::Gyro, None, 0, 0, Rs2Format::Any, 0)?;
+            .enable_stream(
+                Rs2StreamKind::Depth,
+                None,
+                Some(320),
+                None,
+                Rs2Format::Z16,
+                30,
+            )?
+            .enable_stream(
+                Rs2StreamKind::Infrared,
+                None,
+                Some(320),
+                None,
+                Rs2Format::Y8,
+                30,
+            )?
+            .enable_stream(Rs2StreamKind::Gyro, None, None, None, Rs2Format::Any, 0)?;
     }
 
     // Change pipeline's type from InactivePipeline -> ActivePipeline
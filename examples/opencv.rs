@@ -98,8 +98,22 @@ fn main() -> Result<()> {
     config
         .enable_device_from_serial(devices[0].info(Rs2CameraInfo::SerialNumber).unwrap())?
         .disable_all_streams()?
-        .enable_stream(Rs2StreamKind::Color, None, 640, 0, Rs2Format::Bgr8, 30)?
-        .enable_stream(Rs2StreamKind::Depth, None, 0, 240, Rs2Format::Z16, 30)
+        .enable_stream(
+            Rs2StreamKind::Color,
+            None,
+            Some(640),
+            None,
+            Rs2Format::Bgr8,
+            30,
+        )?
+        .enable_stream(
+            Rs2StreamKind::Depth,
+            None,
+            None,
+            Some(240),
+            Rs2Format::Z16,
+            30,
+        )
         .unwrap();
 
     // Change pipeline's type from InactivePipeline -> ActivePipeline
@@ -6,6 +6,7 @@ use opencv::{core, highgui, imgproc, prelude::*};
 use realsense_rust::{
     config::Config,
     context::Context,
+    fps::FpsCounter,
     frame::PixelKind,
     frame::{ColorFrame, DepthFrame},
     kind::{Rs2CameraInfo, Rs2Format, Rs2StreamKind},
@@ -99,8 +100,22 @@ fn main() -> Result<()> {
     config
         .enable_device_from_serial(devices[0].info(Rs2CameraInfo::SerialNumber).unwrap())?
         .disable_all_streams()?
-        .enable_stream(Rs2StreamKind::Color, None, 640, 0, Rs2Format::Bgr8, 15)?
-        .enable_stream(Rs2StreamKind::Depth, None, 0, 480, Rs2Format::Z16, 15)
+        .enable_stream(
+            Rs2StreamKind::Color,
+            None,
+            Some(640),
+            None,
+            Rs2Format::Bgr8,
+            15,
+        )?
+        .enable_stream(
+            Rs2StreamKind::Depth,
+            None,
+            None,
+            Some(480),
+            Rs2Format::Z16,
+            15,
+        )
         .unwrap();
 
     // Change pipeline's type from InactivePipeline -> ActivePipeline
@@ -120,7 +135,7 @@ fn main() -> Result<()> {
 
     // process frames
     let timeout = Duration::from_millis(1000);
-    let mut now = std::time::SystemTime::now();
+    let mut fps = FpsCounter::new(30);
 
     let mut aligned_mat = unsafe { Mat::new_rows_cols(480, 640, core::CV_8UC3).unwrap() };
 
@@ -192,13 +207,9 @@ fn main() -> Result<()> {
         }
 
         // Print out our Hz for the loop
-        print!(
-            "\rCurrent hz: {:.4?} fps",
-            1000.0 / (now.elapsed().unwrap().as_millis()) as f64
-        );
+        print!("\rCurrent hz: {:.4?} fps", fps.tick());
         use std::io::Write;
         stdout().flush().unwrap();
-        now = std::time::SystemTime::now();
     }
 
     Ok(())
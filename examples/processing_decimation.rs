@@ -6,6 +6,7 @@ use opencv::{core, highgui, imgproc, prelude::*};
 use realsense_rust::{
     config::Config,
     context::Context,
+    fps::FpsCounter,
     frame::DepthFrame,
     frame::PixelKind,
     kind::{Rs2CameraInfo, Rs2Format, Rs2StreamKind},
@@ -75,7 +76,14 @@ fn main() -> Result<()> {
     config
         .enable_device_from_serial(devices[0].info(Rs2CameraInfo::SerialNumber).unwrap())?
         .disable_all_streams()?
-        .enable_stream(Rs2StreamKind::Depth, None, 0, 480, Rs2Format::Z16, 15)
+        .enable_stream(
+            Rs2StreamKind::Depth,
+            None,
+            None,
+            Some(480),
+            Rs2Format::Z16,
+            15,
+        )
         .unwrap();
 
     // Change pipeline's type from InactivePipeline -> ActivePipeline
@@ -93,7 +101,7 @@ fn main() -> Result<()> {
 
     // process frames
     let timeout = Duration::from_millis(1000);
-    let mut now = std::time::SystemTime::now();
+    let mut fps = FpsCounter::new(30);
 
     loop {
         let frames = pipeline.wait(Some(timeout)).unwrap();
@@ -122,13 +130,9 @@ fn main() -> Result<()> {
             highgui::imshow(depth_aligned_window, &colorized_depth).unwrap();
 
             // Print out our Hz for the loop
-            print!(
-                "\rCurrent hz: {:.4?} fps",
-                1000.0 / (now.elapsed().unwrap().as_millis()) as f64
-            );
+            print!("\rCurrent hz: {:.4?} fps", fps.tick());
             use std::io::Write;
             stdout().flush().unwrap();
-            now = std::time::SystemTime::now();
 
             if highgui::wait_key(2)? != -1 {
                 break;
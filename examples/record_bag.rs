@@ -78,20 +78,27 @@ fn main() -> Result<()> {
         .enable_device_from_serial(devices[0].info(Rs2CameraInfo::SerialNumber).unwrap())?
         .enable_record_to_file(bag_file)?
         .disable_all_streams()?
-        .enable_stream(Rs2StreamKind::Color, None, 1920, 1080, Rs2Format::Yuyv, 15)?
+        .enable_stream(
+            Rs2StreamKind::Color,
+            None,
+            Some(1920),
+            Some(1080),
+            Rs2Format::Yuyv,
+            15,
+        )?
         .enable_stream(
             Rs2StreamKind::Infrared,
             Some(1),
-            1280,
-            800,
+            Some(1280),
+            Some(800),
             Rs2Format::Y16,
             15,
         )?
         .enable_stream(
             Rs2StreamKind::Infrared,
             Some(2),
-            1280,
-            800,
+            Some(1280),
+            Some(800),
             Rs2Format::Y16,
             15,
         )?;
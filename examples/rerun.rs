@@ -4,6 +4,7 @@ use anyhow::{ensure, Result};
 use realsense_rust::{
     config::Config,
     context::Context,
+    fps::FpsCounter,
     frame::{ColorFrame, DepthFrame, PixelKind},
     kind::{Rs2CameraInfo, Rs2Format, Rs2StreamKind},
     pipeline::InactivePipeline,
@@ -14,14 +15,8 @@ use std::{collections::HashSet, convert::TryFrom, io::stdout, time::Duration};
 fn color_image_to_rgb_image(color_frame: &ColorFrame) -> rerun::external::image::DynamicImage {
     let width = color_frame.width() as u32;
     let height = color_frame.height() as u32;
-    let pixels = color_frame
-        .iter()
-        .flat_map(|rs| match rs {
-            PixelKind::Bgr8 { b, g, r } => [*r, *g, *b],
-            _ => panic!("We got our types wrong!"),
-        })
-        .collect::<Vec<u8>>();
-    rerun::external::image::RgbImage::from_vec(width as u32, height as u32, pixels)
+    let pixels = color_frame.to_rgb8().expect("We got our types wrong!");
+    rerun::external::image::RgbImage::from_raw(width, height, pixels)
         .unwrap()
         .into()
 }
@@ -61,8 +56,22 @@ fn main() -> Result<()> {
     config
         .enable_device_from_serial(devices[0].info(Rs2CameraInfo::SerialNumber).unwrap())?
         .disable_all_streams()?
-        .enable_stream(Rs2StreamKind::Color, None, 640, 0, Rs2Format::Bgr8, 15)?
-        .enable_stream(Rs2StreamKind::Depth, None, 0, 480, Rs2Format::Z16, 15)
+        .enable_stream(
+            Rs2StreamKind::Color,
+            None,
+            Some(640),
+            None,
+            Rs2Format::Bgr8,
+            15,
+        )?
+        .enable_stream(
+            Rs2StreamKind::Depth,
+            None,
+            None,
+            Some(480),
+            Rs2Format::Z16,
+            15,
+        )
         .unwrap();
 
     // Change pipeline's type from InactivePipeline -> ActivePipeline
@@ -73,29 +82,22 @@ fn main() -> Result<()> {
 
     // process frames
     let timeout = Duration::from_millis(1000);
-    let mut now = std::time::SystemTime::now();
+    let mut fps = FpsCounter::new(30);
 
     loop {
         let frames = pipeline.wait(Some(timeout)).unwrap();
-        let color_frames = frames.frames_of_type::<ColorFrame>();
-        let depth_frames = frames.frames_of_type::<DepthFrame>();
-
-        if !color_frames.is_empty() {
-            let image = color_image_to_rgb_image(&color_frames[0]);
+        if let Some(color_frame) = frames.first_of_type::<ColorFrame>() {
+            let image = color_image_to_rgb_image(&color_frame);
             rec.log("color image", &rerun::Image::try_from(image)?)?;
         }
-        if !depth_frames.is_empty() {
-            let image = depth_to_rgb_image(&depth_frames[0]);
+        if let Some(depth_frame) = frames.first_of_type::<DepthFrame>() {
+            let image = depth_to_rgb_image(&depth_frame);
             rec.log("depth image", &rerun::Image::try_from(image)?)?;
         }
 
         // Print out our Hz for the loop
-        print!(
-            "\rCurrent hz: {:.4?} fps",
-            1000.0 / (now.elapsed().unwrap().as_millis()) as f64
-        );
+        print!("\rCurrent hz: {:.4?} fps", fps.tick());
         use std::io::Write;
         stdout().flush().unwrap();
-        now = std::time::SystemTime::now();
     }
 }
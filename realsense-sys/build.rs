@@ -5,7 +5,8 @@
 //! 1. Find librealsense on the current system
 //! 2. If the buildtime-bindgen feature is enabled, we run bindgen over the librealsense headers
 //!    and generate bindings.rs
-//! 3. Link this crate to the librealsense2 library.
+//! 3. Compile the C shims in `shim/` against the librealsense headers.
+//! 4. Link this crate to the librealsense2 library.
 //!
 //! NOTE: If we build in "docs-only" mode (the feature), then this script does nothing, since we
 //! don't need to link to librealsense2 or regenerate bindings to build the docs.
@@ -98,6 +99,14 @@ fn main() {
             .expect("Couldn't write bindings!");
     }
 
+    // compile the shims, which must come before librealsense2 on the link line.
+    println!("cargo:rerun-if-changed=shim");
+    cc::Build::new()
+        .cpp(true)
+        .file("shim/playback.cpp")
+        .includes(&library.include_paths)
+        .compile("realsense_shim");
+
     // link the libraries specified by pkg-config.
     for dir in &library.link_paths {
         println!("cargo:rustc-link-search=native={}", dir.to_str().unwrap());
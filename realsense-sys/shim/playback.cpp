@@ -0,0 +1,67 @@
+// C interface to the playback status callbacks of librealsense2.
+//
+// librealsense2 only accepts playback status callbacks as objects implementing the C++
+// `rs2_playback_status_changed_callback` interface. This wraps a plain function pointer and user
+// data in such an object, so that callbacks can be registered from other languages.
+
+// The C++ interface itself is declared in rs_types.hpp; rs.h only declares the type.
+#include <librealsense2/rs.h>
+#include <librealsense2/hpp/rs_types.hpp>
+
+extern "C" {
+
+// Invoked with the user data whenever the status of the playback changes.
+typedef void (*rs2_playback_status_changed_callback_user_ptr)(rs2_playback_status, void*);
+
+// Invoked with the user data once librealsense2 no longer holds onto the callback.
+typedef void (*rs2_playback_status_changed_release_ptr)(void*);
+
+}
+
+namespace
+{
+    class status_changed_callback : public rs2_playback_status_changed_callback
+    {
+    public:
+        status_changed_callback(rs2_playback_status_changed_callback_user_ptr on_status_changed,
+                                rs2_playback_status_changed_release_ptr on_release,
+                                void* user)
+            : _on_status_changed(on_status_changed), _on_release(on_release), _user(user)
+        {
+        }
+
+        void on_playback_status_changed(rs2_playback_status status) override
+        {
+            _on_status_changed(status, _user);
+        }
+
+        void release() override
+        {
+            _on_release(_user);
+            delete this;
+        }
+
+    private:
+        rs2_playback_status_changed_callback_user_ptr _on_status_changed;
+        rs2_playback_status_changed_release_ptr _on_release;
+        void* _user;
+    };
+}
+
+extern "C" void rs2_set_playback_status_changed_callback(
+    const rs2_device* device,
+    rs2_playback_status_changed_callback_user_ptr on_status_changed,
+    rs2_playback_status_changed_release_ptr on_release,
+    void* user,
+    rs2_error** error)
+{
+    auto callback = new status_changed_callback(on_status_changed, on_release, user);
+    rs2_playback_device_set_status_changed_callback(device, callback, error);
+
+    // librealsense2 only takes ownership of the callback once the device has been validated as a
+    // playback device, so the callback is still ours to release if registration failed.
+    if (error && *error)
+    {
+        callback->release();
+    }
+}
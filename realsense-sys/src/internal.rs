@@ -0,0 +1,280 @@
+//! Declarations from `rs_internal.h` used for software devices.
+//!
+//! `build.rs` does not generate bindings for `rs_internal.h`, which declares far more than software
+//! devices need. The few declarations used are written out here instead, in the same form that
+//! bindgen produces for them, and have to be kept in sync with the header by hand when moving to a
+//! new librealsense2 version. The layout tests catch changes to the structs.
+
+use crate::{
+    rs2_device, rs2_error, rs2_format, rs2_intrinsics, rs2_sensor, rs2_stream, rs2_stream_profile,
+    rs2_time_t, rs2_timestamp_domain,
+};
+
+#[doc = " \\brief All the parameters required to define a video stream."]
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct rs2_video_stream {
+    pub type_: rs2_stream,
+    pub index: ::std::os::raw::c_int,
+    pub uid: ::std::os::raw::c_int,
+    pub width: ::std::os::raw::c_int,
+    pub height: ::std::os::raw::c_int,
+    pub fps: ::std::os::raw::c_int,
+    pub bpp: ::std::os::raw::c_int,
+    pub fmt: rs2_format,
+    pub intrinsics: rs2_intrinsics,
+}
+#[test]
+fn bindgen_test_layout_rs2_video_stream() {
+    const UNINIT: ::std::mem::MaybeUninit<rs2_video_stream> = ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<rs2_video_stream>(),
+        80usize,
+        concat!("Size of: ", stringify!(rs2_video_stream))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<rs2_video_stream>(),
+        4usize,
+        concat!("Alignment of ", stringify!(rs2_video_stream))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).type_) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rs2_video_stream),
+            "::",
+            stringify!(type_)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).index) as usize - ptr as usize },
+        4usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rs2_video_stream),
+            "::",
+            stringify!(index)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).uid) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rs2_video_stream),
+            "::",
+            stringify!(uid)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).width) as usize - ptr as usize },
+        12usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rs2_video_stream),
+            "::",
+            stringify!(width)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).height) as usize - ptr as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rs2_video_stream),
+            "::",
+            stringify!(height)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).fps) as usize - ptr as usize },
+        20usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rs2_video_stream),
+            "::",
+            stringify!(fps)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).bpp) as usize - ptr as usize },
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rs2_video_stream),
+            "::",
+            stringify!(bpp)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).fmt) as usize - ptr as usize },
+        28usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rs2_video_stream),
+            "::",
+            stringify!(fmt)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).intrinsics) as usize - ptr as usize },
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rs2_video_stream),
+            "::",
+            stringify!(intrinsics)
+        )
+    );
+}
+#[doc = " \\brief All the parameters required to define a video frame."]
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct rs2_software_video_frame {
+    pub pixels: *mut ::std::os::raw::c_void,
+    pub deleter: ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>,
+    pub stride: ::std::os::raw::c_int,
+    pub bpp: ::std::os::raw::c_int,
+    pub timestamp: rs2_time_t,
+    pub domain: rs2_timestamp_domain,
+    pub frame_number: ::std::os::raw::c_int,
+    pub profile: *const rs2_stream_profile,
+    pub depth_units: f32,
+}
+#[test]
+fn bindgen_test_layout_rs2_software_video_frame() {
+    const UNINIT: ::std::mem::MaybeUninit<rs2_software_video_frame> =
+        ::std::mem::MaybeUninit::uninit();
+    let ptr = UNINIT.as_ptr();
+    assert_eq!(
+        ::std::mem::size_of::<rs2_software_video_frame>(),
+        56usize,
+        concat!("Size of: ", stringify!(rs2_software_video_frame))
+    );
+    assert_eq!(
+        ::std::mem::align_of::<rs2_software_video_frame>(),
+        8usize,
+        concat!("Alignment of ", stringify!(rs2_software_video_frame))
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).pixels) as usize - ptr as usize },
+        0usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rs2_software_video_frame),
+            "::",
+            stringify!(pixels)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).deleter) as usize - ptr as usize },
+        8usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rs2_software_video_frame),
+            "::",
+            stringify!(deleter)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).stride) as usize - ptr as usize },
+        16usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rs2_software_video_frame),
+            "::",
+            stringify!(stride)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).bpp) as usize - ptr as usize },
+        20usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rs2_software_video_frame),
+            "::",
+            stringify!(bpp)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).timestamp) as usize - ptr as usize },
+        24usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rs2_software_video_frame),
+            "::",
+            stringify!(timestamp)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).domain) as usize - ptr as usize },
+        32usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rs2_software_video_frame),
+            "::",
+            stringify!(domain)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).frame_number) as usize - ptr as usize },
+        36usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rs2_software_video_frame),
+            "::",
+            stringify!(frame_number)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).profile) as usize - ptr as usize },
+        40usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rs2_software_video_frame),
+            "::",
+            stringify!(profile)
+        )
+    );
+    assert_eq!(
+        unsafe { ::std::ptr::addr_of!((*ptr).depth_units) as usize - ptr as usize },
+        48usize,
+        concat!(
+            "Offset of field: ",
+            stringify!(rs2_software_video_frame),
+            "::",
+            stringify!(depth_units)
+        )
+    );
+}
+extern "C" {
+    #[doc = " Create software device to enable use librealsense logic without getting data from backend\n but inject the data from outside\n \\param[out] error     If non-null, receives any error that occurs during this call, otherwise, errors are ignored\n \\return               software device object, should be released by rs2_delete_device"]
+    pub fn rs2_create_software_device(error: *mut *mut rs2_error) -> *mut rs2_device;
+}
+extern "C" {
+    #[doc = " Add sensor to the software device\n \\param[in] dev             the software device\n \\param[in] sensor_name     the name of the sensor\n \\param[out] error          If non-null, receives any error that occurs during this call, otherwise, errors are ignored\n \\return                    software sensor object, should be released by rs2_delete_sensor"]
+    pub fn rs2_software_device_add_sensor(
+        dev: *mut rs2_device,
+        sensor_name: *const ::std::os::raw::c_char,
+        error: *mut *mut rs2_error,
+    ) -> *mut rs2_sensor;
+}
+extern "C" {
+    #[doc = " Inject video frame to software sonsor\n \\param[in] sensor the software sensor\n \\param[in] frame  all the frame components\n \\param[out] error If non-null, receives any error that occurs during this call, otherwise, errors are ignored"]
+    pub fn rs2_software_sensor_on_video_frame(
+        sensor: *mut rs2_sensor,
+        frame: rs2_software_video_frame,
+        error: *mut *mut rs2_error,
+    );
+}
+extern "C" {
+    #[doc = " Add video stream to sensor\n \\param[in] sensor       the software sensor\n \\param[in] video_stream all the stream components\n \\param[in] is_default   whether or not the stream should be a default stream for the device\n \\param[out] error       If non-null, receives any error that occurs during this call, otherwise, errors are ignored"]
+    pub fn rs2_software_sensor_add_video_stream_ex(
+        sensor: *mut rs2_sensor,
+        video_stream: rs2_video_stream,
+        is_default: ::std::os::raw::c_int,
+        error: *mut *mut rs2_error,
+    ) -> *mut rs2_stream_profile;
+}
@@ -6,3 +6,8 @@
 #![allow(missing_docs)]
 #![allow(clippy::missing_docs_in_private_items)]
 include!("../bindings/bindings.rs");
+
+mod internal;
+mod shim;
+pub use internal::*;
+pub use shim::*;
@@ -0,0 +1,29 @@
+//! Declarations of the C shims compiled from `shim/` by the build script.
+//!
+//! Some librealsense2 APIs only accept objects implementing a C++ interface. The shims wrap plain
+//! C function pointers and user data in such objects, so that they can be called through FFI.
+
+use crate::{rs2_device, rs2_error, rs2_playback_status};
+
+/// Invoked with the user data whenever the status of the playback changes.
+pub type rs2_playback_status_changed_callback_user_ptr = ::std::option::Option<
+    unsafe extern "C" fn(arg1: rs2_playback_status, arg2: *mut ::std::os::raw::c_void),
+>;
+
+/// Invoked with the user data once librealsense2 no longer holds onto the callback.
+pub type rs2_playback_status_changed_release_ptr =
+    ::std::option::Option<unsafe extern "C" fn(arg1: *mut ::std::os::raw::c_void)>;
+
+extern "C" {
+    /// Register to receive a callback from a playback device upon its status changes.
+    ///
+    /// `on_release` is invoked with `user` once the callback is no longer used, including when
+    /// registration fails, so `user` can be freed there.
+    pub fn rs2_set_playback_status_changed_callback(
+        device: *const rs2_device,
+        on_status_changed: rs2_playback_status_changed_callback_user_ptr,
+        on_release: rs2_playback_status_changed_release_ptr,
+        user: *mut ::std::os::raw::c_void,
+        error: *mut *mut rs2_error,
+    );
+}
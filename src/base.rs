@@ -8,6 +8,27 @@ use std::{ffi::CString, time::Duration};
 /// The default timeout duration in librealsense2
 pub const DEFAULT_TIMEOUT: Duration = Duration::from_millis(sys::RS2_DEFAULT_TIMEOUT as u64);
 
+/// Get the current time of the host clock that librealsense2 uses for timestamps, in milliseconds.
+///
+/// Frames in the [`SystemTime`](crate::kind::Rs2TimestampDomain::SystemTime) and
+/// [`GlobalTime`](crate::kind::Rs2TimestampDomain::GlobalTime) domains are timestamped on this
+/// clock, which is the system clock counted from the Unix epoch. Reading it alongside the clocks of
+/// other sensors makes it possible to map frame timestamps onto them.
+///
+/// Returns `None` if the time cannot be read.
+pub fn host_time() -> Option<f64> {
+    unsafe {
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        let time = sys::rs2_get_time(&mut err);
+        if err.as_ref().is_none() {
+            Some(time)
+        } else {
+            sys::rs2_free_error(err);
+            None
+        }
+    }
+}
+
 /// Helper function for converting a path to a series of `c_char` that can be interpreted as a
 /// sequence of bytes / native path for a given platform..
 pub(crate) fn from_path<P>(path: P) -> anyhow::Result<CString>
@@ -138,6 +159,20 @@ impl Rs2Intrinsics {
             coeffs: self.0.coeffs,
         }
     }
+
+    /// Deproject a pixel at the given distance into a 3D point in the coordinate frame of the
+    /// stream.
+    ///
+    /// `pixel` is the `[x, y]` pixel coordinate and `depth` the distance along the optical axis,
+    /// in meters. The point is returned as `[x, y, z]` in meters, with x pointing right, y down
+    /// and z forward. The distortion model of the intrinsics is taken into account.
+    pub fn deproject(&self, pixel: [f32; 2], depth: f32) -> [f32; 3] {
+        let mut point = [0.0; 3];
+        unsafe {
+            sys::rs2_deproject_pixel_to_point(point.as_mut_ptr(), &self.0, pixel.as_ptr(), depth);
+        }
+        point
+    }
 }
 
 unsafe impl Send for Rs2Intrinsics {}
@@ -174,3 +209,36 @@ pub struct Rs2Roi {
     /// Bottom coordinate of the region of interest.
     pub max_y: i32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deprojection_scales_with_depth() {
+        let intrinsics = Rs2Intrinsics(sys::rs2_intrinsics {
+            width: 640,
+            height: 480,
+            ppx: 320.0,
+            ppy: 240.0,
+            fx: 100.0,
+            fy: 100.0,
+            model: sys::rs2_distortion_RS2_DISTORTION_NONE,
+            coeffs: [0.0; 5],
+        });
+
+        assert_eq!(intrinsics.deproject([320.0, 240.0], 2.0), [0.0, 0.0, 2.0]);
+        assert_eq!(intrinsics.deproject([420.0, 190.0], 2.0), [2.0, -1.0, 2.0]);
+    }
+
+    #[test]
+    fn host_time_is_the_system_clock() {
+        let system_ms = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs_f64()
+            * 1000.0;
+        let host_ms = host_time().unwrap();
+        assert!((host_ms - system_ms).abs() < 1000.0);
+    }
+}
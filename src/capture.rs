@@ -0,0 +1,142 @@
+//! Utility for writing a tabular index of the frames of a capture.
+//!
+//! Datasets recorded from RealSense cameras usually ship the images themselves alongside an index
+//! of when each frame was captured. [`CaptureWriter`] writes that index as CSV, with one row per
+//! frame, so that frames of different streams can be aligned by time afterwards.
+
+use crate::frame::{CompositeFrame, FrameEx};
+use crate::kind::Rs2FrameMetadata;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+/// The header row written by [`CaptureWriter`].
+const HEADER: &str = "set,stream,index,frame_number,timestamp,timestamp_domain,exposure";
+
+/// Writes the metadata of every frame of a capture as CSV.
+///
+/// Every [`CompositeFrame`] passed to [`write`](CaptureWriter::write) is a numbered set, and each
+/// frame in it becomes one row with the columns:
+///
+/// | Column             | Content                                                            |
+/// |--------------------|--------------------------------------------------------------------|
+/// | `set`              | The number of the composite frame the frame came from, from zero.  |
+/// | `stream`           | The stream kind, e.g. `Depth`.                                     |
+/// | `index`            | The stream index, e.g. to tell the two infrared streams apart.     |
+/// | `frame_number`     | The frame number.                                                  |
+/// | `timestamp`        | The timestamp in milliseconds.                                     |
+/// | `timestamp_domain` | The domain of the timestamp, e.g. `Global Time`.                   |
+/// | `exposure`         | The actual exposure in microseconds, if the frame reports it.      |
+///
+/// Columns without a value, e.g. the exposure of a motion frame, are left empty.
+///
+/// ```no_run
+/// use realsense_rust::capture::CaptureWriter;
+/// # fn next_frames() -> Option<realsense_rust::frame::CompositeFrame> { unimplemented!() }
+///
+/// let mut writer = CaptureWriter::create("capture.csv").unwrap();
+/// while let Some(frames) = next_frames() {
+///     writer.write(&frames).unwrap();
+/// }
+/// writer.finish().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct CaptureWriter<W: Write> {
+    /// The destination of the CSV.
+    writer: W,
+    /// The number of the next composite frame.
+    sets: u64,
+    /// The number of rows written, not counting the header.
+    rows: u64,
+}
+
+impl CaptureWriter<BufWriter<File>> {
+    /// Create a writer to the file at `path`, replacing the file if it exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the file cannot be created, or the header cannot be written.
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> CaptureWriter<W> {
+    /// Create a writer to `writer`, and write the header row.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the header cannot be written.
+    pub fn new(mut writer: W) -> io::Result<Self> {
+        writeln!(writer, "{}", HEADER)?;
+        Ok(Self {
+            writer,
+            sets: 0,
+            rows: 0,
+        })
+    }
+
+    /// Write one row for every frame in `frames`.
+    ///
+    /// Frames that do not correspond to any frame type in this crate are skipped, but the set is
+    /// numbered regardless, so that set numbers keep matching the order of the capture.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if a row cannot be written.
+    pub fn write(&mut self, frames: &CompositeFrame) -> io::Result<()> {
+        let set = self.sets;
+        self.sets += 1;
+
+        for frame in frames.any_frames() {
+            let profile = frame.stream_profile();
+            let exposure = frame
+                .metadata(Rs2FrameMetadata::ActualExposure)
+                .map(|exposure| exposure.to_string())
+                .unwrap_or_default();
+            writeln!(
+                self.writer,
+                "{},{},{},{},{},{},{}",
+                set,
+                profile.kind(),
+                profile.index(),
+                frame.frame_number(),
+                frame.timestamp(),
+                frame.timestamp_domain().as_str(),
+                exposure
+            )?;
+            self.rows += 1;
+        }
+        Ok(())
+    }
+
+    /// Get the number of rows written so far, not counting the header.
+    pub fn rows(&self) -> u64 {
+        self.rows
+    }
+
+    /// Flush the writer, and return the underlying destination.
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the writer cannot be flushed.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_writer_writes_only_the_header() {
+        let writer = CaptureWriter::new(Vec::new()).unwrap();
+        assert_eq!(writer.rows(), 0);
+        let csv = String::from_utf8(writer.finish().unwrap()).unwrap();
+        assert_eq!(csv, format!("{}\n", HEADER));
+    }
+}
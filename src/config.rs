@@ -3,13 +3,17 @@
 use crate::{
     base::from_path,
     check_rs2_error,
-    kind::{Rs2Exception, Rs2Format, Rs2StreamKind},
+    context::Context,
+    device::Device,
+    kind::{Rs2CameraInfo, Rs2Exception, Rs2Format, Rs2Option, Rs2StreamKind},
 };
 use anyhow::Result;
 use realsense_sys as sys;
-use std::{convert::TryInto, ffi::CStr, path::Path, ptr::NonNull};
+use std::{collections::HashSet, convert::TryInto, ffi::CStr, path::Path, ptr::NonNull};
 use thiserror::Error;
 
+mod json;
+
 /// Type describing all possible errors that can occur when trying to configure a pipeline.
 #[derive(Error, Debug, PartialEq)]
 pub enum ConfigurationError {
@@ -31,6 +35,119 @@ pub enum ConfigurationError {
     /// Recording to file could not be enabled for the specified device.
     #[error("Could not enable recording to file from device. Type: {0}; Reason: {1}")]
     CouldNotEnableRecordingToFile(Rs2Exception, String),
+    /// No connected device is attached to the requested physical port.
+    #[error("No device found on physical port {0}.")]
+    NoDeviceOnPhysicalPort(String),
+    /// The device on the requested physical port has no serial number to select it by.
+    #[error("The device on physical port {0} has no serial number.")]
+    DeviceHasNoSerialNumber(String),
+    /// The requested stream is not supported by the device.
+    #[error("{requested} not supported; nearest is {}", nearest.as_deref().unwrap_or("none"))]
+    UnsupportedStream {
+        /// Description of the requested stream, e.g. `Color 1280x720@90 RGB8`.
+        requested: String,
+        /// Description of the closest stream that the device supports, if any.
+        nearest: Option<String>,
+    },
+    /// The configuration JSON could not be parsed.
+    #[error("Could not parse configuration JSON. Reason: {0}")]
+    InvalidJson(String),
+    /// The configuration JSON names a stream kind, format or option that does not exist.
+    #[error("Unknown {0} in configuration JSON: {1}")]
+    UnknownJsonName(&'static str, String),
+}
+
+/// Attributes of a stream, as passed to [`Config::enable_stream`].
+///
+/// Zero-valued dimensions and framerates, and [`Rs2Format::Any`], act as wildcards when matching a
+/// request against a supported stream.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct StreamAttributes {
+    /// The stream kind.
+    pub(crate) stream: Rs2StreamKind,
+    /// The image width in pixels, or zero.
+    pub(crate) width: usize,
+    /// The image height in pixels, or zero.
+    pub(crate) height: usize,
+    /// The data format.
+    pub(crate) format: Rs2Format,
+    /// The framerate, or zero.
+    pub(crate) framerate: usize,
+}
+
+/// A stream enabled with [`Config::enable_stream`], as it was requested.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct StreamRequest {
+    /// The requested stream index, if any.
+    pub(crate) index: Option<usize>,
+    /// The requested attributes of the stream.
+    pub(crate) attributes: StreamAttributes,
+}
+
+impl std::fmt::Display for StreamAttributes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} {}x{}@{} {}",
+            self.stream, self.width, self.height, self.framerate, self.format
+        )
+    }
+}
+
+impl StreamAttributes {
+    /// Predicate for whether the supported stream `other` satisfies this request.
+    fn is_satisfied_by(&self, other: &StreamAttributes) -> bool {
+        self.stream == other.stream
+            && (self.width == 0 || self.width == other.width)
+            && (self.height == 0 || self.height == other.height)
+            && (self.format == Rs2Format::Any || self.format == other.format)
+            && (self.framerate == 0 || self.framerate == other.framerate)
+    }
+
+    /// How far the supported stream `other` is from this request.
+    ///
+    /// Sums the differences of every attribute that was requested explicitly.
+    fn distance_to(&self, other: &StreamAttributes) -> usize {
+        let diff = |requested: usize, supported: usize| {
+            if requested == 0 {
+                0
+            } else {
+                requested.abs_diff(supported)
+            }
+        };
+        diff(self.width, other.width)
+            + diff(self.height, other.height)
+            + diff(self.framerate, other.framerate)
+    }
+
+    /// Find the supported stream closest to this request.
+    ///
+    /// Streams with the requested format are preferred over those that would need a different
+    /// format.
+    fn nearest<'a>(&self, supported: &'a [StreamAttributes]) -> Option<&'a StreamAttributes> {
+        let same_kind = supported.iter().filter(|s| s.stream == self.stream);
+        let same_format = same_kind
+            .clone()
+            .filter(|s| self.format == Rs2Format::Any || s.format == self.format);
+
+        same_format
+            .min_by_key(|s| self.distance_to(s))
+            .or_else(|| same_kind.min_by_key(|s| self.distance_to(s)))
+    }
+}
+
+/// The device selected with one of the `Config::enable_device_from_*` methods.
+#[derive(Debug, Clone, PartialEq)]
+enum DeviceSelection {
+    /// A connected device, selected by its serial number.
+    Serial(String),
+    /// A recording, played back from a file.
+    File {
+        /// The path of the recording.
+        path: String,
+        /// Whether playback loops back to the start once it reaches the end.
+        loop_playback: bool,
+    },
 }
 
 /// Type representing the [`Pipeline`](crate::pipeline::InactivePipeline) configuration.
@@ -38,6 +155,21 @@ pub enum ConfigurationError {
 pub struct Config {
     /// A non-null pointer to the underlying librealsense2 configuration.
     config_ptr: NonNull<sys::rs2_config>,
+    /// Options to set on the sensors of the device once the pipeline is started, in the order
+    /// they were recorded.
+    sensor_options: Vec<(Rs2Option, f32)>,
+    /// Whether all streams have been disabled, with no stream enabled since.
+    all_streams_disabled: bool,
+    /// The streams enabled with [`Config::enable_stream`] and not disabled since.
+    requested_streams: Vec<StreamRequest>,
+    /// Whether all streams have been enabled, with no stream enabled or all disabled since.
+    all_streams_enabled: bool,
+    /// The streams disabled since all streams were enabled, by kind and index if any.
+    disabled_streams: Vec<(Rs2StreamKind, Option<usize>)>,
+    /// The device selected for the pipeline, if any.
+    device_selection: Option<DeviceSelection>,
+    /// The path of the file that streams are recorded to, if any.
+    record_to_file: Option<String>,
 }
 
 impl Drop for Config {
@@ -74,6 +206,13 @@ impl Config {
 
             Self {
                 config_ptr: NonNull::new(ptr).unwrap(),
+                sensor_options: Vec::new(),
+                all_streams_disabled: false,
+                requested_streams: Vec::new(),
+                all_streams_enabled: false,
+                disabled_streams: Vec::new(),
+                device_selection: None,
+                record_to_file: None,
             }
         }
     }
@@ -95,9 +234,10 @@ impl Config {
     /// The index is can be optionally provided. If it is not provided, then librealsense2 will
     /// pick the most suitable stream index it can find.
     ///
-    /// If either `width` or `height` (but not both) are zero, librealsense2 will find the most
-    /// appropriate value to match the non-zero one. E.g. if `width` is 640 and `height` is 0, then
-    /// librealsense2 will return 640x480 images (the closest appropriate format).
+    /// The `width` and `height` can likewise be left as `None`, in which case librealsense2 will
+    /// find the most appropriate value to match the other one. E.g. if `width` is `Some(640)` and
+    /// `height` is `None`, then librealsense2 will return 640x480 images (the closest appropriate
+    /// format). A `framerate` of zero lets librealsense2 pick the framerate.
     ///
     /// # Errors
     ///
@@ -111,12 +251,25 @@ impl Config {
         &mut self,
         stream: Rs2StreamKind,
         index: Option<usize>,
-        width: usize,
-        height: usize,
+        width: Option<usize>,
+        height: Option<usize>,
         format: Rs2Format,
         framerate: usize,
     ) -> Result<&mut Self, ConfigurationError> {
+        let request = StreamRequest {
+            index,
+            attributes: StreamAttributes {
+                stream,
+                width: width.unwrap_or(0),
+                height: height.unwrap_or(0),
+                format,
+                framerate,
+            },
+        };
         let index: i32 = if let Some(i) = index { i as i32 } else { -1 };
+        // librealsense2 treats zero as "any" for both dimensions.
+        let width = width.unwrap_or(0);
+        let height = height.unwrap_or(0);
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
             sys::rs2_config_enable_stream(
@@ -133,9 +286,95 @@ impl Config {
             );
             check_rs2_error!(err, ConfigurationError::CouldNotEnableStream)?;
         };
+        self.all_streams_disabled = false;
+        self.all_streams_enabled = false;
+        self.disabled_streams.clear();
+
+        // Like librealsense2, a later request for the same stream replaces an earlier one.
+        self.requested_streams
+            .retain(|r| !(r.attributes.stream == stream && r.index == request.index));
+        self.requested_streams.push(request);
         Ok(self)
     }
 
+    /// Enable the stream of kind `stream`, letting librealsense2 pick all of its attributes.
+    ///
+    /// This is equivalent to calling [`Config::enable_stream`] with no index, width, or height,
+    /// [`Rs2Format::Any`], and a framerate of zero.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigurationError::CouldNotEnableStream`] if any internal exceptions occur while
+    /// making this call.
+    ///
+    pub fn enable_stream_any(
+        &mut self,
+        stream: Rs2StreamKind,
+    ) -> Result<&mut Self, ConfigurationError> {
+        self.enable_stream(stream, None, None, None, Rs2Format::Any, 0)
+    }
+
+    /// Enable the stream of kind `stream`, after checking that `device` supports it.
+    ///
+    /// This behaves like [`Config::enable_stream`], but validates the requested attributes
+    /// against the stream profiles of the device's sensors first. An unsupported combination of
+    /// resolution, format, and framerate is then reported here, alongside the closest combination
+    /// the device does support, rather than when the pipeline is started.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigurationError::UnsupportedStream`] if no stream profile of `device` matches
+    /// the requested attributes.
+    ///
+    /// Returns [`ConfigurationError::CouldNotEnableStream`] if any internal exceptions occur while
+    /// enabling the stream.
+    ///
+    #[allow(clippy::too_many_arguments)]
+    pub fn enable_stream_checked(
+        &mut self,
+        device: &Device,
+        stream: Rs2StreamKind,
+        index: Option<usize>,
+        width: Option<usize>,
+        height: Option<usize>,
+        format: Rs2Format,
+        framerate: usize,
+    ) -> Result<&mut Self, ConfigurationError> {
+        let requested = StreamAttributes {
+            stream,
+            width: width.unwrap_or(0),
+            height: height.unwrap_or(0),
+            format,
+            framerate,
+        };
+
+        let supported: Vec<StreamAttributes> = device
+            .sensors()
+            .iter()
+            .flat_map(|sensor| sensor.stream_profiles())
+            .filter(|profile| index.map_or(true, |i| profile.index() == i))
+            .map(|profile| {
+                let (width, height) = profile.resolution().unwrap_or((0, 0));
+                StreamAttributes {
+                    stream: profile.kind(),
+                    width,
+                    height,
+                    format: profile.format(),
+                    framerate: profile.framerate() as usize,
+                }
+            })
+            .collect();
+
+        if !supported.iter().any(|s| requested.is_satisfied_by(s)) {
+            return Err(ConfigurationError::UnsupportedStream {
+                requested: requested.to_string(),
+                nearest: requested.nearest(&supported).map(|s| s.to_string()),
+            });
+        }
+
+        self.enable_stream(stream, index, width, height, format, framerate)
+    }
+
     /// Enable all device streams explicitly.
     ///
     /// This enables all streams with the default configuration. What this means is that
@@ -155,6 +394,11 @@ impl Config {
             sys::rs2_config_enable_all_stream(self.config_ptr.as_ptr(), &mut err);
             check_rs2_error!(err, ConfigurationError::CouldNotEnableAllStreams)?;
         }
+        // Like librealsense2, enabling all streams discards the streams enabled individually.
+        self.all_streams_disabled = false;
+        self.all_streams_enabled = true;
+        self.disabled_streams.clear();
+        self.requested_streams.clear();
         Ok(self)
     }
 
@@ -180,9 +424,55 @@ impl Config {
             sys::rs2_config_enable_device(self.config_ptr.as_ptr(), serial.as_ptr(), &mut err);
             check_rs2_error!(err, ConfigurationError::CouldNotEnableDevice)?;
         }
+        self.device_selection = Some(DeviceSelection::Serial(
+            serial.to_string_lossy().into_owned(),
+        ));
         Ok(self)
     }
 
+    /// Enable the device attached to a given physical port.
+    ///
+    /// On fixed multi-camera rigs the serial numbers of the devices may not be known ahead of
+    /// time, while the USB ports they are plugged into are. The port of a connected device can be
+    /// obtained from [`Device::physical_port`]; its format is platform specific.
+    ///
+    /// The device is looked up among the devices currently connected to `context`, and then
+    /// selected by its serial number as in [`Config::enable_device_from_serial`].
+    ///
+    /// Returns a mutable reference to self if it succeeds or a configuration error.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigurationError::NoDeviceOnPhysicalPort`] if no connected device is attached
+    /// to `port`.
+    ///
+    /// Returns [`ConfigurationError::DeviceHasNoSerialNumber`] if the serial number of the device
+    /// cannot be read.
+    ///
+    /// Returns [`ConfigurationError::CouldNotEnableDevice`] if the device could not be enabled.
+    ///
+    pub fn enable_device_from_physical_port(
+        &mut self,
+        context: &Context,
+        port: &str,
+    ) -> Result<&mut Self, ConfigurationError> {
+        let devices = context.query_devices(HashSet::new());
+        let device = devices
+            .iter()
+            .find(|device| {
+                device
+                    .physical_port()
+                    .map_or(false, |device_port| device_port.to_str() == Ok(port))
+            })
+            .ok_or_else(|| ConfigurationError::NoDeviceOnPhysicalPort(port.to_owned()))?;
+
+        let serial = device
+            .info(Rs2CameraInfo::SerialNumber)
+            .ok_or_else(|| ConfigurationError::DeviceHasNoSerialNumber(port.to_owned()))?;
+
+        self.enable_device_from_serial(serial)
+    }
+
     /// Enable device from a file path.
     ///
     /// Enables a virtual "device" whose observations have been recorded to a file. If
@@ -207,6 +497,10 @@ impl Config {
     where
         P: AsRef<Path>,
     {
+        let selection = DeviceSelection::File {
+            path: file.as_ref().to_string_lossy().into_owned(),
+            loop_playback,
+        };
         let path = from_path(file)?;
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
@@ -218,6 +512,7 @@ impl Config {
             );
             check_rs2_error!(err, ConfigurationError::CouldNotEnableDevice)?;
         }
+        self.device_selection = Some(selection);
         Ok(self)
     }
 
@@ -244,6 +539,7 @@ impl Config {
     where
         P: AsRef<Path>,
     {
+        let record_to_file = file.as_ref().to_string_lossy().into_owned();
         let path = from_path(file)?;
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
@@ -254,6 +550,7 @@ impl Config {
             );
             check_rs2_error!(err, ConfigurationError::CouldNotEnableRecordingToFile)?;
         }
+        self.record_to_file = Some(record_to_file);
         Ok(self)
     }
 
@@ -281,6 +578,11 @@ impl Config {
             );
             check_rs2_error!(err, ConfigurationError::CouldNotDisableStream)?;
         }
+        self.requested_streams
+            .retain(|r| !(r.attributes.stream == stream && r.index == Some(index)));
+        if self.all_streams_enabled {
+            self.disabled_streams.push((stream, Some(index)));
+        }
         Ok(self)
     }
 
@@ -308,12 +610,19 @@ impl Config {
             );
             check_rs2_error!(err, ConfigurationError::CouldNotDisableStream)?;
         }
+        self.requested_streams
+            .retain(|r| r.attributes.stream != stream);
+        if self.all_streams_enabled {
+            self.disabled_streams.push((stream, None));
+        }
         Ok(self)
     }
 
     /// Disable all device streams explicitly.
     ///
-    /// This method disables every stream.
+    /// This method disables every stream. At least one stream has to be enabled again afterwards,
+    /// or starting a pipeline with this configuration fails with
+    /// [`PipelineActivationError::NoStreamsEnabled`](crate::pipeline::PipelineActivationError::NoStreamsEnabled).
     ///
     /// Returns a mutable reference to self or a configuration error.
     ///
@@ -328,9 +637,48 @@ impl Config {
             sys::rs2_config_disable_all_streams(self.config_ptr.as_ptr(), &mut err);
             check_rs2_error!(err, ConfigurationError::CouldNotDisableAllStreams)?;
         }
+        self.all_streams_disabled = true;
+        self.all_streams_enabled = false;
+        self.disabled_streams.clear();
+        self.requested_streams.clear();
         Ok(self)
     }
 
+    /// Set `option` to `value` on the sensors of the device once the pipeline is started.
+    ///
+    /// This lets the full starting state of a device be described in the configuration, e.g. the
+    /// exposure and laser power, rather than by fetching the sensors and setting options after the
+    /// pipeline has started. The option is set on every sensor of the device that supports it.
+    /// Options are applied in the order they are recorded, and recording the same option again
+    /// replaces its value.
+    ///
+    /// The value is not validated here; if it cannot be applied,
+    /// [`InactivePipeline::start`](crate::pipeline::InactivePipeline::start) returns
+    /// [`PipelineActivationError::CouldNotSetSensorOption`](crate::pipeline::PipelineActivationError::CouldNotSetSensorOption).
+    ///
+    pub fn set_sensor_option(&mut self, option: Rs2Option, value: f32) -> &mut Self {
+        match self.sensor_options.iter_mut().find(|(o, _)| *o == option) {
+            Some(entry) => entry.1 = value,
+            None => self.sensor_options.push((option, value)),
+        }
+        self
+    }
+
+    /// Get the options to set on the sensors of the device once the pipeline is started.
+    pub(crate) fn sensor_options(&self) -> &[(Rs2Option, f32)] {
+        &self.sensor_options
+    }
+
+    /// Get the streams enabled with [`Config::enable_stream`] and not disabled since.
+    pub(crate) fn requested_streams(&self) -> &[StreamRequest] {
+        &self.requested_streams
+    }
+
+    /// Predicate for whether all streams have been disabled, and no stream enabled since.
+    pub(crate) fn has_no_streams(&self) -> bool {
+        self.all_streams_disabled
+    }
+
     /// Get the underlying low-level pointer to the configuration object.
     ///
     /// # Safety
@@ -343,3 +691,93 @@ impl Config {
         self.config_ptr
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Shorthand for constructing stream attributes in tests.
+    fn attributes(
+        stream: Rs2StreamKind,
+        width: usize,
+        height: usize,
+        format: Rs2Format,
+        framerate: usize,
+    ) -> StreamAttributes {
+        StreamAttributes {
+            stream,
+            width,
+            height,
+            format,
+            framerate,
+        }
+    }
+
+    #[test]
+    fn wildcards_match_any_supported_stream() {
+        let requested = attributes(Rs2StreamKind::Color, 0, 0, Rs2Format::Any, 0);
+        let supported = attributes(Rs2StreamKind::Color, 1280, 720, Rs2Format::Rgb8, 30);
+        assert!(requested.is_satisfied_by(&supported));
+
+        let depth = attributes(Rs2StreamKind::Depth, 1280, 720, Rs2Format::Z16, 30);
+        assert!(!requested.is_satisfied_by(&depth));
+    }
+
+    #[test]
+    fn nearest_prefers_matching_resolution_and_format() {
+        let requested = attributes(Rs2StreamKind::Color, 1280, 720, Rs2Format::Rgb8, 90);
+        let supported = [
+            attributes(Rs2StreamKind::Color, 848, 480, Rs2Format::Rgb8, 90),
+            attributes(Rs2StreamKind::Color, 1280, 720, Rs2Format::Yuyv, 90),
+            attributes(Rs2StreamKind::Color, 1280, 720, Rs2Format::Rgb8, 30),
+            attributes(Rs2StreamKind::Depth, 1280, 720, Rs2Format::Z16, 90),
+        ];
+
+        assert!(!supported.iter().any(|s| requested.is_satisfied_by(s)));
+        assert_eq!(requested.nearest(&supported), Some(&supported[2]));
+    }
+
+    #[test]
+    fn unsupported_stream_error_names_nearest_stream() {
+        let requested = attributes(Rs2StreamKind::Color, 1280, 720, Rs2Format::Rgb8, 90);
+        let nearest = attributes(Rs2StreamKind::Color, 1280, 720, Rs2Format::Rgb8, 30);
+        let err = ConfigurationError::UnsupportedStream {
+            requested: requested.to_string(),
+            nearest: Some(nearest.to_string()),
+        };
+
+        assert_eq!(
+            err.to_string(),
+            "Color 1280x720@90 RGB8 not supported; nearest is Color 1280x720@30 RGB8"
+        );
+    }
+
+    #[test]
+    fn sensor_options_keep_order_and_replace_values() {
+        let mut config = Config::new();
+        config
+            .set_sensor_option(Rs2Option::Exposure, 8000.0)
+            .set_sensor_option(Rs2Option::LaserPower, 150.0)
+            .set_sensor_option(Rs2Option::Exposure, 4000.0);
+
+        assert_eq!(
+            config.sensor_options(),
+            &[
+                (Rs2Option::Exposure, 4000.0),
+                (Rs2Option::LaserPower, 150.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn disabling_all_streams_is_undone_by_enabling_one() {
+        let mut config = Config::new();
+        assert!(!config.has_no_streams());
+
+        config.disable_all_streams().unwrap();
+        assert!(config.has_no_streams());
+
+        config.enable_stream_any(Rs2StreamKind::Depth).unwrap();
+        assert!(!config.has_no_streams());
+    }
+}
@@ -0,0 +1,343 @@
+//! Serialization of a [`Config`] to and from JSON.
+//!
+//! A `Config` only wraps an opaque librealsense2 pointer, so what is serialized is the sequence of
+//! calls that produced it, as recorded by the `Config` itself. Restoring a configuration replays
+//! those calls on a new `Config`. Stream kinds, formats and options are written by their
+//! librealsense2 names (e.g. `"Depth"`, `"Z16"`, `"Exposure"`), so that configuration files can be
+//! read and edited by hand.
+
+use super::{Config, ConfigurationError, DeviceSelection};
+use crate::kind::{Rs2Format, Rs2Option, Rs2StreamKind};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::ffi::CString;
+
+/// The serialized form of a [`Config`].
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct ConfigJson {
+    /// The device selected for the pipeline, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    device: Option<DeviceJson>,
+    /// The path of the file that streams are recorded to, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    record_to_file: Option<String>,
+    /// Whether all streams are enabled.
+    #[serde(default)]
+    all_streams: bool,
+    /// Whether all streams are disabled.
+    #[serde(default)]
+    no_streams: bool,
+    /// The streams disabled after enabling all streams, in order.
+    #[serde(default)]
+    disabled_streams: Vec<DisabledStreamJson>,
+    /// The streams enabled individually.
+    #[serde(default)]
+    streams: Vec<StreamJson>,
+    /// The options set on the sensors once the pipeline is started, in order.
+    #[serde(default)]
+    sensor_options: Vec<SensorOptionJson>,
+}
+
+/// The serialized form of a device selection.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+enum DeviceJson {
+    /// A connected device, selected by its serial number.
+    Serial(String),
+    /// A recording, played back from a file.
+    File {
+        /// The path of the recording.
+        path: String,
+        /// Whether playback loops back to the start once it reaches the end.
+        #[serde(default)]
+        loop_playback: bool,
+    },
+}
+
+/// The serialized form of a stream enabled with [`Config::enable_stream`].
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct StreamJson {
+    /// The stream kind, by name.
+    stream: String,
+    /// The stream index, or `None` to let librealsense2 pick.
+    #[serde(default)]
+    index: Option<usize>,
+    /// The image width in pixels, or `None` to let librealsense2 pick.
+    #[serde(default)]
+    width: Option<usize>,
+    /// The image height in pixels, or `None` to let librealsense2 pick.
+    #[serde(default)]
+    height: Option<usize>,
+    /// The data format, by name, or `None` to let librealsense2 pick.
+    #[serde(default)]
+    format: Option<String>,
+    /// The framerate, where zero lets librealsense2 pick.
+    #[serde(default)]
+    framerate: usize,
+}
+
+/// The serialized form of a stream disabled with [`Config::disable_stream`] or
+/// [`Config::disable_stream_at_index`].
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct DisabledStreamJson {
+    /// The stream kind, by name.
+    stream: String,
+    /// The stream index, or `None` to disable every stream of the kind.
+    #[serde(default)]
+    index: Option<usize>,
+}
+
+/// The serialized form of an option recorded with [`Config::set_sensor_option`].
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct SensorOptionJson {
+    /// The option, by name.
+    option: String,
+    /// The value to set the option to.
+    value: f32,
+}
+
+/// Look up `name` with `from_name`, or fail with an error naming `what` was looked up.
+fn parse_name<T>(
+    what: &'static str,
+    name: &str,
+    from_name: fn(&str) -> Option<T>,
+) -> Result<T, ConfigurationError> {
+    from_name(name).ok_or_else(|| ConfigurationError::UnknownJsonName(what, name.to_owned()))
+}
+
+impl Config {
+    /// Serialize this configuration to JSON.
+    ///
+    /// The JSON captures the selected device, the file streams are recorded to, the enabled and
+    /// disabled streams and the recorded sensor options, and can be turned back into an equivalent
+    /// configuration with [`Config::from_json`]. A device selected with
+    /// [`enable_device_from_physical_port`](Config::enable_device_from_physical_port) is written
+    /// as the serial number of the device that was found on the port.
+    ///
+    /// Paths that are not valid UTF-8 are written with the invalid sequences replaced.
+    pub fn to_json(&self) -> String {
+        let device = self.device_selection.as_ref().map(|device| match device {
+            DeviceSelection::Serial(serial) => DeviceJson::Serial(serial.clone()),
+            DeviceSelection::File {
+                path,
+                loop_playback,
+            } => DeviceJson::File {
+                path: path.clone(),
+                loop_playback: *loop_playback,
+            },
+        });
+
+        let streams = self
+            .requested_streams
+            .iter()
+            .map(|request| {
+                let attributes = &request.attributes;
+                StreamJson {
+                    stream: attributes.stream.to_str().to_owned(),
+                    index: request.index,
+                    width: Some(attributes.width).filter(|&width| width != 0),
+                    height: Some(attributes.height).filter(|&height| height != 0),
+                    format: Some(attributes.format)
+                        .filter(|&format| format != Rs2Format::Any)
+                        .map(|format| format.to_str().to_owned()),
+                    framerate: attributes.framerate,
+                }
+            })
+            .collect();
+
+        let disabled_streams = self
+            .disabled_streams
+            .iter()
+            .map(|&(stream, index)| DisabledStreamJson {
+                stream: stream.to_str().to_owned(),
+                index,
+            })
+            .collect();
+
+        let sensor_options = self
+            .sensor_options
+            .iter()
+            .map(|&(option, value)| SensorOptionJson {
+                option: option.to_str().to_owned(),
+                value,
+            })
+            .collect();
+
+        let json = ConfigJson {
+            device,
+            record_to_file: self.record_to_file.clone(),
+            all_streams: self.all_streams_enabled,
+            no_streams: self.all_streams_disabled,
+            disabled_streams,
+            streams,
+            sensor_options,
+        };
+        // Every field serializes to a string, number or boolean, so this cannot fail.
+        serde_json::to_string_pretty(&json).unwrap()
+    }
+
+    /// Construct a configuration from JSON written by [`Config::to_json`].
+    ///
+    /// Every field is optional, so a hand-written file only needs to list what it configures,
+    /// e.g.
+    ///
+    /// ```json
+    /// {
+    ///     "device": { "serial": "012345678901" },
+    ///     "streams": [
+    ///         { "stream": "Depth", "width": 640, "height": 480, "format": "Z16", "framerate": 30 }
+    ///     ],
+    ///     "sensor_options": [{ "option": "Exposure", "value": 8500.0 }]
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ConfigurationError::InvalidJson`] if `json` is not a valid configuration.
+    ///
+    /// Returns [`ConfigurationError::UnknownJsonName`] if `json` names a stream kind, format or
+    /// option that librealsense2 does not know.
+    ///
+    /// Otherwise returns any error that the corresponding `Config` methods return when applying
+    /// the configuration, e.g. if a recording cannot be opened.
+    pub fn from_json(json: &str) -> Result<Config> {
+        let json: ConfigJson = serde_json::from_str(json)
+            .map_err(|e| ConfigurationError::InvalidJson(e.to_string()))?;
+
+        let mut config = Config::new();
+        match json.device {
+            Some(DeviceJson::Serial(serial)) => {
+                let serial = CString::new(serial)
+                    .map_err(|e| ConfigurationError::InvalidJson(e.to_string()))?;
+                config.enable_device_from_serial(&serial)?;
+            }
+            Some(DeviceJson::File {
+                path,
+                loop_playback,
+            }) => {
+                config.enable_device_from_file(path, loop_playback)?;
+            }
+            None => {}
+        }
+        if let Some(path) = json.record_to_file {
+            config.enable_record_to_file(path)?;
+        }
+        if json.all_streams {
+            config.enable_all_streams()?;
+        }
+        if json.no_streams {
+            config.disable_all_streams()?;
+        }
+
+        for stream in json.disabled_streams {
+            let kind = parse_name("stream kind", &stream.stream, Rs2StreamKind::from_name)?;
+            match stream.index {
+                Some(index) => config.disable_stream_at_index(kind, index)?,
+                None => config.disable_stream(kind)?,
+            };
+        }
+
+        for stream in json.streams {
+            let kind = parse_name("stream kind", &stream.stream, Rs2StreamKind::from_name)?;
+            let format = match stream.format {
+                Some(format) => parse_name("format", &format, Rs2Format::from_name)?,
+                None => Rs2Format::Any,
+            };
+            config.enable_stream(
+                kind,
+                stream.index,
+                stream.width,
+                stream.height,
+                format,
+                stream.framerate,
+            )?;
+        }
+
+        for option in json.sensor_options {
+            let kind = parse_name("option", &option.option, Rs2Option::from_name)?;
+            config.set_sensor_option(kind, option.value);
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_round_trips_through_json() {
+        let mut config = Config::new();
+        config
+            .enable_stream(
+                Rs2StreamKind::Depth,
+                None,
+                Some(640),
+                Some(480),
+                Rs2Format::Z16,
+                30,
+            )
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Color, Some(0), None, None, Rs2Format::Any, 0)
+            .unwrap()
+            .set_sensor_option(Rs2Option::Exposure, 8500.0);
+
+        let json = config.to_json();
+        let restored = Config::from_json(&json).unwrap();
+        assert_eq!(restored.requested_streams(), config.requested_streams());
+        assert_eq!(restored.sensor_options(), config.sensor_options());
+        assert_eq!(restored.to_json(), json);
+    }
+
+    #[test]
+    fn disabled_streams_round_trip_through_json() {
+        let mut config = Config::new();
+        config
+            .enable_all_streams()
+            .unwrap()
+            .disable_stream(Rs2StreamKind::Color)
+            .unwrap()
+            .disable_stream_at_index(Rs2StreamKind::Infrared, 2)
+            .unwrap();
+
+        let json = config.to_json();
+        let restored = Config::from_json(&json).unwrap();
+        assert!(restored.all_streams_enabled);
+        assert_eq!(
+            restored.disabled_streams,
+            vec![
+                (Rs2StreamKind::Color, None),
+                (Rs2StreamKind::Infrared, Some(2))
+            ]
+        );
+        assert_eq!(restored.to_json(), json);
+    }
+
+    #[test]
+    fn disabling_all_streams_round_trips_through_json() {
+        let mut config = Config::new();
+        config.disable_all_streams().unwrap();
+
+        let json = config.to_json();
+        let restored = Config::from_json(&json).unwrap();
+        assert!(restored.has_no_streams());
+        assert_eq!(restored.to_json(), json);
+    }
+
+    #[test]
+    fn unknown_names_are_rejected() {
+        let err = Config::from_json(r#"{ "streams": [{ "stream": "Sonar" }] }"#).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ConfigurationError>(),
+            Some(&ConfigurationError::UnknownJsonName(
+                "stream kind",
+                "Sonar".to_owned()
+            ))
+        );
+        assert!(Config::from_json(r#"{ "streams": 3 }"#).is_err());
+    }
+}
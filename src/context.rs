@@ -2,22 +2,42 @@
 
 use crate::{
     base::from_path,
+    base::Rs2Intrinsics,
     check_rs2_error,
+    config::Config,
     device::Device,
     device_hub::DeviceHub,
-    kind::{Rs2Exception, Rs2ProductLine},
+    frame::{ColorFrame, DepthFrame, FrameEx},
+    kind::{Rs2CameraInfo, Rs2Exception, Rs2Format, Rs2ProductLine, Rs2StreamKind},
+    pipeline::{ActivePipeline, InactivePipeline},
 };
 use anyhow::Result;
 use num_traits::ToPrimitive;
 use realsense_sys as sys;
-use std::{collections::HashSet, convert::From, path::Path, ptr::NonNull};
+use std::{
+    collections::HashSet,
+    convert::{From, TryFrom},
+    ffi::CString,
+    os::raw::c_void,
+    path::Path,
+    ptr::NonNull,
+    sync::{Arc, Condvar, Mutex},
+    time::{Duration, Instant},
+};
 use thiserror::Error;
 
 /// Type describing a RealSense context, used by the rest of the API.
+///
+/// A context can be shared between threads, e.g. in an [`Arc`](std::sync::Arc), so that each
+/// thread can build its own pipeline from the same context. librealsense2 recommends a single
+/// context per process over one context per thread.
 #[derive(Debug)]
 pub struct Context {
     /// A non-null pointer to the underlying librealsense context.
     context_ptr: NonNull<sys::rs2_context>,
+    /// Held while a [`Context::reset_and_reacquire`] is waiting for device changes, since
+    /// librealsense2 only delivers them to one callback per context.
+    devices_changed_lock: Mutex<()>,
 }
 
 /// An error type describing failure to construct a context.
@@ -40,6 +60,105 @@ pub struct CouldNotAddDeviceError(pub Rs2Exception, pub String);
 #[error("Could not remove device from file. Type: {0}; Reason: {1}")]
 pub struct CouldNotRemoveDeviceError(pub Rs2Exception, pub String);
 
+/// An error type describing failure to find exactly one connected device.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum SingleDeviceError {
+    /// No devices are connected.
+    #[error("No devices found.")]
+    NoDevicesFound,
+    /// More than one device is connected, so the device to use is ambiguous.
+    #[error("Multiple devices found ({0}); expected exactly one.")]
+    MultipleDevicesFound(usize),
+}
+
+/// An error type describing failure to capture an RGBD snapshot.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CaptureError {
+    /// Frames can only be aligned to the color or depth stream of the snapshot.
+    #[error("Cannot align the snapshot to the {0:?} stream; align to Color or Depth.")]
+    UnsupportedAlignment(Rs2StreamKind),
+    /// The aligned frames did not include a frame of the given stream.
+    #[error("The captured frames did not include a {0:?} frame.")]
+    MissingFrame(Rs2StreamKind),
+}
+
+/// An error type describing failure to reset a device and reacquire it afterwards.
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ReacquireError {
+    /// No connected device has the given serial number.
+    #[error("No connected device has the serial number {0}.")]
+    NoDeviceWithSerial(String),
+    /// The device did not disconnect and reconnect within the timeout.
+    #[error("Device with serial number {serial} did not reconnect within {timeout:?}.")]
+    TimedOut {
+        /// The serial number of the device that was reset.
+        serial: String,
+        /// How long the device was waited for.
+        timeout: Duration,
+    },
+    /// Could not register for notifications of devices being connected and disconnected.
+    #[error("Could not watch for device changes. Type: {0}; Reason: {1}")]
+    CouldNotWatchDevices(Rs2Exception, String),
+}
+
+/// The device changes seen by [`Context::reset_and_reacquire`] while waiting for a device.
+#[derive(Default)]
+struct ReacquireState {
+    /// Whether the device has been removed since it was reset.
+    disconnected: bool,
+    /// How many times devices have been added or removed, to tell when to look again.
+    changes: u64,
+}
+
+/// The device that [`Context::reset_and_reacquire`] is waiting for, shared with the devices
+/// changed callback.
+struct ReacquireWatch {
+    /// The device as it was before the reset.
+    device: Device,
+    /// The device changes seen so far.
+    state: Mutex<ReacquireState>,
+    /// Notified whenever devices are added or removed.
+    changed: Condvar,
+}
+
+/// Record a device change from librealsense2 for the [`ReacquireWatch`] at `user`.
+unsafe extern "C" fn on_devices_changed(
+    removed: *mut sys::rs2_device_list,
+    added: *mut sys::rs2_device_list,
+    user: *mut c_void,
+) {
+    let watch = &*(user as *const ReacquireWatch);
+
+    let mut err = std::ptr::null_mut::<sys::rs2_error>();
+    let was_removed =
+        sys::rs2_device_list_contains(removed, watch.device.get_raw().as_ptr(), &mut err);
+    if err.as_ref().is_some() {
+        sys::rs2_free_error(err);
+    }
+
+    let mut state = watch.state.lock().unwrap_or_else(|e| e.into_inner());
+    state.disconnected |= was_removed != 0;
+    state.changes += 1;
+    watch.changed.notify_all();
+    drop(state);
+
+    ignore_devices_changed(removed, added, std::ptr::null_mut());
+}
+
+/// Release the device lists of a device change without looking at them.
+unsafe extern "C" fn ignore_devices_changed(
+    removed: *mut sys::rs2_device_list,
+    added: *mut sys::rs2_device_list,
+    _user: *mut c_void,
+) {
+    // The lists are handed over to the callback, which has to delete them.
+    sys::rs2_delete_device_list(removed);
+    sys::rs2_delete_device_list(added);
+}
+
+/// How long [`Context::capture_rgbd`] waits for each set of frames.
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(5);
+
 impl Drop for Context {
     fn drop(&mut self) {
         unsafe { sys::rs2_delete_context(self.context_ptr.as_ptr()) }
@@ -48,6 +167,10 @@ impl Drop for Context {
 
 unsafe impl Send for Context {}
 
+// librealsense2 guards the internal state of a context, so methods taking `&Context` can be
+// called from several threads at once. Methods that add or remove devices take `&mut self`.
+unsafe impl Sync for Context {}
+
 impl Context {
     /// Construct a new context.
     ///
@@ -63,6 +186,7 @@ impl Context {
 
             Ok(Self {
                 context_ptr: NonNull::new(ptr).unwrap(),
+                devices_changed_lock: Mutex::new(()),
             })
         }
     }
@@ -131,6 +255,200 @@ impl Context {
         devices
     }
 
+    /// Get the devices connected to the host through the given USB controller or hub.
+    ///
+    /// On hosts with several USB controllers, this restricts device discovery to the cameras
+    /// plugged into one of them. Devices are selected by their [physical
+    /// port](Device::physical_port), which includes the path of the controller and any hubs the
+    /// device is attached through; every device whose port starts with `port_prefix` is returned.
+    /// The format of the port is platform specific: on Linux it is a sysfs path such as
+    /// `/sys/devices/pci0000:00/0000:00:14.0/usb2/2-1/2-1:1.0/video4linux/video0`, so a prefix of
+    /// `/sys/devices/pci0000:00/0000:00:14.0/` selects the devices on the controller at PCI
+    /// address `0000:00:14.0`.
+    ///
+    /// To pin a pipeline to one of the returned devices, use
+    /// [`InactivePipeline::for_device`](crate::pipeline::InactivePipeline::for_device) or
+    /// [`Config::enable_device_from_serial`].
+    ///
+    /// The backend that librealsense2 uses to talk to devices is chosen when librealsense2 is
+    /// built, and cannot be selected through its C API, so it is not configurable here.
+    pub fn query_devices_on_port(
+        &self,
+        product_mask: HashSet<Rs2ProductLine>,
+        port_prefix: &str,
+    ) -> Vec<Device> {
+        self.query_devices(product_mask)
+            .into_iter()
+            .filter(|device| {
+                device.physical_port().map_or(false, |port| {
+                    port.to_bytes().starts_with(port_prefix.as_bytes())
+                })
+            })
+            .collect()
+    }
+
+    /// Get the one device connected to the host.
+    ///
+    /// This is a shorthand for the common single-camera case, in place of calling
+    /// [`Context::query_devices`] and taking the first device.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SingleDeviceError::NoDevicesFound`] if no devices are connected.
+    ///
+    /// Returns [`SingleDeviceError::MultipleDevicesFound`] if more than one device is connected.
+    /// Use [`Context::query_devices`] to choose between them.
+    ///
+    pub fn single_device(&self) -> Result<Device, SingleDeviceError> {
+        let mut devices = self.query_devices(HashSet::new());
+
+        match devices.len() {
+            0 => Err(SingleDeviceError::NoDevicesFound),
+            1 => Ok(devices.remove(0)),
+            n => Err(SingleDeviceError::MultipleDevicesFound(n)),
+        }
+    }
+
+    /// Get the connected device with the given serial number, if there is one.
+    fn device_with_serial(&self, serial: &str) -> Option<Device> {
+        self.query_devices(HashSet::new())
+            .into_iter()
+            .find(|device| {
+                device
+                    .info(Rs2CameraInfo::SerialNumber)
+                    .map_or(false, |s| s.to_bytes() == serial.as_bytes())
+            })
+    }
+
+    /// Reset the device with the given serial number, and wait for it to reconnect.
+    ///
+    /// This is the recovery sequence for a device that has stopped responding, e.g. one that is
+    /// stuck on USB: [`Device::hardware_reset`] is issued to the device, which disconnects it from
+    /// the host, and the device is then waited for until it disconnects and enumerates again. The
+    /// returned device is the newly connected one; any handles to the device from before the reset
+    /// are no longer valid.
+    ///
+    /// `timeout` covers the whole sequence, from the reset until the device reconnects. Device
+    /// changes are followed through a devices changed callback on the context, which
+    /// librealsense2 only supports one of at a time, so concurrent calls on the same context wait
+    /// for each other.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ReacquireError::NoDeviceWithSerial`] if no connected device has the given serial.
+    ///
+    /// Returns [`ReacquireError::CouldNotWatchDevices`] if device changes cannot be followed. The
+    /// device is not reset in that case.
+    ///
+    /// Returns [`ReacquireError::TimedOut`] if the device does not disconnect and reconnect within
+    /// `timeout`.
+    ///
+    pub fn reset_and_reacquire(
+        &self,
+        serial: &str,
+        timeout: Duration,
+    ) -> Result<Device, ReacquireError> {
+        let find_device = || {
+            self.device_with_serial(serial)
+                .ok_or_else(|| ReacquireError::NoDeviceWithSerial(serial.to_owned()))
+        };
+        let device = find_device()?;
+        // The reset consumes `device`, so the device is recognized among the removed devices by a
+        // handle of its own.
+        let watched = find_device()?;
+
+        let start = Instant::now();
+        let _guard = self
+            .devices_changed_lock
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let watch = Arc::new(ReacquireWatch {
+            device: watched,
+            state: Mutex::new(ReacquireState::default()),
+            changed: Condvar::new(),
+        });
+        let user = Arc::into_raw(Arc::clone(&watch));
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_set_devices_changed_callback(
+                self.context_ptr.as_ptr(),
+                Some(on_devices_changed),
+                user as *mut c_void,
+                &mut err,
+            );
+            if let Err(e) = check_rs2_error!(err, ReacquireError::CouldNotWatchDevices) {
+                drop(Arc::from_raw(user));
+                return Err(e);
+            }
+        }
+
+        device.hardware_reset();
+        let result = self.wait_for_reconnect(&watch, serial, start, timeout);
+
+        unsafe {
+            // Replacing the callback stops librealsense2 from calling the old one, after which
+            // the watch can be freed. If it cannot be replaced, the watch is leaked instead.
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_set_devices_changed_callback(
+                self.context_ptr.as_ptr(),
+                Some(ignore_devices_changed),
+                std::ptr::null_mut(),
+                &mut err,
+            );
+            if err.as_ref().is_none() {
+                drop(Arc::from_raw(user));
+            } else {
+                sys::rs2_free_error(err);
+            }
+        }
+        result
+    }
+
+    /// Wait until the device of `watch` has disconnected and a device with `serial` is connected.
+    ///
+    /// The device lingers in the device list for a moment after the reset, so it has to be seen
+    /// disconnecting first, or the stale device would be returned. The disconnection is recorded
+    /// by the devices changed callback, so it is not missed however quickly the device returns.
+    fn wait_for_reconnect(
+        &self,
+        watch: &ReacquireWatch,
+        serial: &str,
+        start: Instant,
+        timeout: Duration,
+    ) -> Result<Device, ReacquireError> {
+        let mut state = watch.state.lock().unwrap_or_else(|e| e.into_inner());
+        loop {
+            if state.disconnected {
+                let changes = state.changes;
+                drop(state);
+                if let Some(device) = self.device_with_serial(serial) {
+                    return Ok(device);
+                }
+                state = watch.state.lock().unwrap_or_else(|e| e.into_inner());
+                if state.changes != changes {
+                    continue;
+                }
+            }
+
+            let remaining = match timeout.checked_sub(start.elapsed()) {
+                Some(remaining) => remaining,
+                None => {
+                    return Err(ReacquireError::TimedOut {
+                        serial: serial.to_owned(),
+                        timeout,
+                    })
+                }
+            };
+            let changes = state.changes;
+            state = watch
+                .changed
+                .wait_timeout_while(state, remaining, |state| state.changes == changes)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+
     /// Create a new device and add it to the context.
     ///
     /// This adds a "device" at a particular file on the system to the RealSense context. Returns a
@@ -183,6 +501,97 @@ impl Context {
         }
     }
 
+    /// Create a pipeline from the context and start it with the provided config.
+    ///
+    /// This is a shorthand for the common single-camera case, equivalent to calling
+    /// [`InactivePipeline::try_from`] followed by [`InactivePipeline::start`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PipelineConstructionError`](crate::pipeline::PipelineConstructionError) if the
+    /// pipeline cannot be created from the context.
+    ///
+    /// Returns any error that [`InactivePipeline::start`] can return, e.g.
+    /// [`PipelineActivationError`](crate::pipeline::PipelineActivationError) if the config cannot
+    /// be resolved or the pipeline fails to start.
+    ///
+    pub fn start_default_pipeline(&self, config: Config) -> Result<ActivePipeline> {
+        let pipeline = InactivePipeline::try_from(self)?;
+        pipeline.start(Some(config))
+    }
+
+    /// Capture a single pair of color and depth frames, aligned to the stream of kind `align_to`.
+    ///
+    /// This starts a pipeline streaming color and depth from the device with the given `serial`,
+    /// or from any device if `serial` is `None`. The first `warmup` sets of frames are discarded so
+    /// that auto-exposure can settle, and the next set is aligned and returned alongside the
+    /// intrinsics of the stream it was aligned to. The pipeline is stopped before returning.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CaptureError::UnsupportedAlignment`] if `align_to` is neither
+    /// [`Rs2StreamKind::Color`] nor [`Rs2StreamKind::Depth`].
+    ///
+    /// Returns [`NulError`](std::ffi::NulError) if `serial` contains a null character.
+    ///
+    /// Returns any error that [`Context::start_default_pipeline`] can return, e.g. if no device
+    /// with the given serial is connected or it cannot stream color and depth.
+    ///
+    /// Returns [`FrameWaitError`](crate::pipeline::FrameWaitError) or
+    /// [`ProcessFrameError`](crate::processing_blocks::errors::ProcessFrameError) if frames
+    /// cannot be received or aligned.
+    ///
+    /// Returns [`CaptureError::MissingFrame`] if the aligned frames lack a color or depth frame.
+    ///
+    /// Returns [`DataError`](crate::stream_profile::DataError) if the intrinsics cannot be read.
+    ///
+    pub fn capture_rgbd(
+        &self,
+        serial: Option<&str>,
+        align_to: Rs2StreamKind,
+        warmup: usize,
+    ) -> Result<(ColorFrame, DepthFrame, Rs2Intrinsics)> {
+        if align_to != Rs2StreamKind::Color && align_to != Rs2StreamKind::Depth {
+            return Err(CaptureError::UnsupportedAlignment(align_to).into());
+        }
+
+        let mut config = Config::new();
+        if let Some(serial) = serial {
+            config.enable_device_from_serial(&CString::new(serial)?)?;
+        }
+        config
+            .enable_stream(Rs2StreamKind::Color, None, None, None, Rs2Format::Rgb8, 0)?
+            .enable_stream(Rs2StreamKind::Depth, None, None, None, Rs2Format::Z16, 0)?;
+
+        let mut pipeline = self.start_default_pipeline(config)?;
+        for _ in 0..warmup {
+            pipeline.wait(Some(CAPTURE_TIMEOUT))?;
+        }
+
+        let frames = pipeline
+            .frames_aligned_to(align_to, CAPTURE_TIMEOUT)?
+            .next()
+            .unwrap()?;
+        pipeline.stop();
+
+        let color = frames
+            .frames_of_type::<ColorFrame>()
+            .pop()
+            .ok_or(CaptureError::MissingFrame(Rs2StreamKind::Color))?;
+        let depth = frames
+            .frames_of_type::<DepthFrame>()
+            .pop()
+            .ok_or(CaptureError::MissingFrame(Rs2StreamKind::Depth))?;
+
+        let intrinsics = if align_to == Rs2StreamKind::Color {
+            color.stream_profile().intrinsics()?
+        } else {
+            depth.stream_profile().intrinsics()?
+        };
+
+        Ok((color, depth, intrinsics))
+    }
+
     /// Get the underlying low-level pointer to the context object.
     ///
     /// # Safety
@@ -195,3 +604,40 @@ impl Context {
         self.context_ptr
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn context_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Context>();
+    }
+
+    #[test]
+    fn capture_rejects_unsupported_alignment() {
+        let context = Context::new().unwrap();
+        let err = context
+            .capture_rgbd(None, Rs2StreamKind::Gyro, 0)
+            .unwrap_err();
+
+        assert_eq!(
+            err.downcast_ref::<CaptureError>(),
+            Some(&CaptureError::UnsupportedAlignment(Rs2StreamKind::Gyro))
+        );
+    }
+
+    #[test]
+    fn reacquire_rejects_unknown_serial() {
+        let context = Context::new().unwrap();
+        let err = context
+            .reset_and_reacquire("not-a-serial-number", Duration::from_millis(10))
+            .unwrap_err();
+
+        assert_eq!(
+            err,
+            ReacquireError::NoDeviceWithSerial("not-a-serial-number".to_owned())
+        );
+    }
+}
@@ -6,19 +6,28 @@
 //! See [`sensors`](crate::sensor) for more info.
 
 use crate::{
+    base::Rs2Extrinsics,
     check_rs2_error,
-    kind::{Rs2CameraInfo, Rs2Exception},
-    sensor::Sensor,
+    kind::{
+        OptionSetError, Rs2CameraInfo, Rs2Exception, Rs2Extension, Rs2Option, Rs2ProductLine,
+        Rs2StreamKind,
+    },
+    sensor::{ColorSensor, DepthSensor, MotionSensor, PoseSensor, Sensor},
+    stream_profile::StreamProfile,
 };
 use anyhow::Result;
 use realsense_sys as sys;
 use std::{
-    convert::{From, TryInto},
+    convert::{From, TryFrom, TryInto},
     ffi::CStr,
     ptr::NonNull,
 };
 use thiserror::Error;
 
+mod playback;
+
+pub use playback::{PlaybackDevice, PlaybackError};
+
 /// Enumeration of possible errors that can occur during device construction
 #[derive(Error, Debug)]
 pub enum DeviceConstructionError {
@@ -30,6 +39,15 @@ pub enum DeviceConstructionError {
     CouldNotGetDeviceFromDeviceList(Rs2Exception, String),
 }
 
+/// Enumeration of possible errors that can occur when looking up the extrinsics between the
+/// streams of a device.
+#[derive(Error, Debug, PartialEq)]
+pub enum DeviceExtrinsicsError {
+    /// The device has no sensor that streams the given kind of stream.
+    #[error("Device has no stream of kind {0}.")]
+    NoStreamOfKind(Rs2StreamKind),
+}
+
 /// A type representing a RealSense device.
 ///
 /// A device in librealsense2 corresponds to a physical unit that connects to your computer
@@ -133,6 +151,124 @@ impl Device {
         }
     }
 
+    /// Enable or disable global time on every sensor of the device that supports it.
+    ///
+    /// With global time enabled, librealsense2 continuously correlates the hardware clock of the
+    /// device with the host clock, and timestamps frames in the
+    /// [`GlobalTime`](crate::kind::Rs2TimestampDomain::GlobalTime) domain: hardware timestamps
+    /// mapped onto the host clock of [`host_time`](crate::base::host_time). This is what makes
+    /// frames comparable with the timestamps of other sensors on the same host, e.g. a lidar or an
+    /// external IMU. See [`FrameEx::host_clock_offset`](crate::frame::FrameEx::host_clock_offset)
+    /// for frames that are not in that domain.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error that [`Sensor::set_option`] returns for a sensor that supports
+    /// [`Rs2Option::GlobalTimeEnabled`]. Sensors that do not support it are skipped.
+    pub fn set_global_time_enabled(&self, enabled: bool) -> Result<(), OptionSetError> {
+        let val = if enabled { 1.0 } else { 0.0 };
+        for mut sensor in self.sensors() {
+            if sensor.supports_option(Rs2Option::GlobalTimeEnabled) {
+                sensor.set_option(Rs2Option::GlobalTimeEnabled, val)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the extrinsics from the depth stream of the device to its IMU.
+    ///
+    /// The returned transform maps points in the coordinate frame of the depth stream to the
+    /// coordinate frame of the IMU, which is what visual-inertial odometry needs, e.g. on a D435i.
+    /// The gyroscope and accelerometer of D400 devices are on one chip and share a coordinate
+    /// frame, so the gyroscope stream is used if there is one, and the accelerometer otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DeviceExtrinsicsError::NoStreamOfKind`] if the device has no depth stream, or no
+    /// IMU.
+    ///
+    /// Returns [`DataError::CouldNotGetExtrinsics`](crate::stream_profile::DataError) if the
+    /// extrinsics cannot be read from the device.
+    pub fn depth_to_imu_extrinsics(&self) -> Result<Rs2Extrinsics> {
+        let depth = self
+            .stream_profile_of_kind(Rs2StreamKind::Depth)
+            .ok_or(DeviceExtrinsicsError::NoStreamOfKind(Rs2StreamKind::Depth))?;
+        let imu = self
+            .stream_profile_of_kind(Rs2StreamKind::Gyro)
+            .or_else(|| self.stream_profile_of_kind(Rs2StreamKind::Accel))
+            .ok_or(DeviceExtrinsicsError::NoStreamOfKind(Rs2StreamKind::Gyro))?;
+        Ok(depth.extrinsics(&imu)?)
+    }
+
+    /// Get any stream profile of the given kind from the sensors of the device.
+    ///
+    /// Extrinsics are a property of a stream rather than of a profile, so which profile of the
+    /// stream is returned does not matter for them.
+    fn stream_profile_of_kind(&self, kind: Rs2StreamKind) -> Option<StreamProfile> {
+        self.sensors().into_iter().find_map(|sensor| {
+            sensor
+                .stream_profiles()
+                .into_iter()
+                .find(|profile| profile.kind() == kind)
+        })
+    }
+
+    /// Get the first sensor of the device that converts to the typed sensor `S`.
+    fn typed_sensor<S>(&self) -> Option<S>
+    where
+        S: TryFrom<Sensor, Error = Sensor>,
+    {
+        self.sensors()
+            .into_iter()
+            .find_map(|sensor| S::try_from(sensor).ok())
+    }
+
+    /// Get the color sensor of the device, or `None` if it has none.
+    pub fn color_sensor(&self) -> Option<ColorSensor> {
+        self.typed_sensor()
+    }
+
+    /// Get the depth sensor of the device, or `None` if it has none.
+    ///
+    /// On L500 devices the depth sensor can be converted further with
+    /// [`L500DepthSensor::try_from`](crate::sensor::L500DepthSensor) applied to
+    /// [`DepthSensor::into_inner`].
+    pub fn depth_sensor(&self) -> Option<DepthSensor> {
+        self.typed_sensor()
+    }
+
+    /// Get the motion sensor (IMU) of the device, or `None` if it has none.
+    pub fn motion_sensor(&self) -> Option<MotionSensor> {
+        self.typed_sensor()
+    }
+
+    /// Get the pose sensor of the device, or `None` if it has none.
+    pub fn pose_sensor(&self) -> Option<PoseSensor> {
+        self.typed_sensor()
+    }
+
+    /// Predicate for determining if this device extends to the provided extension.
+    ///
+    /// e.g. a device streaming from a recorded file extends to [`Rs2Extension::Playback`].
+    pub fn is_extendable_to(&self, extension: Rs2Extension) -> bool {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let is_extendable = sys::rs2_is_device_extendable_to(
+                self.device_ptr.as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (extension as i32).try_into().unwrap(),
+                &mut err,
+            );
+
+            if err.as_ref().is_none() {
+                is_extendable != 0
+            } else {
+                sys::rs2_free_error(err);
+                false
+            }
+        }
+    }
+
     /// Takes ownership of the device and forces a hardware reset on the device.
     ///
     /// Ownership of the device is taken as the underlying state can no longer be safely retained
@@ -182,6 +318,34 @@ impl Device {
         }
     }
 
+    /// Gets the physical port that the device is attached to.
+    ///
+    /// This is a shorthand for [`Device::info`] with [`Rs2CameraInfo::PhysicalPort`]. The format of
+    /// the value is platform specific (e.g. a sysfs path on Linux), but it is stable for a given
+    /// port, so it can be used to select a device with
+    /// [`Config::enable_device_from_physical_port`](crate::config::Config::enable_device_from_physical_port).
+    ///
+    /// Returns `None` if the device does not report its physical port.
+    ///
+    pub fn physical_port(&self) -> Option<&CStr> {
+        self.info(Rs2CameraInfo::PhysicalPort)
+    }
+
+    /// Gets the product line that the device belongs to.
+    ///
+    /// This is read from the [`Rs2CameraInfo::ProductLine`] info of the device, and is a more
+    /// reliable way to branch on the camera family than matching on the device name. Use
+    /// [`Rs2ProductLine::contains`] to test against groupings such as
+    /// [`Rs2ProductLine::Depth`] or [`Rs2ProductLine::TRACKING`].
+    ///
+    /// Returns `None` if the device does not report a product line, or reports one that is not
+    /// known to this crate.
+    ///
+    pub fn product_line(&self) -> Option<Rs2ProductLine> {
+        let product_line = self.info(Rs2CameraInfo::ProductLine)?;
+        Rs2ProductLine::from_info(product_line.to_str().ok()?)
+    }
+
     /// Predicate for checking if `camera_info` is supported for this device.
     ///
     /// Returns true iff the device has a value associated with the `camera_info` key.
@@ -0,0 +1,238 @@
+//! Type for a device that streams frames from a recorded file.
+//!
+//! This wraps a [`Device`] so that playback-specific APIs, such as querying whether the end of
+//! the file has been reached, can be used.
+
+use super::Device;
+use crate::{
+    check_rs2_error,
+    kind::{PlaybackStatus, Rs2Exception, Rs2Extension, Rs2Option},
+};
+use num_traits::FromPrimitive;
+use realsense_sys as sys;
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    os::raw::c_void,
+    panic::{self, AssertUnwindSafe},
+    sync::Mutex,
+};
+use thiserror::Error;
+
+/// Type describing errors that can occur when controlling a playback device.
+///
+/// Follows the standard pattern of errors where the enum variant describes what the low-level code
+/// was attempting to do while the string carried alongside describes the underlying error message
+/// from any C++ exceptions that occur.
+#[derive(Error, Debug)]
+pub enum PlaybackError {
+    /// Could not switch the playback between real-time and non-real-time.
+    #[error("Could not set real time playback. Type: {0}; Reason: {1}")]
+    CouldNotSetRealTime(Rs2Exception, String),
+    /// Could not register a callback for changes to the playback status.
+    #[error("Could not set playback status changed callback. Type: {0}; Reason: {1}")]
+    CouldNotSetStatusChangedCallback(Rs2Exception, String),
+}
+
+/// The user callback invoked when the status of a playback device changes.
+///
+/// librealsense2 invokes callbacks from its reading thread, so access is synchronized.
+type StatusChangedFn = Mutex<Box<dyn FnMut(PlaybackStatus) + Send>>;
+
+/// Forward a status change from librealsense2 to the user callback.
+unsafe extern "C" fn on_playback_status_changed(
+    status: sys::rs2_playback_status,
+    user: *mut c_void,
+) {
+    let status = PlaybackStatus::from_i32(status as i32).unwrap_or(PlaybackStatus::Unknown);
+
+    // Unwinding into librealsense2 is undefined behaviour, so a panicking callback is contained
+    // here.
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+        if let Ok(mut callback) = (*(user as *const StatusChangedFn)).lock() {
+            callback(status);
+        }
+    }));
+}
+
+/// Free a user callback once librealsense2 no longer holds onto it.
+unsafe extern "C" fn release_status_changed(user: *mut c_void) {
+    drop(Box::from_raw(user as *mut StatusChangedFn));
+}
+
+/// A device that streams frames from a recorded file (e.g. a `.bag` file).
+///
+/// Constructed from a [`Device`] that extends to [`Rs2Extension::Playback`]. All of the generic
+/// device APIs remain available through [`device`](PlaybackDevice::device).
+pub struct PlaybackDevice {
+    /// The underlying device.
+    device: Device,
+}
+
+impl TryFrom<Device> for PlaybackDevice {
+    type Error = Device;
+
+    /// Attempt to construct a playback device from a generic device.
+    ///
+    /// # Errors
+    ///
+    /// Returns the device back if it does not extend to [`Rs2Extension::Playback`].
+    fn try_from(device: Device) -> Result<Self, Self::Error> {
+        if device.is_extendable_to(Rs2Extension::Playback) {
+            Ok(Self { device })
+        } else {
+            Err(device)
+        }
+    }
+}
+
+impl PlaybackDevice {
+    /// Get a reference to the underlying device.
+    pub fn device(&self) -> &Device {
+        &self.device
+    }
+
+    /// Consume the playback device, returning the underlying device.
+    pub fn into_inner(self) -> Device {
+        self.device
+    }
+
+    /// Get the sensor options that were active when the file was recorded.
+    ///
+    /// Recordings store the options of every sensor alongside the frames, and the sensors of a
+    /// playback device report those values. Reading them back makes it possible to reproduce the
+    /// live configuration offline, e.g. to apply the same post-processing to a capture.
+    ///
+    /// The options of every sensor are merged into one map. Where more than one sensor recorded the
+    /// same option (e.g. [`Rs2Option::Exposure`] on both the depth and the color sensor), the value
+    /// of the first sensor in [`Device::sensors`] is kept; use [`Sensor::get_option`] on the
+    /// sensors of [`device`](PlaybackDevice::device) to tell them apart. Options that cannot be
+    /// read are omitted.
+    ///
+    /// [`Sensor::get_option`]: crate::sensor::Sensor::get_option
+    pub fn recorded_options(&self) -> HashMap<Rs2Option, f32> {
+        let mut options = HashMap::new();
+        for sensor in self.device.sensors() {
+            let supported = (0..sys::rs2_option_RS2_OPTION_COUNT as i32)
+                .filter_map(Rs2Option::from_i32)
+                .filter(|&option| sensor.supports_option(option));
+            for option in supported {
+                if let Some(value) = sensor.get_option(option) {
+                    options.entry(option).or_insert(value);
+                }
+            }
+        }
+        options
+    }
+
+    /// Get the current status of the playback.
+    ///
+    /// Once every frame has been read from a file that is not looped, the status becomes
+    /// [`PlaybackStatus::Stopped`].
+    ///
+    /// Returns [`PlaybackStatus::Unknown`] if the status cannot be read.
+    pub fn status(&self) -> PlaybackStatus {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let status = sys::rs2_playback_device_get_current_status(
+                self.device.get_raw().as_ptr(),
+                &mut err,
+            );
+
+            if err.as_ref().is_none() {
+                PlaybackStatus::from_i32(status as i32).unwrap_or(PlaybackStatus::Unknown)
+            } else {
+                sys::rs2_free_error(err);
+                PlaybackStatus::Unknown
+            }
+        }
+    }
+
+    /// Register a callback to be invoked whenever the status of the playback changes.
+    ///
+    /// This is the most direct way to detect that the end of a file has been reached: the status
+    /// changes to [`PlaybackStatus::Stopped`], whereas waiting on a pipeline just starts timing
+    /// out, which cannot be told apart from a stall.
+    ///
+    /// The callback is invoked from the thread reading the file, so any heavy processing in it
+    /// delays reading and may cause frames to be dropped. Callbacks cannot be unregistered; every
+    /// registered callback is kept until the device is dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PlaybackError::CouldNotSetStatusChangedCallback`] if the callback cannot be
+    /// registered.
+    pub fn set_status_changed_callback<F>(&mut self, callback: F) -> Result<(), PlaybackError>
+    where
+        F: FnMut(PlaybackStatus) + Send + 'static,
+    {
+        let callback: Box<StatusChangedFn> = Box::new(Mutex::new(Box::new(callback)));
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+
+            // Ownership passes to librealsense2, which frees the callback through
+            // `release_status_changed`, also if registration fails.
+            sys::rs2_set_playback_status_changed_callback(
+                self.device.get_raw().as_ptr(),
+                Some(on_playback_status_changed),
+                Some(release_status_changed),
+                Box::into_raw(callback) as *mut c_void,
+                &mut err,
+            );
+            check_rs2_error!(err, PlaybackError::CouldNotSetStatusChangedCallback)?;
+            Ok(())
+        }
+    }
+
+    /// Set whether frames are played back at the rate they were recorded at.
+    ///
+    /// In real-time playback, frames that are not consumed in time are dropped, just as they
+    /// would be from a live camera. Disable real-time playback to process every recorded frame,
+    /// at whatever rate the consumer keeps up with.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PlaybackError::CouldNotSetRealTime`] if the playback mode cannot be changed.
+    pub fn set_real_time(&mut self, real_time: bool) -> Result<(), PlaybackError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_playback_device_set_real_time(
+                self.device.get_raw().as_ptr(),
+                real_time as i32,
+                &mut err,
+            );
+            check_rs2_error!(err, PlaybackError::CouldNotSetRealTime)?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn status_callback_forwards_status_until_released() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&received);
+
+        let callback: Box<StatusChangedFn> = Box::new(Mutex::new(Box::new(move |status| {
+            sink.lock().unwrap().push(status)
+        })));
+        let user = Box::into_raw(callback) as *mut c_void;
+
+        unsafe {
+            on_playback_status_changed(sys::rs2_playback_status_RS2_PLAYBACK_STATUS_PLAYING, user);
+            on_playback_status_changed(sys::rs2_playback_status_RS2_PLAYBACK_STATUS_STOPPED, user);
+            release_status_changed(user);
+        }
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![PlaybackStatus::Playing, PlaybackStatus::Stopped]
+        );
+        assert_eq!(Arc::strong_count(&received), 1);
+    }
+}
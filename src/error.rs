@@ -87,6 +87,10 @@
 /// 2. Performs a null check on that error
 /// 3. Converts that null check to either an `Ok(())` or constructs your custom error type if the
 ///    pointer is non-null.
+/// 4. Populates the [`Rs2Exception`](crate::kind::Rs2Exception) of your error from
+///    `rs2_get_librealsense_exception_type`, and the message from `rs2_get_error_message`. The
+///    exception kind is always the one reported by librealsense2, never a default.
+/// 5. Frees the `rs2_error`, so callers must not free it again.
 ///
 /// Having this expand to a block-expression has some benefits. The chief one is that when you use
 /// this macro the expression returns, which means that if you fail to check the result the
@@ -149,10 +153,10 @@ macro_rules! check_rs2_error {
                     Rs2Exception::from_i32(realsense_exception_type_i32).unwrap_or_else(|| {
                         panic!("Unknown Rs2Exception: {}", realsense_exception_type_i32)
                     }),
+                    // The message comes from an arbitrary C++ exception, so don't assume UTF-8.
                     std::ffi::CStr::from_ptr(sys::rs2_get_error_message(err))
-                        .to_str()
-                        .unwrap()
-                        .to_string(),
+                        .to_string_lossy()
+                        .into_owned(),
                 );
                 sys::rs2_free_error(err);
                 Err(res)
@@ -162,3 +166,76 @@ macro_rules! check_rs2_error {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::kind::Rs2Exception;
+    use realsense_sys as sys;
+
+    /// An error in the form that [`check_rs2_error`] constructs.
+    #[derive(Debug, PartialEq)]
+    struct TestError(Rs2Exception, String);
+
+    // librealsense2 offers no way to construct an `rs2_error` directly, so these tests provoke
+    // real errors from calls that fail without a device attached.
+
+    #[test]
+    fn null_error_is_ok() {
+        unsafe {
+            let err = std::ptr::null_mut::<sys::rs2_error>();
+            assert_eq!(check_rs2_error!(err, TestError), Ok(()));
+        }
+    }
+
+    #[test]
+    fn invalid_value_is_classified_with_its_message() {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let context = sys::rs2_create_context(-1, &mut err);
+            assert!(context.is_null());
+
+            let TestError(kind, message) = check_rs2_error!(err, TestError).unwrap_err();
+            assert_eq!(kind, Rs2Exception::InvalidValue);
+            assert!(!message.is_empty());
+        }
+    }
+
+    #[test]
+    fn wrong_api_call_sequence_is_classified() {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let context = sys::rs2_create_context(sys::RS2_API_VERSION as i32, &mut err);
+            check_rs2_error!(err, TestError).unwrap();
+            let pipeline = sys::rs2_create_pipeline(context, &mut err);
+            check_rs2_error!(err, TestError).unwrap();
+
+            // Polling a pipeline that was never started is a precondition violation.
+            let mut frame = std::ptr::null_mut::<sys::rs2_frame>();
+            sys::rs2_pipeline_poll_for_frames(pipeline, &mut frame, &mut err);
+            let result = check_rs2_error!(err, TestError);
+
+            sys::rs2_delete_pipeline(pipeline);
+            sys::rs2_delete_context(context);
+            assert_eq!(result.unwrap_err().0, Rs2Exception::WrongApiCallSequence);
+        }
+    }
+
+    #[test]
+    fn timeouts_are_reported_without_an_error() {
+        // Waits use the `try_wait` variants so that a timeout never has to be recognized from the
+        // message of an error.
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let queue = sys::rs2_create_frame_queue(1, &mut err);
+            check_rs2_error!(err, TestError).unwrap();
+
+            let mut frame = std::ptr::null_mut::<sys::rs2_frame>();
+            let did_get_frame = sys::rs2_try_wait_for_frame(queue, 1, &mut frame, &mut err);
+            let result = check_rs2_error!(err, TestError);
+
+            sys::rs2_delete_frame_queue(queue);
+            assert_eq!(result, Ok(()));
+            assert_eq!(did_get_frame, 0);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Utility for estimating the rate at which frames are received.
+
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// Estimates frames per second from the time between calls to [`tick`](FpsCounter::tick).
+///
+/// The estimate is averaged over the last `window` intervals, so that it does not jump around with
+/// the jitter of individual frames.
+///
+/// ```no_run
+/// use realsense_rust::fps::FpsCounter;
+///
+/// let mut fps = FpsCounter::new(30);
+/// loop {
+///     // ... wait for and process frames ...
+///     print!("\rCurrent hz: {:.1} fps", fps.tick());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct FpsCounter {
+    /// The most recent intervals between ticks, oldest first.
+    intervals: VecDeque<Duration>,
+    /// The sum of `intervals`.
+    total: Duration,
+    /// The number of intervals averaged over.
+    window: usize,
+    /// When [`tick`](FpsCounter::tick) was last called.
+    last_tick: Option<Instant>,
+}
+
+impl FpsCounter {
+    /// Create a counter averaging over the last `window` intervals.
+    ///
+    /// A `window` of zero is treated as one, i.e. no averaging.
+    pub fn new(window: usize) -> Self {
+        let window = window.max(1);
+        Self {
+            intervals: VecDeque::with_capacity(window),
+            total: Duration::ZERO,
+            window,
+            last_tick: None,
+        }
+    }
+
+    /// Record a frame, and return the current estimate of frames per second.
+    ///
+    /// Returns `0.0` until at least two frames have been recorded.
+    pub fn tick(&mut self) -> f32 {
+        self.tick_at(Instant::now())
+    }
+
+    /// Record a frame at `now`, and return the current estimate of frames per second.
+    fn tick_at(&mut self, now: Instant) -> f32 {
+        if let Some(last_tick) = self.last_tick.replace(now) {
+            let interval = now.saturating_duration_since(last_tick);
+            if self.intervals.len() == self.window {
+                self.total -= self.intervals.pop_front().unwrap();
+            }
+            self.intervals.push_back(interval);
+            self.total += interval;
+        }
+        self.fps()
+    }
+
+    /// Get the current estimate of frames per second without recording a frame.
+    ///
+    /// Returns `0.0` until at least two frames have been recorded.
+    pub fn fps(&self) -> f32 {
+        if self.total.is_zero() {
+            0.0
+        } else {
+            self.intervals.len() as f32 / self.total.as_secs_f32()
+        }
+    }
+
+    /// Forget all recorded frames, e.g. after the stream was paused.
+    pub fn reset(&mut self) {
+        self.intervals.clear();
+        self.total = Duration::ZERO;
+        self.last_tick = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fps_is_averaged_over_the_window() {
+        let start = Instant::now();
+        let mut fps = FpsCounter::new(2);
+
+        assert_eq!(fps.tick_at(start), 0.0);
+        assert_eq!(fps.tick_at(start + Duration::from_millis(100)), 10.0);
+        // Intervals of 100ms and 300ms average to 200ms.
+        assert_eq!(fps.tick_at(start + Duration::from_millis(400)), 5.0);
+        // The 100ms interval falls out of the window, leaving 300ms and 100ms.
+        assert_eq!(fps.tick_at(start + Duration::from_millis(500)), 5.0);
+
+        fps.reset();
+        assert_eq!(fps.fps(), 0.0);
+    }
+}
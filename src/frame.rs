@@ -1,20 +1,34 @@
 //! Defines the frame type including sensor data.
 
+mod any;
+mod colormap;
 mod composite;
 mod image;
+mod monotonic;
 mod motion;
 mod pixel;
 mod points;
+mod pool;
 mod pose;
 mod prelude;
+mod rgbd;
+mod rolling;
+mod smoothing;
 
+pub use self::any::AnyFrame;
+pub use self::colormap::InvalidDepthRangeError;
 pub use self::image::{
     ColorFrame, ConfidenceFrame, DepthFrame, DisparityFrame, FisheyeFrame, ImageFrame,
-    InfraredFrame,
+    InfraredFrame, StereoParameters,
 };
+pub use self::monotonic::{MonotonicClock, TimestampViolation};
 pub use self::motion::{AccelFrame, GyroFrame, MotionFrame};
 pub use self::points::PointsFrame;
-pub use composite::CompositeFrame;
+pub use composite::{CompositeFrame, FrameExtractionError};
 pub use pixel::PixelKind;
+pub use pool::{FramePool, PooledBuffer};
 pub use pose::{Confidence, PoseFrame};
 pub use prelude::{FrameCategory, FrameConstructionError, FrameEx};
+pub use rgbd::{fuse_rgbd, RgbdError, RgbdImage};
+pub use rolling::RollingFrameBuffer;
+pub use smoothing::{smooth_depth, SmoothingError};
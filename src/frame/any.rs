@@ -0,0 +1,268 @@
+//! Type for representing a frame of any kind.
+//!
+//! Frames extracted from a [`CompositeFrame`](super::CompositeFrame) without knowing the streams
+//! it holds can be of any of the frame types in this crate. [`AnyFrame`] wraps whichever type the
+//! frame turned out to be, and implements [`FrameEx`] so that the common functionality can be used
+//! without matching on it first.
+
+use super::{
+    image::{ColorFrame, ConfidenceFrame, DepthFrame, DisparityFrame, FisheyeFrame, InfraredFrame},
+    motion::{AccelFrame, GyroFrame},
+    points::PointsFrame,
+    pose::PoseFrame,
+    prelude::FrameEx,
+};
+use crate::{
+    kind::{Rs2Extension, Rs2FrameMetadata, Rs2StreamKind, Rs2TimestampDomain},
+    sensor::Sensor,
+    stream_profile::StreamProfile,
+};
+use anyhow::Result;
+use num_traits::FromPrimitive;
+use realsense_sys as sys;
+use std::{
+    convert::{TryFrom, TryInto},
+    mem::MaybeUninit,
+    ptr::NonNull,
+};
+
+/// A frame of any of the frame types in this crate.
+#[derive(Debug)]
+pub enum AnyFrame {
+    /// A [`DepthFrame`].
+    Depth(DepthFrame),
+    /// A [`DisparityFrame`].
+    Disparity(DisparityFrame),
+    /// A [`ColorFrame`].
+    Color(ColorFrame),
+    /// An [`InfraredFrame`].
+    Infrared(InfraredFrame),
+    /// A [`FisheyeFrame`].
+    Fisheye(FisheyeFrame),
+    /// A [`ConfidenceFrame`].
+    Confidence(ConfidenceFrame),
+    /// An [`AccelFrame`].
+    Accel(AccelFrame),
+    /// A [`GyroFrame`].
+    Gyro(GyroFrame),
+    /// A [`PoseFrame`].
+    Pose(PoseFrame),
+    /// A [`PointsFrame`].
+    Points(PointsFrame),
+}
+
+/// Apply `$f` to the frame held by any variant of `$frame`.
+macro_rules! with_frame {
+    ($frame:expr, $inner:ident => $f:expr) => {
+        match $frame {
+            AnyFrame::Depth($inner) => $f,
+            AnyFrame::Disparity($inner) => $f,
+            AnyFrame::Color($inner) => $f,
+            AnyFrame::Infrared($inner) => $f,
+            AnyFrame::Fisheye($inner) => $f,
+            AnyFrame::Confidence($inner) => $f,
+            AnyFrame::Accel($inner) => $f,
+            AnyFrame::Gyro($inner) => $f,
+            AnyFrame::Pose($inner) => $f,
+            AnyFrame::Points($inner) => $f,
+        }
+    };
+}
+
+/// The extensions that the frame types in this crate are categorized by.
+///
+/// Disparity frames are also depth frames, and depth frames are also video frames, so the
+/// extensions are ordered from the most to the least specific.
+const FRAME_CATEGORIES: [Rs2Extension; 6] = [
+    Rs2Extension::Points,
+    Rs2Extension::PoseFrame,
+    Rs2Extension::MotionFrame,
+    Rs2Extension::DisparityFrame,
+    Rs2Extension::DepthFrame,
+    Rs2Extension::VideoFrame,
+];
+
+/// Get the most specific extension that a frame is extendable to, given a predicate for whether
+/// it is extendable to an extension.
+///
+/// This is the [`FrameCategory::extension`](super::prelude::FrameCategory::extension) of the frame
+/// type that the frame belongs to, or `None` if it does not belong to any frame type in this crate.
+pub(crate) fn frame_category<P>(mut is_extendable_to: P) -> Option<Rs2Extension>
+where
+    P: FnMut(Rs2Extension) -> bool,
+{
+    FRAME_CATEGORIES
+        .iter()
+        .copied()
+        .find(|extension| is_extendable_to(*extension))
+}
+
+/// Predicate for whether the frame at `frame_ptr` is of the given extension.
+///
+/// # Safety
+///
+/// `frame_ptr` must point to a valid frame for the duration of this call.
+unsafe fn is_extendable_to(frame_ptr: NonNull<sys::rs2_frame>, extension: Rs2Extension) -> bool {
+    let mut err = std::ptr::null_mut::<sys::rs2_error>();
+    let is_extendable = sys::rs2_is_frame_extendable_to(
+        frame_ptr.as_ptr(),
+        #[allow(clippy::useless_conversion)]
+        (extension as i32).try_into().unwrap(),
+        &mut err,
+    );
+
+    if err.as_ref().is_none() {
+        is_extendable != 0
+    } else {
+        sys::rs2_free_error(err);
+        false
+    }
+}
+
+/// Get the stream kind of the frame at `frame_ptr`, if it can be read.
+///
+/// # Safety
+///
+/// `frame_ptr` must point to a valid frame for the duration of this call.
+pub(crate) unsafe fn stream_kind(frame_ptr: NonNull<sys::rs2_frame>) -> Option<Rs2StreamKind> {
+    let mut err = std::ptr::null_mut::<sys::rs2_error>();
+    let profile_ptr = sys::rs2_get_frame_stream_profile(frame_ptr.as_ptr(), &mut err);
+    if err.as_ref().is_some() {
+        sys::rs2_free_error(err);
+        return None;
+    }
+
+    let mut stream = MaybeUninit::uninit();
+    let mut format = MaybeUninit::uninit();
+    let mut index = MaybeUninit::uninit();
+    let mut unique_id = MaybeUninit::uninit();
+    let mut framerate = MaybeUninit::uninit();
+
+    sys::rs2_get_stream_profile_data(
+        profile_ptr,
+        stream.as_mut_ptr(),
+        format.as_mut_ptr(),
+        index.as_mut_ptr(),
+        unique_id.as_mut_ptr(),
+        framerate.as_mut_ptr(),
+        &mut err,
+    );
+    if err.as_ref().is_some() {
+        sys::rs2_free_error(err);
+        return None;
+    }
+
+    Rs2StreamKind::from_i32(stream.assume_init() as i32)
+}
+
+impl AnyFrame {
+    /// Get the resolution of the frame as `(width, height)` in pixels, or `None` if the frame is
+    /// not an image.
+    ///
+    /// Motion, pose and points frames have no pixels, so generic code handling frames of any kind
+    /// should go through this rather than assume every frame has dimensions. Frames with known
+    /// types expose `width` and `height` only where they apply, on
+    /// [`ImageFrame`](super::ImageFrame).
+    pub fn resolution(&self) -> Option<(usize, usize)> {
+        match self {
+            AnyFrame::Depth(f) => Some((f.width(), f.height())),
+            AnyFrame::Disparity(f) => Some((f.width(), f.height())),
+            AnyFrame::Color(f) => Some((f.width(), f.height())),
+            AnyFrame::Infrared(f) => Some((f.width(), f.height())),
+            AnyFrame::Fisheye(f) => Some((f.width(), f.height())),
+            AnyFrame::Confidence(f) => Some((f.width(), f.height())),
+            AnyFrame::Accel(_) | AnyFrame::Gyro(_) | AnyFrame::Pose(_) | AnyFrame::Points(_) => {
+                None
+            }
+        }
+    }
+
+    /// Attempt to construct the frame type matching the frame at `frame_ptr`.
+    ///
+    /// The frame type is determined from the extension and stream kind of the frame. Takes
+    /// ownership of the frame if a frame type could be constructed; otherwise returns `None` and
+    /// the caller remains responsible for releasing the frame.
+    ///
+    /// # Safety
+    ///
+    /// `frame_ptr` must point to a valid frame that the caller owns.
+    pub(crate) unsafe fn from_raw(frame_ptr: NonNull<sys::rs2_frame>) -> Option<Self> {
+        let kind = stream_kind(frame_ptr)?;
+
+        match frame_category(|extension| is_extendable_to(frame_ptr, extension))? {
+            Rs2Extension::Points => PointsFrame::try_from(frame_ptr).ok().map(Self::Points),
+            Rs2Extension::PoseFrame => PoseFrame::try_from(frame_ptr).ok().map(Self::Pose),
+            Rs2Extension::MotionFrame => match kind {
+                Rs2StreamKind::Accel => AccelFrame::try_from(frame_ptr).ok().map(Self::Accel),
+                Rs2StreamKind::Gyro => GyroFrame::try_from(frame_ptr).ok().map(Self::Gyro),
+                _ => None,
+            },
+            Rs2Extension::DisparityFrame => DisparityFrame::try_from(frame_ptr)
+                .ok()
+                .map(Self::Disparity),
+            Rs2Extension::DepthFrame => DepthFrame::try_from(frame_ptr).ok().map(Self::Depth),
+            Rs2Extension::VideoFrame => match kind {
+                Rs2StreamKind::Color => ColorFrame::try_from(frame_ptr).ok().map(Self::Color),
+                Rs2StreamKind::Infrared => {
+                    InfraredFrame::try_from(frame_ptr).ok().map(Self::Infrared)
+                }
+                Rs2StreamKind::Fisheye => FisheyeFrame::try_from(frame_ptr).ok().map(Self::Fisheye),
+                Rs2StreamKind::Confidence => ConfidenceFrame::try_from(frame_ptr)
+                    .ok()
+                    .map(Self::Confidence),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl FrameEx for AnyFrame {
+    fn stream_profile(&self) -> &StreamProfile {
+        with_frame!(self, f => f.stream_profile())
+    }
+
+    fn sensor(&self) -> Result<Sensor> {
+        with_frame!(self, f => f.sensor())
+    }
+
+    fn frame_number(&self) -> u64 {
+        with_frame!(self, f => f.frame_number())
+    }
+
+    fn timestamp(&self) -> f64 {
+        with_frame!(self, f => f.timestamp())
+    }
+
+    fn timestamp_domain(&self) -> Rs2TimestampDomain {
+        with_frame!(self, f => f.timestamp_domain())
+    }
+
+    fn metadata(&self, metadata_kind: Rs2FrameMetadata) -> Option<std::os::raw::c_longlong> {
+        with_frame!(self, f => f.metadata(metadata_kind))
+    }
+
+    fn supports_metadata(&self, metadata_kind: Rs2FrameMetadata) -> bool {
+        with_frame!(self, f => f.supports_metadata(metadata_kind))
+    }
+
+    fn to_owned_bytes(&self) -> Result<(Vec<u8>, StreamProfile)> {
+        with_frame!(self, f => f.to_owned_bytes())
+    }
+
+    fn content_hash(&self) -> u64 {
+        with_frame!(self, f => f.content_hash())
+    }
+
+    fn keep(&self) {
+        with_frame!(self, f => f.keep())
+    }
+
+    fn as_raw(&self) -> *const sys::rs2_frame {
+        with_frame!(self, f => f.as_raw())
+    }
+
+    unsafe fn get_owned_raw(self) -> NonNull<sys::rs2_frame> {
+        with_frame!(self, f => f.get_owned_raw())
+    }
+}
@@ -0,0 +1,161 @@
+//! Built-in color maps for visualizing depth without a colorizer processing block.
+//!
+//! Each [`ColorScheme`] is described by a list of RGB control points, evenly spaced over `[0, 1]`
+//! and linearly interpolated between. The control points approximate the color maps used by the
+//! librealsense2 colorizer, so that images colorized on the host look familiar.
+
+use crate::kind::ColorScheme;
+use thiserror::Error;
+
+/// Occurs when a depth frame is colorized over a range of distances that is empty or inverted.
+#[derive(Error, Debug, PartialEq)]
+#[error(
+    "Cannot colorize distances from {min_m} m to {max_m} m; the maximum must exceed the minimum."
+)]
+pub struct InvalidDepthRangeError {
+    /// The near end of the range, in meters.
+    pub min_m: f32,
+    /// The far end of the range, in meters.
+    pub max_m: f32,
+}
+
+/// Check that distances in `[min_m, max_m]` can be mapped onto a color map.
+///
+/// # Errors
+///
+/// Returns [`InvalidDepthRangeError`] unless `max_m` is greater than `min_m`, which also rejects
+/// NaN.
+pub(crate) fn check_depth_range(min_m: f32, max_m: f32) -> Result<(), InvalidDepthRangeError> {
+    if max_m > min_m {
+        Ok(())
+    } else {
+        Err(InvalidDepthRangeError { min_m, max_m })
+    }
+}
+
+/// Control points of the jet color map.
+const JET: [[u8; 3]; 5] = [
+    [0, 0, 255],
+    [0, 255, 255],
+    [255, 255, 0],
+    [255, 0, 0],
+    [50, 0, 0],
+];
+
+/// Control points of the classic color map.
+const CLASSIC: [[u8; 3]; 6] = [
+    [30, 77, 203],
+    [25, 60, 192],
+    [45, 117, 220],
+    [204, 108, 191],
+    [196, 57, 178],
+    [198, 33, 24],
+];
+
+/// Control points of the white to black color map.
+const WHITE_TO_BLACK: [[u8; 3]; 2] = [[255, 255, 255], [0, 0, 0]];
+
+/// Control points of the black to white color map.
+const BLACK_TO_WHITE: [[u8; 3]; 2] = [[0, 0, 0], [255, 255, 255]];
+
+/// Control points of the bio color map.
+const BIO: [[u8; 3]; 6] = [
+    [0, 0, 204],
+    [0, 153, 255],
+    [255, 255, 153],
+    [170, 255, 146],
+    [0, 153, 0],
+    [102, 51, 0],
+];
+
+/// Control points of the cold color map.
+const COLD: [[u8; 3]; 4] = [[0, 0, 0], [0, 0, 255], [0, 255, 255], [255, 255, 255]];
+
+/// Control points of the warm color map.
+const WARM: [[u8; 3]; 4] = [[0, 0, 0], [255, 0, 0], [255, 255, 0], [255, 255, 255]];
+
+/// Control points of the hue color map.
+const HUE: [[u8; 3]; 7] = [
+    [255, 0, 0],
+    [255, 255, 0],
+    [0, 255, 0],
+    [0, 255, 255],
+    [0, 0, 255],
+    [255, 0, 255],
+    [255, 0, 0],
+];
+
+/// The number of distinct colors used by the quantized color map.
+const QUANTIZED_LEVELS: f32 = 8.0;
+
+/// The number of times the pattern color map repeats over `[0, 1]`.
+const PATTERN_REPEATS: f32 = 10.0;
+
+/// Linearly interpolate between evenly spaced control points at `t` in `[0, 1]`.
+fn interpolate(points: &[[u8; 3]], t: f32) -> [u8; 3] {
+    let position = t * (points.len() - 1) as f32;
+    let i = (position.floor() as usize).min(points.len() - 2);
+    let fraction = position - i as f32;
+
+    let mut rgb = [0u8; 3];
+    for (c, channel) in rgb.iter_mut().enumerate() {
+        let from = points[i][c] as f32;
+        let to = points[i + 1][c] as f32;
+        *channel = (from + (to - from) * fraction).round() as u8;
+    }
+    rgb
+}
+
+/// Map `t` in `[0, 1]` to an RGB color using `scheme`. Values outside of `[0, 1]` are clamped.
+pub(crate) fn color_at(scheme: ColorScheme, t: f32) -> [u8; 3] {
+    let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+
+    match scheme {
+        ColorScheme::Jet => interpolate(&JET, t),
+        ColorScheme::Classic => interpolate(&CLASSIC, t),
+        ColorScheme::WhiteToBlack => interpolate(&WHITE_TO_BLACK, t),
+        ColorScheme::BlackToWhite => interpolate(&BLACK_TO_WHITE, t),
+        ColorScheme::Bio => interpolate(&BIO, t),
+        ColorScheme::Cold => interpolate(&COLD, t),
+        ColorScheme::Warm => interpolate(&WARM, t),
+        ColorScheme::Quantized => {
+            let level = (t * QUANTIZED_LEVELS).floor().min(QUANTIZED_LEVELS - 1.0);
+            interpolate(&JET, level / (QUANTIZED_LEVELS - 1.0))
+        }
+        ColorScheme::Pattern => interpolate(&JET, (t * PATTERN_REPEATS).fract()),
+        ColorScheme::Hue => interpolate(&HUE, t),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoints_match_control_points() {
+        assert_eq!(color_at(ColorScheme::Jet, 0.0), JET[0]);
+        assert_eq!(color_at(ColorScheme::Jet, 1.0), JET[4]);
+        assert_eq!(color_at(ColorScheme::BlackToWhite, 0.5), [128, 128, 128]);
+    }
+
+    #[test]
+    fn out_of_range_values_are_clamped() {
+        assert_eq!(color_at(ColorScheme::Jet, -1.0), JET[0]);
+        assert_eq!(color_at(ColorScheme::Jet, 2.0), JET[4]);
+        assert_eq!(color_at(ColorScheme::Jet, f32::NAN), JET[0]);
+    }
+
+    #[test]
+    fn depth_ranges_must_not_be_empty_or_inverted() {
+        assert_eq!(check_depth_range(0.5, 2.0), Ok(()));
+        assert_eq!(
+            check_depth_range(2.0, 2.0),
+            Err(InvalidDepthRangeError {
+                min_m: 2.0,
+                max_m: 2.0
+            })
+        );
+        assert!(check_depth_range(3.0, 1.0).is_err());
+        assert!(check_depth_range(0.0, f32::NAN).is_err());
+    }
+}
@@ -6,13 +6,36 @@
 //!
 //! This is typically what is delivered from the pipeline.
 
-use super::prelude::FrameCategory;
-use crate::kind::Rs2StreamKind;
+use super::{
+    any::{frame_category, stream_kind, AnyFrame},
+    prelude::{FrameCategory, FrameConstructionError, FrameEx},
+};
+use crate::{
+    check_rs2_error,
+    kind::{Rs2Exception, Rs2Extension, Rs2StreamKind},
+    processing_blocks::{align::Align, errors::ProcessFrameError},
+    stream_profile::StreamProfile,
+};
 use realsense_sys as sys;
 use std::{
     convert::{TryFrom, TryInto},
     ptr::NonNull,
 };
+use thiserror::Error;
+
+/// Occurs when a frame of a given type cannot be retrieved from a [`CompositeFrame`].
+///
+/// `E` is the error returned when converting into the requested frame type, usually a
+/// [`FrameConstructionError`] wrapped in an [`anyhow::Error`].
+#[derive(Error, Debug)]
+pub enum FrameExtractionError<E> {
+    /// librealsense2 failed to extract or classify the frame.
+    #[error("Could not extract frame. Type: {0}; Reason: {1}")]
+    CouldNotExtractFrame(Rs2Exception, String),
+    /// The frame could not be converted into the requested frame type.
+    #[error("Could not convert frame: {0}")]
+    CouldNotConvertFrame(E),
+}
 
 /// Holds the raw data pointer from an RS2 Composite frame type.
 #[derive(Debug)]
@@ -66,6 +89,27 @@ impl CompositeFrame {
         }
     }
 
+    /// Predicate for whether the Composite frame collection holds a frame of the stream `kind`.
+    pub(crate) fn contains_stream(&self, kind: Rs2StreamKind) -> bool {
+        (0..self.count()).any(|i| unsafe {
+            let frame = self.frame.as_ref().unwrap();
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let frame_ptr =
+                sys::rs2_extract_frame(frame.as_ptr(), i as std::os::raw::c_int, &mut err);
+
+            if err.as_ref().is_some() {
+                sys::rs2_free_error(err);
+                return false;
+            }
+
+            let nonnull_frame_ptr = NonNull::new(frame_ptr).unwrap();
+            let frame_kind = stream_kind(nonnull_frame_ptr);
+            sys::rs2_release_frame(nonnull_frame_ptr.as_ptr());
+
+            frame_kind == Some(kind)
+        })
+    }
+
     /// Checks if the Composite frame collection is empty.
     pub fn is_empty(&self) -> bool {
         self.count() == 0
@@ -78,15 +122,178 @@ impl CompositeFrame {
     /// `F` must implement [`FrameCategory`](super::prelude::FrameCategory). Some examples of good
     /// types to use for this are:
     ///
+    /// * [`AccelFrame`](crate::frame::AccelFrame)
     /// * [`ColorFrame`](crate::frame::ColorFrame)
+    /// * [`ConfidenceFrame`](crate::frame::ConfidenceFrame)
     /// * [`DepthFrame`](crate::frame::DepthFrame)
     /// * [`DisparityFrame`](crate::frame::DisparityFrame)
+    /// * [`GyroFrame`](crate::frame::GyroFrame)
     /// * [`PoseFrame`](crate::frame::PoseFrame)
     /// * [`PointsFrame`](crate::frame::PointsFrame)
     ///
+    /// Frames are filtered by both their extension and their stream kind, so e.g. accel and gyro
+    /// frames are told apart even though both are motion frames.
+    ///
     pub fn frames_of_type<F>(&self) -> Vec<F>
+    where
+        F: TryFrom<NonNull<sys::rs2_frame>> + FrameCategory,
+    {
+        (0..self.count())
+            .filter_map(|i| self.try_frame_of_type_at(i).ok().flatten())
+            .collect()
+    }
+
+    /// Retrieves all frames in the Composite frame collection of a given type, alongside the
+    /// errors for frames that could not be retrieved.
+    ///
+    /// This is [`frames_of_type`](CompositeFrame::frames_of_type), except that frames which fail
+    /// to be extracted from the collection, classified, or converted into `F` are reported rather
+    /// than skipped. Frames that are simply of another type are not errors. Use this to find out
+    /// why an expected frame is missing, e.g. because its data was truncated in transfer.
+    pub fn try_frames_of_type<F>(&self) -> (Vec<F>, Vec<FrameExtractionError<F::Error>>)
     where
         F: TryFrom<NonNull<sys::rs2_frame>> + FrameCategory,
+    {
+        let mut frames = Vec::new();
+        let mut errors = Vec::new();
+        for i in 0..self.count() {
+            match self.try_frame_of_type_at(i) {
+                Ok(Some(frame)) => frames.push(frame),
+                Ok(None) => {}
+                Err(e) => errors.push(e),
+            }
+        }
+        (frames, errors)
+    }
+
+    /// Retrieves the first frame in the Composite frame collection of a given type.
+    ///
+    /// This is equivalent to taking the first element of
+    /// [`frames_of_type`](CompositeFrame::frames_of_type), but stops at the first matching frame
+    /// and does not allocate. Composite frames from a pipeline hold one frame per stream, so this
+    /// is all that is needed unless several streams of the same kind are enabled, e.g. both
+    /// infrared streams.
+    ///
+    /// Returns `None` if the collection holds no frame of the given type.
+    pub fn first_of_type<F>(&self) -> Option<F>
+    where
+        F: TryFrom<NonNull<sys::rs2_frame>> + FrameCategory,
+    {
+        (0..self.count()).find_map(|i| self.try_frame_of_type_at(i).ok().flatten())
+    }
+
+    /// Retrieves the frame at `index` in the Composite frame collection, if it is of type `F`.
+    ///
+    /// Frames are filtered by both their extension and their stream kind, as described for
+    /// [`frames_of_type`](CompositeFrame::frames_of_type). Returns `Ok(None)` if the frame is of
+    /// another type, and an error if it could not be extracted, classified or converted.
+    fn try_frame_of_type_at<F>(
+        &self,
+        index: usize,
+    ) -> Result<Option<F>, FrameExtractionError<F::Error>>
+    where
+        F: TryFrom<NonNull<sys::rs2_frame>> + FrameCategory,
+    {
+        unsafe {
+            let frame = match self.frame.as_ref() {
+                Some(frame) => frame,
+                None => return Ok(None),
+            };
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let frame_ptr =
+                sys::rs2_extract_frame(frame.as_ptr(), index as std::os::raw::c_int, &mut err);
+            check_rs2_error!(err, FrameExtractionError::CouldNotExtractFrame)?;
+
+            let nonnull_frame_ptr = NonNull::new(frame_ptr).unwrap();
+
+            // Frames are extendable to every less specific extension too, e.g. disparity frames
+            // to depth frames, so only frames whose most specific extension is that of `F` are
+            // converted.
+            let mut extension_error = None;
+            let category = frame_category(|extension| {
+                if extension_error.is_some() {
+                    return false;
+                }
+                let is_extendable_to = sys::rs2_is_frame_extendable_to(
+                    nonnull_frame_ptr.as_ptr(),
+                    #[allow(clippy::useless_conversion)]
+                    (extension as i32).try_into().unwrap(),
+                    &mut err,
+                );
+                match check_rs2_error!(err, FrameExtractionError::CouldNotExtractFrame) {
+                    Ok(()) => is_extendable_to != 0,
+                    Err(e) => {
+                        extension_error = Some(e);
+                        false
+                    }
+                }
+            });
+            if let Some(e) = extension_error {
+                sys::rs2_release_frame(nonnull_frame_ptr.as_ptr());
+                return Err(e);
+            }
+            if !is_of_category::<F>(category) {
+                sys::rs2_release_frame(nonnull_frame_ptr.as_ptr());
+                return Ok(None);
+            }
+
+            match F::try_from(nonnull_frame_ptr) {
+                // If the kind does not match, dropping `f` releases the frame, so it must not be
+                // released again here.
+                Ok(f) => Ok(if F::kind() == Rs2StreamKind::Any || f.has_correct_kind() {
+                    Some(f)
+                } else {
+                    None
+                }),
+                Err(e) => {
+                    sys::rs2_release_frame(nonnull_frame_ptr.as_ptr());
+                    Err(FrameExtractionError::CouldNotConvertFrame(e))
+                }
+            }
+        }
+    }
+
+    /// Retrieves every frame in the Composite frame collection, alongside its stream profile.
+    ///
+    /// Unlike [`frames_of_type`](Self::frames_of_type), this does not require knowing which
+    /// streams the collection holds, which makes it suitable for generic recorders and loggers.
+    /// Each frame is returned as the [`AnyFrame`] variant matching its extension and stream kind,
+    /// and each stream profile is an owned copy that outlives the frame.
+    ///
+    /// Frames that do not correspond to any frame type in this crate, or whose stream profile
+    /// cannot be copied, are skipped.
+    ///
+    pub fn iter_with_profiles(&self) -> impl Iterator<Item = (AnyFrame, StreamProfile)> {
+        self.any_frames().into_iter().filter_map(|f| {
+            let profile = f.stream_profile().try_clone().ok()?;
+            Some((f, profile))
+        })
+    }
+
+    /// Retrieves all frames in the Composite frame collection of the stream `kind`.
+    ///
+    /// This is the runtime counterpart of [`frames_of_type`](Self::frames_of_type), for when the
+    /// stream to extract is only known at runtime, e.g. from a configuration file. Each frame is
+    /// returned as the [`AnyFrame`] variant matching its extension, so e.g. extracting the
+    /// [`Rs2StreamKind::Depth`] stream returns [`AnyFrame::Depth`] frames.
+    ///
+    /// Frames that do not correspond to any frame type in this crate are skipped.
+    ///
+    pub fn frames_of_stream(&self, kind: Rs2StreamKind) -> Vec<AnyFrame> {
+        self.any_frames_where(|frame_kind| frame_kind == kind)
+    }
+
+    /// Retrieves every frame in the Composite frame collection that corresponds to a frame type
+    /// in this crate.
+    pub(crate) fn any_frames(&self) -> Vec<AnyFrame> {
+        self.any_frames_where(|_| true)
+    }
+
+    /// Retrieves every frame in the Composite frame collection that corresponds to a frame type
+    /// in this crate, and whose stream kind satisfies `predicate`.
+    fn any_frames_where<P>(&self, predicate: P) -> Vec<AnyFrame>
+    where
+        P: Fn(Rs2StreamKind) -> bool,
     {
         let mut frames = Vec::new();
         for i in 0..self.count() {
@@ -102,37 +309,61 @@ impl CompositeFrame {
                 }
 
                 let nonnull_frame_ptr = NonNull::new(frame_ptr).unwrap();
+                if !stream_kind(nonnull_frame_ptr).map_or(false, &predicate) {
+                    sys::rs2_release_frame(nonnull_frame_ptr.as_ptr());
+                    continue;
+                }
 
-                let is_extendable_to = sys::rs2_is_frame_extendable_to(
-                    nonnull_frame_ptr.as_ptr(),
-                    #[allow(clippy::useless_conversion)]
-                    (F::extension() as i32).try_into().unwrap(),
-                    &mut err,
-                );
-
-                if err.as_ref().is_none() {
-                    if is_extendable_to != 0 {
-                        if let Ok(f) = F::try_from(nonnull_frame_ptr) {
-                            let kind_for_frame = F::kind();
-
-                            if kind_for_frame == Rs2StreamKind::Any || f.has_correct_kind() {
-                                frames.push(f);
-                            }
-                            // This continue is to skip releasing the frame at the end of the loop.
-                            // If the call to try_from above is successful and we can push, then
-                            // the frame is owned by the type `E` and we should not release it.
-                            continue;
-                        }
-                    }
-                } else {
-                    sys::rs2_free_error(err);
+                match AnyFrame::from_raw(nonnull_frame_ptr) {
+                    Some(f) => frames.push(f),
+                    None => sys::rs2_release_frame(nonnull_frame_ptr.as_ptr()),
                 }
-                sys::rs2_release_frame(nonnull_frame_ptr.as_ptr());
             }
         }
         frames
     }
 
+    /// Get another handle to the same composite frame.
+    ///
+    /// Frames are reference counted in librealsense2, so this does not copy any frame data. Both
+    /// handles remain valid, and the frames are released once both are dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameConstructionError::CouldNotAddReference`] if the reference count of the
+    /// frame cannot be increased.
+    pub fn try_clone(&self) -> Result<Self, FrameConstructionError> {
+        self.add_ref(FrameConstructionError::CouldNotAddReference)
+    }
+
+    /// Increase the reference count of the frame and wrap it in a new handle, reporting failure
+    /// with `error`.
+    fn add_ref<E>(&self, error: fn(Rs2Exception, String) -> E) -> Result<Self, E> {
+        unsafe {
+            let frame = self.frame.unwrap();
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_frame_add_ref(frame.as_ptr(), &mut err);
+            check_rs2_error!(err, error)?;
+
+            Ok(Self::from(frame))
+        }
+    }
+
+    /// Queue these frames on `block` without giving them up.
+    ///
+    /// [`Align::queue`] takes ownership of the frames it is given. This hands the block another
+    /// handle to the same frames instead (see [`try_clone`](Self::try_clone)), so that e.g. the
+    /// raw frames can still be displayed after queueing them to be aligned.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::CouldNotAddFrameReference`] if a handle to the frames cannot be
+    /// created, or any error that [`Align::queue`] can return.
+    pub fn process_into(&self, block: &mut Align) -> Result<(), ProcessFrameError> {
+        let frames = self.add_ref(ProcessFrameError::CouldNotAddFrameReference)?;
+        block.queue(frames)
+    }
+
     /// Get (and own) the underlying frame pointer for this frame.
     ///
     /// This is primarily useful for passing this frame forward to a processing block or blocks
@@ -147,3 +378,42 @@ impl CompositeFrame {
         std::mem::take(&mut self.frame).unwrap()
     }
 }
+
+/// Predicate for whether a frame of the given category, as determined by [`frame_category`],
+/// is converted into `F`.
+fn is_of_category<F: FrameCategory>(category: Option<Rs2Extension>) -> bool {
+    category == Some(F::extension())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame::{ColorFrame, DepthFrame, DisparityFrame};
+
+    #[test]
+    fn disparity_frames_are_not_converted_into_depth_frames() {
+        let category = frame_category(|extension| {
+            matches!(
+                extension,
+                Rs2Extension::DisparityFrame | Rs2Extension::DepthFrame | Rs2Extension::VideoFrame
+            )
+        });
+
+        assert!(is_of_category::<DisparityFrame>(category));
+        assert!(!is_of_category::<DepthFrame>(category));
+        assert!(!is_of_category::<ColorFrame>(category));
+    }
+
+    #[test]
+    fn depth_frames_are_converted_into_depth_frames() {
+        let category = frame_category(|extension| {
+            matches!(
+                extension,
+                Rs2Extension::DepthFrame | Rs2Extension::VideoFrame
+            )
+        });
+
+        assert!(is_of_category::<DepthFrame>(category));
+        assert!(!is_of_category::<DisparityFrame>(category));
+    }
+}
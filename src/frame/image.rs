@@ -9,14 +9,21 @@
 //! Each frame type can hold data in multiple formats. The data type presented
 //! depends on the settings and flags used at runtime on the RealSense device.
 
+use super::colormap::{check_depth_range, color_at};
 use super::pixel::{get_pixel, PixelKind};
+use super::pool::{FramePool, PooledBuffer};
 use super::prelude::{
-    CouldNotGetFrameSensorError, DepthError, DisparityError, FrameCategory, FrameConstructionError,
-    FrameEx, BITS_PER_BYTE,
+    copy_frame_data, hash_frame_data, CouldNotGetFrameSensorError, DepthError, DisparityError,
+    FrameCategory, FrameConstructionError, FrameEx, BITS_PER_BYTE,
 };
 use crate::{
+    base::{Rs2Intrinsics, Rs2Roi},
     check_rs2_error,
-    kind::{Rs2Extension, Rs2FrameMetadata, Rs2Option, Rs2StreamKind, Rs2TimestampDomain},
+    kind::{
+        ColorScheme, DownsampleMode, Rs2Extension, Rs2Format, Rs2FrameMetadata, Rs2Option,
+        Rs2StreamKind, Rs2TimestampDomain,
+    },
+    processing_blocks::disparity::DisparityTransform,
     sensor::Sensor,
     stream_profile::StreamProfile,
 };
@@ -24,12 +31,277 @@ use anyhow::Result;
 use num_traits::FromPrimitive;
 use realsense_sys as sys;
 use std::{
+    borrow::Cow,
     convert::{TryFrom, TryInto},
     marker::PhantomData,
     os::raw::c_int,
     ptr::{self, NonNull},
+    time::Duration,
 };
 
+/// How long [`DepthFrame::to_disparity`] waits for the transformed frame.
+const ONE_SHOT_PROCESSING_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Check that `size` bytes of frame data hold every row of a `width` by `height` frame.
+///
+/// Every row takes up the larger of `stride` bytes and `width` pixels of `bits_per_pixel` bits, and
+/// must be present in full, padding included.
+///
+/// # Errors
+///
+/// Returns [`FrameConstructionError::DataSizeMismatch`] if the data is truncated.
+fn check_data_size(
+    width: c_int,
+    height: c_int,
+    bits_per_pixel: c_int,
+    stride: c_int,
+    size: c_int,
+) -> Result<(), FrameConstructionError> {
+    let row_size = std::cmp::max(stride, width * bits_per_pixel / BITS_PER_BYTE);
+    let expected = row_size.max(0) as usize * height.max(0) as usize;
+    let actual = size.max(0) as usize;
+    if actual < expected {
+        Err(FrameConstructionError::DataSizeMismatch { expected, actual })
+    } else {
+        Ok(())
+    }
+}
+
+/// Get the fraction of raw depth `values` that lie within `[min_m, max_m]` meters.
+///
+/// Values of `0` (no depth data) are never in range. Returns `0.0` if there are no values.
+fn depth_fill_rate<I>(values: I, units: f32, min_m: f32, max_m: f32) -> f32
+where
+    I: IntoIterator<Item = u16>,
+{
+    let mut total = 0_usize;
+    let mut valid = 0_usize;
+    for value in values {
+        total += 1;
+        let meters = f32::from(value) * units;
+        if value != 0 && meters >= min_m && meters <= max_m {
+            valid += 1;
+        }
+    }
+
+    if total == 0 {
+        0.0
+    } else {
+        valid as f32 / total as f32
+    }
+}
+
+/// Fit a plane to `points` by least squares, and get the RMS distance of the points to it.
+///
+/// The plane is fitted as `z = a * x + b * y + c`, which suits surfaces facing the camera, and
+/// distances are measured perpendicular to the plane. Returns `None` if there are fewer than
+/// three points, or if they do not determine a plane (e.g. because they all lie on a line).
+fn plane_fit_rms(points: &[[f32; 3]]) -> Option<f32> {
+    if points.len() < 3 {
+        return None;
+    }
+
+    // Center the points first, which keeps the normal equations well conditioned.
+    let n = points.len() as f64;
+    let mut mean = [0.0_f64; 3];
+    for point in points {
+        for (m, p) in mean.iter_mut().zip(point) {
+            *m += f64::from(*p) / n;
+        }
+    }
+
+    let (mut sxx, mut sxy, mut syy, mut sxz, mut syz) = (0.0, 0.0, 0.0, 0.0, 0.0);
+    for point in points {
+        let x = f64::from(point[0]) - mean[0];
+        let y = f64::from(point[1]) - mean[1];
+        let z = f64::from(point[2]) - mean[2];
+        sxx += x * x;
+        sxy += x * y;
+        syy += y * y;
+        sxz += x * z;
+        syz += y * z;
+    }
+
+    let det = sxx * syy - sxy * sxy;
+    if det.abs() <= f64::EPSILON * (sxx * syy).max(f64::MIN_POSITIVE) {
+        return None;
+    }
+    let a = (sxz * syy - syz * sxy) / det;
+    let b = (syz * sxx - sxz * sxy) / det;
+
+    // With centered coordinates the plane passes through the origin.
+    let norm = (a * a + b * b + 1.0).sqrt();
+    let sum_of_squares: f64 = points
+        .iter()
+        .map(|point| {
+            let x = f64::from(point[0]) - mean[0];
+            let y = f64::from(point[1]) - mean[1];
+            let z = f64::from(point[2]) - mean[2];
+            let distance = (z - a * x - b * y) / norm;
+            distance * distance
+        })
+        .sum();
+    Some((sum_of_squares / n).sqrt() as f32)
+}
+
+/// Deproject one row of raw depth `values`, at pixel row `row`, into 3D points with `intr`.
+///
+/// Values of `0` (no depth data) are skipped.
+fn deproject_row<'a>(
+    intr: &'a Rs2Intrinsics,
+    row: usize,
+    values: &'a [u16],
+    units: f32,
+) -> impl Iterator<Item = [f32; 3]> + 'a {
+    values
+        .iter()
+        .enumerate()
+        .filter(|(_, depth)| **depth != 0)
+        .map(move |(col, depth)| {
+            intr.deproject([col as f32, row as f32], f32::from(*depth) * units)
+        })
+}
+
+/// Deproject the row-major raw depth `values` of an image `width` pixels wide, one row per task
+/// on the rayon thread pool.
+///
+/// Points are returned in row-major pixel order, exactly as a serial deprojection would.
+#[cfg(feature = "rayon")]
+fn par_deproject(intr: &Rs2Intrinsics, values: &[u16], width: usize, units: f32) -> Vec<[f32; 3]> {
+    use rayon::prelude::*;
+
+    if width == 0 {
+        return Vec::new();
+    }
+    values
+        .par_chunks(width)
+        .enumerate()
+        .flat_map_iter(|(row, values)| deproject_row(intr, row, values, units))
+        .collect()
+}
+
+/// Pack rows of 3-byte pixels, `stride` bytes apart in `data`, into a contiguous RGB8 buffer.
+///
+/// If `swap_channels` is set, the first and third byte of every pixel are swapped, converting BGR8
+/// to RGB8. Rows that are already contiguous are copied in one go.
+fn pack_rgb8(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    stride: usize,
+    swap_channels: bool,
+) -> Vec<u8> {
+    let row_len = width * 3;
+    let mut packed = if stride == row_len {
+        data[..row_len * height].to_vec()
+    } else {
+        let mut packed = Vec::with_capacity(row_len * height);
+        for row in data.chunks(stride).take(height) {
+            packed.extend_from_slice(&row[..row_len]);
+        }
+        packed
+    };
+
+    if swap_channels {
+        for pixel in packed.chunks_exact_mut(3) {
+            pixel.swap(0, 2);
+        }
+    }
+    packed
+}
+
+/// Copy the `width` by `height` block of pixels at column `x` and row `y` out of rows `stride`
+/// bytes apart in `data`, with pixels `bytes_per_pixel` bytes wide, into a contiguous buffer.
+///
+/// The block must lie within the image that `data` holds.
+fn crop_rows(
+    data: &[u8],
+    stride: usize,
+    bytes_per_pixel: usize,
+    (x, y): (usize, usize),
+    (width, height): (usize, usize),
+) -> Vec<u8> {
+    let row_len = width * bytes_per_pixel;
+    let mut cropped = Vec::with_capacity(row_len * height);
+    for row in y..y + height {
+        let start = row * stride + x * bytes_per_pixel;
+        cropped.extend_from_slice(&data[start..start + row_len]);
+    }
+    cropped
+}
+
+/// Downsample the row-major raw depth `values` of a `width` by `height` image by `factor`.
+///
+/// Every `factor` by `factor` block of pixels becomes one pixel, combined as described by `mode`.
+/// Blocks at the right and bottom edges are cut short if the dimensions are not multiples of
+/// `factor`, so the result is `ceil(width / factor)` by `ceil(height / factor)` pixels.
+fn downsample_depth(
+    values: &[u16],
+    width: usize,
+    height: usize,
+    factor: usize,
+    mode: DownsampleMode,
+) -> Vec<u16> {
+    let out_width = (width + factor - 1) / factor;
+    let out_height = (height + factor - 1) / factor;
+
+    let mut downsampled = Vec::with_capacity(out_width * out_height);
+    for out_row in 0..out_height {
+        let rows = out_row * factor..((out_row + 1) * factor).min(height);
+        for out_col in 0..out_width {
+            let cols = out_col * factor..((out_col + 1) * factor).min(width);
+            let value = match mode {
+                DownsampleMode::Nearest => values[rows.start * width + cols.start],
+                DownsampleMode::Area => {
+                    let (mut sum, mut count) = (0_u64, 0_u64);
+                    for row in rows.clone() {
+                        for &depth in &values[row * width + cols.start..row * width + cols.end] {
+                            if depth != 0 {
+                                sum += u64::from(depth);
+                                count += 1;
+                            }
+                        }
+                    }
+                    // Round to the nearest unit; the mean of u16 values always fits in a u16.
+                    if count == 0 {
+                        0
+                    } else {
+                        ((sum + count / 2) / count) as u16
+                    }
+                }
+            };
+            downsampled.push(value);
+        }
+    }
+    downsampled
+}
+
+/// Count raw depth `values` into `bins` equal bins spanning `[0, max_m)` meters.
+///
+/// Values of `0` (no depth data) and values at or beyond `max_m` are not counted.
+fn depth_histogram<I>(values: I, units: f32, bins: usize, max_m: f32) -> Vec<u32>
+where
+    I: IntoIterator<Item = u16>,
+{
+    let mut histogram = vec![0; bins];
+    if bins == 0 || max_m <= 0.0 {
+        return histogram;
+    }
+
+    // Map raw values straight to bin indices, rather than converting each one to meters first.
+    let scale = units * bins as f32 / max_m;
+    for value in values {
+        if value == 0 {
+            continue;
+        }
+        let bin = (f32::from(value) * scale) as usize;
+        if bin < bins {
+            histogram[bin] += 1;
+        }
+    }
+    histogram
+}
+
 /// A unit struct defining a Depth frame.
 #[derive(Debug)]
 pub struct Depth;
@@ -80,10 +352,51 @@ pub struct ImageFrame<Kind> {
     /// A boolean used during `Drop` calls. This allows for proper handling of the pointer
     /// during ownership transfer.
     should_drop: bool,
+    /// Depth units reported in place of those of the sensor, if overridden.
+    ///
+    /// See [`DepthFrame::with_units`].
+    depth_units_override: Option<f32>,
     /// Holds the type metadata of this frame.
     _phantom: PhantomData<Kind>,
 }
 
+/// The constants relating the disparity of a stereo depth sensor to depth.
+///
+/// A point at depth `z` appears shifted by a disparity of `baseline * focal_length / z` pixels
+/// between the two cameras of a stereo sensor. These constants make it possible to convert between
+/// the two outside of librealsense2, e.g. in custom stereo post-processing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StereoParameters {
+    /// The distance between the two cameras, in millimeters.
+    pub baseline_mm: f32,
+    /// The horizontal focal length of the depth stream, in pixels.
+    pub focal_length_px: f32,
+}
+
+impl StereoParameters {
+    /// Convert a disparity in pixels to a depth in meters.
+    ///
+    /// Returns `0.0`, i.e. no depth data, for a disparity of zero.
+    pub fn depth_from_disparity(&self, disparity_px: f32) -> f32 {
+        if disparity_px == 0.0 {
+            0.0
+        } else {
+            self.baseline_mm / 1000.0 * self.focal_length_px / disparity_px
+        }
+    }
+
+    /// Convert a depth in meters to a disparity in pixels.
+    ///
+    /// Returns `0.0`, i.e. no disparity data, for a depth of zero.
+    pub fn disparity_from_depth(&self, depth_m: f32) -> f32 {
+        if depth_m == 0.0 {
+            0.0
+        } else {
+            self.baseline_mm / 1000.0 * self.focal_length_px / depth_m
+        }
+    }
+}
+
 /// A type which acts as an iterator over an image frame of some pixel kind.
 pub struct Iter<'a, K> {
     /// The image frame to iterate over.
@@ -122,6 +435,9 @@ impl<'a, K> Iterator for Iter<'a, K> {
 /// All fields in this struct are initialized during struct creation (via `try_from`).
 /// Everything called from here during runtime should be valid as long as the
 /// Frame is in scope... like normal Rust.
+///
+/// Construction fails with [`FrameConstructionError::UnsupportedFormat`] unless the frame data is
+/// in [`Rs2Format::Z16`].
 pub type DepthFrame = ImageFrame<Depth>;
 /// An ImageFrame type holding the raw pointer and derived metadata for an RS2 Disparity frame.
 ///
@@ -175,7 +491,10 @@ impl<'a, K> IntoIterator for &'a ImageFrame<K> {
 
 unsafe impl<K> Send for ImageFrame<K> {}
 
-impl<K> TryFrom<NonNull<sys::rs2_frame>> for ImageFrame<K> {
+impl<K> TryFrom<NonNull<sys::rs2_frame>> for ImageFrame<K>
+where
+    ImageFrame<K>: FrameCategory,
+{
     type Error = anyhow::Error;
 
     /// Attempt to construct an Image frame of extension K from the raw `rs2_frame`.
@@ -196,6 +515,9 @@ impl<K> TryFrom<NonNull<sys::rs2_frame>> for ImageFrame<K> {
     /// - [`CouldNotGetFrameStreamProfile`](FrameConstructionError::CouldNotGetFrameStreamProfile)
     /// - [`CouldNotGetDataSize`](FrameConstructionError::CouldNotGetDataSize)
     /// - [`CouldNotGetData`](FrameConstructionError::CouldNotGetData)
+    /// - [`DataSizeMismatch`](FrameConstructionError::DataSizeMismatch)
+    /// - [`UnsupportedFormat`](FrameConstructionError::UnsupportedFormat)
+    /// - [`ZeroDimensions`](FrameConstructionError::ZeroDimensions)
     ///
     /// See [`FrameConstructionError`] documentation for more details.
     fn try_from(frame_ptr: NonNull<sys::rs2_frame>) -> Result<Self, Self::Error> {
@@ -207,6 +529,15 @@ impl<K> TryFrom<NonNull<sys::rs2_frame>> for ImageFrame<K> {
             let height = sys::rs2_get_frame_height(frame_ptr.as_ptr(), &mut err);
             check_rs2_error!(err, FrameConstructionError::CouldNotGetHeight)?;
 
+            // Every pixel accessor assumes there is at least one pixel.
+            if width <= 0 || height <= 0 {
+                return Err(FrameConstructionError::ZeroDimensions {
+                    width: width.max(0) as usize,
+                    height: height.max(0) as usize,
+                }
+                .into());
+            }
+
             let bits_per_pixel = sys::rs2_get_frame_bits_per_pixel(frame_ptr.as_ptr(), &mut err);
             check_rs2_error!(err, FrameConstructionError::CouldNotGetBitsPerPixel)?;
 
@@ -230,10 +561,20 @@ impl<K> TryFrom<NonNull<sys::rs2_frame>> for ImageFrame<K> {
                 NonNull::new(profile_ptr as *mut sys::rs2_stream_profile).unwrap();
             let profile = StreamProfile::try_from(nonnull_profile_ptr)?;
 
+            if !Self::supports_format(profile.format()) {
+                return Err(FrameConstructionError::UnsupportedFormat {
+                    extension: Self::extension(),
+                    format: profile.format(),
+                }
+                .into());
+            }
+
             let size = sys::rs2_get_frame_data_size(frame_ptr.as_ptr(), &mut err);
             check_rs2_error!(err, FrameConstructionError::CouldNotGetDataSize)?;
 
-            debug_assert_eq!(size, width * height * bits_per_pixel / BITS_PER_BYTE);
+            // Pixel access indexes into the data by row stride, so a truncated buffer (e.g. from a
+            // USB glitch) would otherwise be read out of bounds.
+            check_data_size(width, height, bits_per_pixel, stride, size)?;
 
             let data_ptr = sys::rs2_get_frame_data(frame_ptr.as_ptr(), &mut err);
             check_rs2_error!(err, FrameConstructionError::CouldNotGetData)?;
@@ -253,6 +594,7 @@ impl<K> TryFrom<NonNull<sys::rs2_frame>> for ImageFrame<K> {
                 data_size_in_bytes: size as usize,
                 data: nonnull_data_ptr,
                 should_drop: true,
+                depth_units_override: None,
                 _phantom: PhantomData::<K> {},
             })
         }
@@ -271,6 +613,10 @@ impl FrameCategory for DepthFrame {
     fn has_correct_kind(&self) -> bool {
         self.frame_stream_profile.kind() == Self::kind()
     }
+
+    fn supports_format(format: Rs2Format) -> bool {
+        format == Rs2Format::Z16
+    }
 }
 
 impl FrameCategory for DisparityFrame {
@@ -414,6 +760,23 @@ impl<T> FrameEx for ImageFrame<T> {
         }
     }
 
+    fn to_owned_bytes(&self) -> Result<(Vec<u8>, StreamProfile)> {
+        let bytes = unsafe { copy_frame_data(self.frame_ptr)? };
+        Ok((bytes, self.frame_stream_profile.try_clone()?))
+    }
+
+    fn content_hash(&self) -> u64 {
+        unsafe { hash_frame_data(self.frame_ptr) }
+    }
+
+    fn keep(&self) {
+        unsafe { sys::rs2_keep_frame(self.frame_ptr.as_ptr()) }
+    }
+
+    fn as_raw(&self) -> *const sys::rs2_frame {
+        self.frame_ptr.as_ptr()
+    }
+
     unsafe fn get_owned_raw(mut self) -> NonNull<sys::rs2_frame> {
         self.should_drop = false;
 
@@ -431,6 +794,19 @@ impl DepthFrame {
     /// `DepthFrame::depth_units` and then applying that to the raw data with [`ImageFrame::get`]
     /// is a much more efficient way to handle this.
     pub fn distance(&self, col: usize, row: usize) -> Result<f32, DepthError> {
+        if let Some(units) = self.depth_units_override {
+            return match self.get(col, row) {
+                Some(PixelKind::Z16 { depth }) => Ok(f32::from(*depth) * units),
+                Some(_) => unreachable!("Depth frames are checked to hold Z16 data on creation"),
+                None => Err(DepthError::PixelOutOfBounds {
+                    col,
+                    row,
+                    width: self.width,
+                    height: self.height,
+                }),
+            };
+        }
+
         unsafe {
             let mut err = ptr::null_mut::<sys::rs2_error>();
             let distance = sys::rs2_depth_frame_get_distance(
@@ -445,13 +821,351 @@ impl DepthFrame {
     }
 
     /// Get the metric units currently used for reporting depth information.
+    ///
+    /// This is read from the sensor that produced the frame, unless it was overridden with
+    /// [`with_units`](DepthFrame::with_units).
     pub fn depth_units(&self) -> Result<f32> {
+        if let Some(units) = self.depth_units_override {
+            return Ok(units);
+        }
+
         let sensor = self.sensor()?;
         let depth_units = sensor.get_option(Rs2Option::DepthUnits).ok_or_else(|| {
             anyhow::anyhow!("Option is not supported on the sensor for this frame type.")
         })?;
         Ok(depth_units)
     }
+
+    /// Get another handle to the frame that reports `units` as its depth units.
+    ///
+    /// Recordings made by third-party tools sometimes carry the wrong depth units, or none at all.
+    /// The returned frame shares the data of this one, but its
+    /// [`depth_units`](DepthFrame::depth_units) returns `units` (in meters), and every conversion
+    /// to meters in this crate, such as [`distance`](DepthFrame::distance),
+    /// [`histogram`](DepthFrame::histogram) and [`to_point_cloud`](DepthFrame::to_point_cloud),
+    /// uses it. Processing blocks still read the units from the sensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameConstructionError::CouldNotAddReference`] if another handle to the frame
+    /// cannot be created.
+    pub fn with_units(&self, units: f32) -> Result<DepthFrame> {
+        let mut frame = self.try_clone()?;
+        frame.depth_units_override = Some(units);
+        Ok(frame)
+    }
+
+    /// Get the raw depth values of the frame as a slice, without copying.
+    ///
+    /// Values are in row-major order and in [depth units](DepthFrame::depth_units), with `0`
+    /// meaning there is no depth data for that pixel. This is much faster than matching on
+    /// [`PixelKind::Z16`] for every pixel when operating on the whole image.
+    ///
+    /// Returns `None` if the frame is not in [`Rs2Format::Z16`], or
+    /// if its rows are padded, since the values could then not be represented as one contiguous
+    /// slice. Use [`ImageFrame::get`] for such frames instead.
+    pub fn depth_data(&self) -> Option<&[u16]> {
+        let len = self.width * self.height;
+        let data = self.data.as_ptr().cast::<u16>();
+
+        if self.frame_stream_profile.format() != Rs2Format::Z16
+            || self.stride != self.width * std::mem::size_of::<u16>()
+            || self.data_size_in_bytes < len * std::mem::size_of::<u16>()
+            || data.align_offset(std::mem::align_of::<u16>()) != 0
+        {
+            return None;
+        }
+
+        // The frame data holds at least `len` tightly packed and aligned u16 values, and
+        // lives for as long as the frame does.
+        unsafe { Some(std::slice::from_raw_parts(data, len)) }
+    }
+
+    /// Get the raw depth values of the frame in row-major order, copying them only if the frame
+    /// cannot be viewed as one contiguous slice (see [`depth_data`](DepthFrame::depth_data)).
+    fn depth_values(&self) -> Cow<'_, [u16]> {
+        match self.depth_data() {
+            Some(data) => Cow::Borrowed(data),
+            None => Cow::Owned(
+                self.iter()
+                    .map(|pixel| match pixel {
+                        PixelKind::Z16 { depth } => *depth,
+                        _ => 0,
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Colorize the depth frame, returning packed RGB bytes in row-major order.
+    ///
+    /// Distances in `[min_m, max_m]` (in meters) are mapped onto the color map for `scheme`, and
+    /// distances outside of that range are clamped to its ends. Pixels with no depth data are
+    /// black. Unlike the colorizer processing block this runs entirely on the host, and does not
+    /// require a frame from the pipeline to be queued.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidDepthRangeError`](crate::frame::InvalidDepthRangeError) if `max_m` is not
+    /// greater than `min_m`.
+    ///
+    /// Returns an error if the [depth units](DepthFrame::depth_units) of the frame cannot be
+    /// determined.
+    pub fn colorize(&self, min_m: f32, max_m: f32, scheme: ColorScheme) -> Result<Vec<u8>> {
+        check_depth_range(min_m, max_m)?;
+        let units = self.depth_units()?;
+        let range = max_m - min_m;
+
+        let mut rgb = Vec::with_capacity(self.width * self.height * 3);
+        for pixel in self.iter() {
+            let depth = match pixel {
+                PixelKind::Z16 { depth } => *depth,
+                _ => 0,
+            };
+
+            if depth == 0 {
+                rgb.extend_from_slice(&[0, 0, 0]);
+            } else {
+                let t = (depth as f32 * units - min_m) / range;
+                rgb.extend_from_slice(&color_at(scheme, t));
+            }
+        }
+        Ok(rgb)
+    }
+
+    /// Compute a histogram of the distances in the frame.
+    ///
+    /// The range `[0, max_m)` (in meters) is divided into `bins` bins of equal width, and the
+    /// returned vector holds the number of pixels whose distance falls into each bin. Pixels with
+    /// no depth data, and pixels at `max_m` or further, are not counted.
+    ///
+    /// This is computed in a single pass over [`depth_data`](DepthFrame::depth_data) when it is
+    /// available, and falls back to iterating over every pixel otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the [depth units](DepthFrame::depth_units) of the frame cannot be
+    /// determined.
+    pub fn histogram(&self, bins: usize, max_m: f32) -> Result<Vec<u32>> {
+        let units = self.depth_units()?;
+
+        let histogram = match self.depth_data() {
+            Some(data) => depth_histogram(data.iter().copied(), units, bins, max_m),
+            None => depth_histogram(
+                self.iter().map(|pixel| match pixel {
+                    PixelKind::Z16 { depth } => *depth,
+                    _ => 0,
+                }),
+                units,
+                bins,
+                max_m,
+            ),
+        };
+        Ok(histogram)
+    }
+
+    /// Downsample the frame by `factor` in both directions, returning raw depth values in
+    /// row-major order.
+    ///
+    /// Every `factor` by `factor` block of pixels becomes one pixel, combined as described by
+    /// `mode`. The result is `ceil(width / factor)` by `ceil(height / factor)` pixels, with
+    /// blocks at the right and bottom edges cut short if the frame dimensions are not multiples
+    /// of `factor`. Values are in [depth units](DepthFrame::depth_units), with `0` meaning there is
+    /// no depth data.
+    ///
+    /// Unlike the [decimation](crate::processing_blocks::decimation) processing block this runs
+    /// entirely on the host and applies no median smoothing, which suits e.g. building a coarse
+    /// occupancy grid.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `factor` is zero.
+    pub fn downsample(&self, factor: u32, mode: DownsampleMode) -> Vec<u16> {
+        assert!(factor != 0, "Cannot downsample by a factor of zero.");
+        downsample_depth(
+            &self.depth_values(),
+            self.width,
+            self.height,
+            factor as usize,
+            mode,
+        )
+    }
+
+    /// Get the fraction of pixels in the frame whose distance lies within `[min_m, max_m]` meters.
+    ///
+    /// Pixels with no depth data are counted as out of range. Pointed at a flat target, this is the
+    /// fill rate that QA setups measure to validate a camera; a healthy camera should be close to
+    /// `1.0` within its working range. Returns `0.0` for an empty frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the [depth units](DepthFrame::depth_units) of the frame cannot be
+    /// determined.
+    pub fn fill_rate(&self, min_m: f32, max_m: f32) -> Result<f32> {
+        let units = self.depth_units()?;
+
+        let rate = match self.depth_data() {
+            Some(data) => depth_fill_rate(data.iter().copied(), units, min_m, max_m),
+            None => depth_fill_rate(
+                self.iter().map(|pixel| match pixel {
+                    PixelKind::Z16 { depth } => *depth,
+                    _ => 0,
+                }),
+                units,
+                min_m,
+                max_m,
+            ),
+        };
+        Ok(rate)
+    }
+
+    /// Measure the spatial noise of the frame within `roi`, as the RMS distance in meters of the
+    /// depth points to the plane that best fits them.
+    ///
+    /// Pointed at a flat wall, this is the flatness (spatial noise) metric that QA setups measure
+    /// to validate a camera. The pixels within `roi` (inclusive, and clipped to the frame) are
+    /// deprojected with the intrinsics of the frame's stream profile, and pixels with no depth
+    /// data are skipped.
+    ///
+    /// Returns `None` if `roi` holds fewer than three pixels with depth data, or if they do not
+    /// determine a plane.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the [depth units](DepthFrame::depth_units) or the intrinsics of the
+    /// frame cannot be determined.
+    pub fn plane_fit_rms(&self, roi: Rs2Roi) -> Result<Option<f32>> {
+        let units = self.depth_units()?;
+        let intr = self.frame_stream_profile.intrinsics()?;
+
+        let clip = |min: i32, max: i32, len: usize| {
+            let min = usize::try_from(min).unwrap_or(0);
+            let max = usize::try_from(i64::from(max) + 1).unwrap_or(0).min(len);
+            min..max
+        };
+
+        let mut points = Vec::new();
+        for row in clip(roi.min_y, roi.max_y, self.height) {
+            for col in clip(roi.min_x, roi.max_x, self.width) {
+                let depth = match self.get(col, row) {
+                    Some(PixelKind::Z16 { depth }) => *depth,
+                    _ => 0,
+                };
+                if depth != 0 {
+                    let pixel = [col as f32, row as f32];
+                    points.push(intr.deproject(pixel, f32::from(depth) * units));
+                }
+            }
+        }
+        Ok(plane_fit_rms(&points))
+    }
+
+    /// Deproject every pixel with depth data into a 3D point, using the intrinsics `intr`.
+    ///
+    /// Points are `[x, y, z]` in meters in the coordinate frame of the stream described by
+    /// `intr` (see [`Rs2Intrinsics::deproject`]), and are returned in row-major pixel order.
+    /// Pixels with no depth data are skipped. `intr` would usually be the intrinsics of this
+    /// frame's own stream profile, or of the stream it was aligned to.
+    ///
+    /// Unlike the point cloud processing block this runs entirely on the host, and produces no
+    /// texture coordinates.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the [depth units](DepthFrame::depth_units) of the frame cannot be
+    /// determined.
+    pub fn to_point_cloud(&self, intr: &Rs2Intrinsics) -> Result<Vec<[f32; 3]>> {
+        let units = self.depth_units()?;
+
+        let values = self.depth_values();
+        let points = values
+            .chunks(self.width.max(1))
+            .enumerate()
+            .flat_map(|(row, values)| deproject_row(intr, row, values, units))
+            .collect();
+        Ok(points)
+    }
+
+    /// Deproject every pixel with depth data into a 3D point, spreading the work over the rayon
+    /// thread pool.
+    ///
+    /// Produces the same points in the same order as [`to_point_cloud`](DepthFrame::to_point_cloud),
+    /// but deprojects rows in parallel, which is what it takes to keep up with high resolution
+    /// streams (e.g. 1280x720 at 30 FPS) in real time.
+    ///
+    /// Only available with the `rayon` feature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the [depth units](DepthFrame::depth_units) of the frame cannot be
+    /// determined.
+    #[cfg(feature = "rayon")]
+    pub fn par_to_point_cloud(&self, intr: &Rs2Intrinsics) -> Result<Vec<[f32; 3]>> {
+        let units = self.depth_units()?;
+
+        Ok(par_deproject(intr, &self.depth_values(), self.width, units))
+    }
+
+    /// Copy the `width` by `height` block of pixels whose top-left corner is at column `x` and
+    /// row `y` into a new image, returning its raw depth values in row-major order.
+    ///
+    /// The result is tightly packed, i.e. has exactly `width * height` values, regardless of any
+    /// row padding in the frame. Values are in [depth units](DepthFrame::depth_units), with `0`
+    /// meaning there is no depth data.
+    ///
+    /// Returns `None` if the block does not lie entirely within the frame.
+    pub fn crop(&self, x: usize, y: usize, width: usize, height: usize) -> Option<Vec<u16>> {
+        let bytes = self.crop_bytes(x, y, width, height)?;
+        Some(
+            bytes
+                .chunks_exact(std::mem::size_of::<u16>())
+                .map(|value| u16::from_ne_bytes([value[0], value[1]]))
+                .collect(),
+        )
+    }
+
+    /// Get the stereo baseline and focal length of the sensor that produced this frame.
+    ///
+    /// The baseline is read from the [`Rs2Option::StereoBaseline`] option of the sensor, and the
+    /// focal length from the intrinsics of the frame's stream profile.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the sensor is not a stereo sensor, or if the intrinsics of the frame
+    /// cannot be determined.
+    pub fn stereo_parameters(&self) -> Result<StereoParameters> {
+        let baseline_mm = self
+            .sensor()?
+            .get_option(Rs2Option::StereoBaseline)
+            .ok_or_else(|| {
+                anyhow::anyhow!("Stereo baseline is not supported on the sensor for this frame.")
+            })?;
+        let focal_length_px = self.frame_stream_profile.intrinsics()?.fx();
+        Ok(StereoParameters {
+            baseline_mm,
+            focal_length_px,
+        })
+    }
+
+    /// Convert the depth frame into a disparity frame.
+    ///
+    /// This runs the frame through a one-shot
+    /// [`DisparityTransform`](crate::processing_blocks::disparity::DisparityTransform) block. To
+    /// convert a stream of frames, create the block once and reuse it instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`](crate::processing_blocks::errors::ProcessingBlockConstructionError)
+    /// if the disparity transform block cannot be created.
+    ///
+    /// Returns [`ProcessFrameError`](crate::processing_blocks::errors::ProcessFrameError) if the
+    /// frame cannot be transformed.
+    pub fn to_disparity(&self) -> Result<DisparityFrame> {
+        let mut transform = DisparityTransform::new(1)?;
+        transform.queue(self.try_clone()?)?;
+        // Frames are processed as they are queued, so the result is already waiting.
+        Ok(transform.wait(ONE_SHOT_PROCESSING_TIMEOUT)?)
+    }
 }
 
 impl DisparityFrame {
@@ -489,6 +1203,30 @@ impl DisparityFrame {
         Ok(depth_units)
     }
 
+    /// Get the frame data as a slice of 32-bit floating point disparity values.
+    ///
+    /// The values are in row-major order, with [`stride`](ImageFrame::stride) / 4 values per row;
+    /// rows may be padded beyond the [`width`](ImageFrame::width) of the frame. This is the
+    /// efficient way to read a whole frame of float disparity, e.g. the output of a
+    /// [`DisparityTransform`] block, rather than calling [`ImageFrame::get`] for every pixel.
+    ///
+    /// Returns `None` if the frame is not in the
+    /// [`Rs2Format::Disparity32`](crate::kind::Rs2Format::Disparity32) format, or if its data is
+    /// not aligned for `f32` values. Use [`ImageFrame::get`] for such frames instead.
+    pub fn as_f32_slice(&self) -> Option<&[f32]> {
+        let len = self.data_size_in_bytes / std::mem::size_of::<f32>();
+        let data = self.data.as_ptr().cast::<f32>();
+
+        if self.frame_stream_profile.format() != Rs2Format::Disparity32
+            || data.align_offset(std::mem::align_of::<f32>()) != 0
+        {
+            return None;
+        }
+
+        // The frame data holds `len` aligned f32 values, and lives for as long as the frame does.
+        unsafe { Some(std::slice::from_raw_parts(data, len)) }
+    }
+
     /// Get the baseline used during construction of the Disparity frame
     pub fn baseline(&self) -> Result<f32, DisparityError> {
         unsafe {
@@ -499,6 +1237,103 @@ impl DisparityFrame {
             Ok(baseline)
         }
     }
+
+    /// Get the stereo baseline and focal length used during construction of the disparity frame.
+    ///
+    /// The baseline is that of [`baseline`](DisparityFrame::baseline), and the focal length is
+    /// read from the intrinsics of the frame's stream profile.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DisparityError`] if the baseline cannot be read, or an error if the intrinsics of
+    /// the frame cannot be determined.
+    pub fn stereo_parameters(&self) -> Result<StereoParameters> {
+        let baseline_mm = self.baseline()?;
+        let focal_length_px = self.frame_stream_profile.intrinsics()?.fx();
+        Ok(StereoParameters {
+            baseline_mm,
+            focal_length_px,
+        })
+    }
+}
+
+impl ColorFrame {
+    /// Copy the frame into a tightly packed buffer of RGB8 pixels.
+    ///
+    /// RGB8 frames are copied as they are, and BGR8 frames have their channels swapped while
+    /// they are copied, so either way the frame data is traversed only once. The result can be
+    /// handed straight to e.g. `image::RgbImage::from_raw(width, height, buffer)` without any
+    /// further conversion.
+    ///
+    /// Returns `None` if the frame is in any other format.
+    pub fn to_rgb8(&self) -> Option<Vec<u8>> {
+        let swap_channels = match self.frame_stream_profile.format() {
+            Rs2Format::Rgb8 => false,
+            Rs2Format::Bgr8 => true,
+            _ => return None,
+        };
+        let data = unsafe {
+            std::slice::from_raw_parts(self.data.as_ptr().cast::<u8>(), self.data_size_in_bytes)
+        };
+        Some(pack_rgb8(
+            data,
+            self.width,
+            self.height,
+            self.stride,
+            swap_channels,
+        ))
+    }
+
+    /// Copy the `width` by `height` block of pixels whose top-left corner is at column `x` and
+    /// row `y` into a new image, returning its bytes in row-major order.
+    ///
+    /// The result keeps the pixel format of the frame, e.g. three bytes per pixel for
+    /// [`Rs2Format::Rgb8`], and is tightly packed regardless of any row padding in the frame.
+    ///
+    /// Returns `None` if the block does not lie entirely within the frame.
+    pub fn crop(&self, x: usize, y: usize, width: usize, height: usize) -> Option<Vec<u8>> {
+        self.crop_bytes(x, y, width, height)
+    }
+}
+
+impl ConfidenceFrame {
+    /// Given the 2D depth coordinate (x,y) provide the confidence value of that pixel.
+    ///
+    /// Confidence frames are emitted by L515 devices in
+    /// [`Rs2Format::Raw8`](crate::kind::Rs2Format::Raw8), and can be used to
+    /// filter the matching depth frame. Returns `None` if the coordinate is out of bounds or the
+    /// frame is not in the expected format.
+    pub fn confidence(&self, col: usize, row: usize) -> Option<u8> {
+        match self.get(col, row)? {
+            PixelKind::Raw8 { val } => Some(*val),
+            _ => None,
+        }
+    }
+}
+
+impl<K> ImageFrame<K>
+where
+    ImageFrame<K>: FrameCategory,
+{
+    /// Get another handle to the same underlying frame.
+    ///
+    /// Frames are reference counted in librealsense2, so this does not copy the frame data. This
+    /// is used to hand a frame to a processing block, which takes ownership, while the caller
+    /// keeps its own handle.
+    pub(crate) fn try_clone(&self) -> Result<Self> {
+        unsafe {
+            let mut err = ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_frame_add_ref(self.frame_ptr.as_ptr(), &mut err);
+            check_rs2_error!(err, FrameConstructionError::CouldNotAddReference)?;
+
+            let mut frame = Self::try_from(self.frame_ptr).map_err(|e| {
+                sys::rs2_release_frame(self.frame_ptr.as_ptr());
+                e
+            })?;
+            frame.depth_units_override = self.depth_units_override;
+            Ok(frame)
+        }
+    }
 }
 
 impl<K> ImageFrame<K> {
@@ -565,6 +1400,23 @@ impl<K> ImageFrame<K> {
         self.data.as_ref()
     }
 
+    /// Copy the raw data held by this Video frame into a buffer taken from `pool`.
+    ///
+    /// The buffer holds [`get_data_size`](ImageFrame::get_data_size) bytes, and is returned to the
+    /// pool once dropped. Unlike the frame itself, the buffer can be kept for as long as needed
+    /// without holding on to librealsense2's frame memory.
+    pub fn copy_to_pool(&self, pool: &FramePool) -> PooledBuffer {
+        let mut buffer = pool.get_buffer(self.data_size_in_bytes);
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                self.data.as_ptr().cast::<u8>(),
+                buffer.as_mut_ptr(),
+                self.data_size_in_bytes,
+            );
+        }
+        buffer
+    }
+
     /// Get the width of this Video frame in pixels
     pub fn width(&self) -> usize {
         self.width
@@ -583,6 +1435,30 @@ impl<K> ImageFrame<K> {
             Some(self.get_unchecked(col, row))
         }
     }
+
+    /// Copy the `width` by `height` block of pixels whose top-left corner is at column `x` and
+    /// row `y` into a buffer without row padding, keeping the pixel format of the frame.
+    ///
+    /// Returns `None` if the block does not lie entirely within the frame.
+    fn crop_bytes(&self, x: usize, y: usize, width: usize, height: usize) -> Option<Vec<u8>> {
+        let fits = |start: usize, len: usize, max: usize| {
+            start.checked_add(len).map_or(false, |end| end <= max)
+        };
+        if !fits(x, width, self.width) || !fits(y, height, self.height) {
+            return None;
+        }
+
+        let data = unsafe {
+            std::slice::from_raw_parts(self.data.as_ptr().cast::<u8>(), self.data_size_in_bytes)
+        };
+        Some(crop_rows(
+            data,
+            self.stride,
+            self.bits_per_pixel / BITS_PER_BYTE as usize,
+            (x, y),
+            (width, height),
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -598,4 +1474,174 @@ mod tests {
         assert_eq!(FisheyeFrame::kind(), Rs2StreamKind::Fisheye);
         assert_eq!(ConfidenceFrame::kind(), Rs2StreamKind::Confidence);
     }
+    #[test]
+    fn depth_frame_supports_only_z16() {
+        assert!(DepthFrame::supports_format(Rs2Format::Z16));
+        assert!(!DepthFrame::supports_format(Rs2Format::Rgb8));
+        assert!(!DepthFrame::supports_format(Rs2Format::Y8));
+        assert!(ColorFrame::supports_format(Rs2Format::Rgb8));
+    }
+
+    #[test]
+    fn data_size_must_cover_every_row() {
+        // 4x3 pixels of 16 bits, tightly packed.
+        assert_eq!(check_data_size(4, 3, 16, 8, 24), Ok(()));
+        assert_eq!(
+            check_data_size(4, 3, 16, 8, 23),
+            Err(FrameConstructionError::DataSizeMismatch {
+                expected: 24,
+                actual: 23
+            })
+        );
+        // Rows padded to 12 bytes, so the padding of every row is required too.
+        assert_eq!(check_data_size(4, 3, 16, 12, 36), Ok(()));
+        assert_eq!(
+            check_data_size(4, 3, 16, 12, 24),
+            Err(FrameConstructionError::DataSizeMismatch {
+                expected: 36,
+                actual: 24
+            })
+        );
+    }
+
+    #[test]
+    fn pack_rgb8_drops_row_padding_and_swaps_channels() {
+        // Two rows of two pixels, each row padded with two bytes.
+        let data = [1, 2, 3, 4, 5, 6, 0, 0, 7, 8, 9, 10, 11, 12, 0, 0];
+        assert_eq!(
+            pack_rgb8(&data, 2, 2, 8, false),
+            vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]
+        );
+        assert_eq!(
+            pack_rgb8(&data, 2, 2, 8, true),
+            vec![3, 2, 1, 6, 5, 4, 9, 8, 7, 12, 11, 10]
+        );
+    }
+
+    #[test]
+    fn fill_rate_counts_pixels_in_range() {
+        let values = [0, 500, 1000, 1500, 2000, 9000, 0, 750];
+        assert_eq!(
+            depth_fill_rate(values.iter().copied(), 0.001, 0.5, 2.0),
+            0.625
+        );
+        assert_eq!(depth_fill_rate(std::iter::empty(), 0.001, 0.5, 2.0), 0.0);
+    }
+
+    #[test]
+    fn plane_fit_measures_distance_to_tilted_plane() {
+        // Points on the plane z = 0.1 x + 1, alternately 1 cm above and below it along z.
+        let points: Vec<[f32; 3]> = (0..10)
+            .flat_map(|i| (0..10).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                let (x, y) = (i as f32 * 0.1, j as f32 * 0.1);
+                let offset = if (i + j) % 2 == 0 { 0.01 } else { -0.01 };
+                [x, y, 0.1 * x + 1.0 + offset]
+            })
+            .collect();
+        let rms = plane_fit_rms(&points).unwrap();
+        let expected = 0.01 / (0.1_f32 * 0.1 + 1.0).sqrt();
+        assert!((rms - expected).abs() < 1e-4, "{} != {}", rms, expected);
+
+        // Collinear points do not determine a plane.
+        assert!(plane_fit_rms(&[[0.0, 0.0, 1.0], [1.0, 1.0, 1.0], [2.0, 2.0, 1.0]]).is_none());
+    }
+
+    #[test]
+    fn stereo_parameters_convert_both_ways() {
+        let stereo = StereoParameters {
+            baseline_mm: 50.0,
+            focal_length_px: 400.0,
+        };
+        assert!((stereo.depth_from_disparity(20.0) - 1.0).abs() < 1e-6);
+        assert!((stereo.disparity_from_depth(1.0) - 20.0).abs() < 1e-4);
+        assert_eq!(stereo.depth_from_disparity(0.0), 0.0);
+        assert_eq!(stereo.disparity_from_depth(0.0), 0.0);
+    }
+
+    #[test]
+    fn depth_histogram_skips_missing_and_distant_pixels() {
+        // With millimeter units, 4 bins over 4 meters are one meter wide each.
+        let values = [0, 500, 1500, 1999, 3999, 4000, 9000];
+        assert_eq!(
+            depth_histogram(values.iter().copied(), 0.001, 4, 4.0),
+            vec![1, 2, 0, 1]
+        );
+        assert!(depth_histogram(values.iter().copied(), 0.001, 0, 4.0).is_empty());
+    }
+
+    #[test]
+    fn deprojection_skips_missing_pixels() {
+        let intr = Rs2Intrinsics(sys::rs2_intrinsics {
+            width: 3,
+            height: 2,
+            ppx: 1.0,
+            ppy: 1.0,
+            fx: 1.0,
+            fy: 1.0,
+            model: sys::rs2_distortion_RS2_DISTORTION_NONE,
+            coeffs: [0.0; 5],
+        });
+        let points: Vec<_> = deproject_row(&intr, 1, &[1000, 0, 2000], 0.001).collect();
+        assert_eq!(points, vec![[-1.0, 0.0, 1.0], [2.0, 0.0, 2.0]]);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_deprojection_matches_serial_order() {
+        let intr = Rs2Intrinsics(sys::rs2_intrinsics {
+            width: 64,
+            height: 48,
+            ppx: 32.0,
+            ppy: 24.0,
+            fx: 50.0,
+            fy: 50.0,
+            model: sys::rs2_distortion_RS2_DISTORTION_NONE,
+            coeffs: [0.0; 5],
+        });
+        let values: Vec<u16> = (0..64 * 48).map(|i| (i % 7 * 300) as u16).collect();
+
+        let serial: Vec<_> = values
+            .chunks(64)
+            .enumerate()
+            .flat_map(|(row, values)| deproject_row(&intr, row, values, 0.001))
+            .collect();
+        assert_eq!(par_deproject(&intr, &values, 64, 0.001), serial);
+        assert!(par_deproject(&intr, &values, 0, 0.001).is_empty());
+    }
+
+    #[test]
+    fn downsampling_keeps_or_averages_each_block() {
+        // A 3x3 image downsampled by 2 has a full block and three blocks cut short by the edges.
+        #[rustfmt::skip]
+        let values = [
+            100, 200, 300,
+            0,   400, 500,
+            600, 700, 0,
+        ];
+        assert_eq!(
+            downsample_depth(&values, 3, 3, 2, DownsampleMode::Nearest),
+            vec![100, 300, 600, 0]
+        );
+        assert_eq!(
+            downsample_depth(&values, 3, 3, 2, DownsampleMode::Area),
+            vec![233, 400, 650, 0]
+        );
+        assert_eq!(
+            downsample_depth(&values, 3, 3, 1, DownsampleMode::Area),
+            values.to_vec()
+        );
+    }
+
+    #[test]
+    fn crop_drops_row_padding() {
+        // A 3x2 image of 2-byte pixels, with 2 bytes of padding at the end of every row.
+        let data = [1, 1, 2, 2, 3, 3, 0, 0, 4, 4, 5, 5, 6, 6, 0, 0];
+        assert_eq!(
+            crop_rows(&data, 8, 2, (1, 0), (2, 2)),
+            vec![2, 2, 3, 3, 5, 5, 6, 6]
+        );
+        assert_eq!(crop_rows(&data, 8, 2, (0, 1), (1, 1)), vec![4, 4]);
+        assert!(crop_rows(&data, 8, 2, (3, 2), (0, 0)).is_empty());
+    }
 }
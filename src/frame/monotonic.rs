@@ -0,0 +1,160 @@
+//! Utility for checking that frame timestamps never go backwards.
+//!
+//! librealsense2 timestamps are only comparable within one [`Rs2TimestampDomain`]. When a sensor
+//! switches domain, e.g. from [`GlobalTime`](Rs2TimestampDomain::GlobalTime) to
+//! [`SystemTime`](Rs2TimestampDomain::SystemTime) because global time could not be established,
+//! timestamps can jump backwards. Consumers that integrate over time, such as sensor fusion, tend
+//! to misbehave quietly when that happens; [`MonotonicClock`] catches it as it occurs.
+
+use super::prelude::FrameEx;
+use crate::kind::Rs2TimestampDomain;
+use thiserror::Error;
+
+/// A timestamp that breaks the monotonicity of a [`MonotonicClock`].
+#[derive(Error, Debug, Clone, Copy, PartialEq)]
+pub enum TimestampViolation {
+    /// The timestamp is earlier than the previous one, in the same domain.
+    #[error("Timestamp went backwards from {previous} ms to {current} ms in domain {domain:?}.")]
+    Backwards {
+        /// The previous timestamp, in milliseconds.
+        previous: f64,
+        /// The offending timestamp, in milliseconds.
+        current: f64,
+        /// The domain of both timestamps.
+        domain: Rs2TimestampDomain,
+    },
+    /// The timestamp is in a different domain than the previous one, so they cannot be compared.
+    #[error("Timestamp domain changed from {previous:?} to {current:?}.")]
+    DomainChanged {
+        /// The domain of the previous timestamp.
+        previous: Rs2TimestampDomain,
+        /// The domain of the offending timestamp.
+        current: Rs2TimestampDomain,
+    },
+}
+
+/// Checks that a sequence of timestamps never goes backwards, nor changes domain.
+///
+/// Every violation is logged as a warning through the `log` crate, and returned so that callers
+/// can react to it. The clock carries on from the offending timestamp, so a single jump is
+/// reported once rather than for every frame after it.
+///
+/// ```no_run
+/// use realsense_rust::frame::{FrameEx, MonotonicClock};
+/// # fn next_frame() -> realsense_rust::frame::DepthFrame { unimplemented!() }
+///
+/// let mut clock = MonotonicClock::new();
+/// loop {
+///     let frame = next_frame();
+///     if clock.check_frame(&frame).is_err() {
+///         // e.g. reset any filter state that depends on time
+///     }
+/// }
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct MonotonicClock {
+    /// The most recent timestamp and its domain, if any.
+    last: Option<(f64, Rs2TimestampDomain)>,
+    /// The number of violations seen since construction or the last reset.
+    violations: usize,
+}
+
+impl MonotonicClock {
+    /// Create a clock that has not seen any timestamps yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record `timestamp`, in milliseconds within `domain`, and check it against the previous one.
+    ///
+    /// Repeated timestamps are allowed; only timestamps that are strictly earlier are violations.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TimestampViolation::DomainChanged`] if `domain` differs from the domain of the
+    /// previous timestamp, and [`TimestampViolation::Backwards`] if `timestamp` is earlier than the
+    /// previous timestamp.
+    pub fn check(
+        &mut self,
+        timestamp: f64,
+        domain: Rs2TimestampDomain,
+    ) -> Result<(), TimestampViolation> {
+        let previous = self.last.replace((timestamp, domain));
+        let violation = match previous {
+            Some((_, previous_domain)) if previous_domain != domain => {
+                TimestampViolation::DomainChanged {
+                    previous: previous_domain,
+                    current: domain,
+                }
+            }
+            Some((previous, _)) if timestamp < previous => TimestampViolation::Backwards {
+                previous,
+                current: timestamp,
+                domain,
+            },
+            _ => return Ok(()),
+        };
+
+        self.violations += 1;
+        log::warn!("{}", violation);
+        Err(violation)
+    }
+
+    /// Record the timestamp of `frame`, and check it against the previous one.
+    ///
+    /// # Errors
+    ///
+    /// See [`check`](MonotonicClock::check).
+    pub fn check_frame<F: FrameEx>(&mut self, frame: &F) -> Result<(), TimestampViolation> {
+        self.check(frame.timestamp(), frame.timestamp_domain())
+    }
+
+    /// Get the most recent timestamp and its domain, if any.
+    pub fn last_timestamp(&self) -> Option<(f64, Rs2TimestampDomain)> {
+        self.last
+    }
+
+    /// Get the number of violations seen since construction or the last reset.
+    pub fn violations(&self) -> usize {
+        self.violations
+    }
+
+    /// Forget all timestamps and violations seen so far.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backwards_timestamps_and_domain_changes_are_violations() {
+        let mut clock = MonotonicClock::new();
+        assert!(clock.check(10.0, Rs2TimestampDomain::GlobalTime).is_ok());
+        assert!(clock.check(10.0, Rs2TimestampDomain::GlobalTime).is_ok());
+        assert_eq!(
+            clock.check(5.0, Rs2TimestampDomain::GlobalTime),
+            Err(TimestampViolation::Backwards {
+                previous: 10.0,
+                current: 5.0,
+                domain: Rs2TimestampDomain::GlobalTime,
+            })
+        );
+        // The clock carries on from the offending timestamp.
+        assert!(clock.check(6.0, Rs2TimestampDomain::GlobalTime).is_ok());
+        assert_eq!(
+            clock.check(100.0, Rs2TimestampDomain::SystemTime),
+            Err(TimestampViolation::DomainChanged {
+                previous: Rs2TimestampDomain::GlobalTime,
+                current: Rs2TimestampDomain::SystemTime,
+            })
+        );
+        assert_eq!(clock.violations(), 2);
+
+        clock.reset();
+        assert_eq!(clock.last_timestamp(), None);
+        assert_eq!(clock.violations(), 0);
+    }
+}
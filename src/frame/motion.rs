@@ -5,7 +5,10 @@
 //!
 //! See the docs for [MotionFrame::motion] for more.
 
-use super::prelude::{CouldNotGetFrameSensorError, FrameCategory, FrameConstructionError, FrameEx};
+use super::prelude::{
+    copy_frame_data, hash_frame_data, CouldNotGetFrameSensorError, FrameCategory,
+    FrameConstructionError, FrameEx,
+};
 use crate::{
     check_rs2_error,
     kind::{Rs2Extension, Rs2FrameMetadata, Rs2StreamKind, Rs2TimestampDomain},
@@ -237,6 +240,23 @@ impl<K> FrameEx for MotionFrame<K> {
         }
     }
 
+    fn to_owned_bytes(&self) -> Result<(Vec<u8>, StreamProfile)> {
+        let bytes = unsafe { copy_frame_data(self.frame_ptr)? };
+        Ok((bytes, self.frame_stream_profile.try_clone()?))
+    }
+
+    fn content_hash(&self) -> u64 {
+        unsafe { hash_frame_data(self.frame_ptr) }
+    }
+
+    fn keep(&self) {
+        unsafe { sys::rs2_keep_frame(self.frame_ptr.as_ptr()) }
+    }
+
+    fn as_raw(&self) -> *const sys::rs2_frame {
+        self.frame_ptr.as_ptr()
+    }
+
     unsafe fn get_owned_raw(mut self) -> NonNull<sys::rs2_frame> {
         self.should_drop = false;
 
@@ -244,6 +264,22 @@ impl<K> FrameEx for MotionFrame<K> {
     }
 }
 
+impl<K> MotionFrame<K> {
+    /// Column names for the rows produced by [`to_csv_row`](MotionFrame::to_csv_row).
+    pub const CSV_HEADER: &'static str = "timestamp,frame_number,x,y,z";
+
+    /// Formats the frame as a single CSV row, with columns in the order given by
+    /// [`CSV_HEADER`](MotionFrame::CSV_HEADER).
+    ///
+    /// Units of the motion values depend on the kind of frame, see
+    /// [`acceleration`](AccelFrame::acceleration) and
+    /// [`rotational_velocity`](GyroFrame::rotational_velocity).
+    pub fn to_csv_row(&self) -> String {
+        let [x, y, z] = self.motion;
+        format!("{},{},{},{},{}", self.timestamp, self.frame_number, x, y, z)
+    }
+}
+
 impl AccelFrame {
     /// Returns a 3-item array representing the sensor motion recorded in the Accel frame.
     ///
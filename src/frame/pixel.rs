@@ -94,6 +94,11 @@ pub enum PixelKind<'a> {
         /// Distance from camera origin in metres
         distance: &'a f32,
     },
+    /// 16-bit fixed-point disparity values. Depth->Disparity conversion : Disparity = Baseline*FocalLength/Depth.
+    Disparity16 {
+        /// The disparity relative to the opposite eye in a depth camera, in fixed-point units.
+        disparity: &'a u16,
+    },
     /// 32-bit float-point disparity values. Depth->Disparity conversion : Disparity = Baseline*FocalLength/Depth.
     Disparity32 {
         /// The disparity relative to the opposite eye in a depth camera.
@@ -280,6 +285,16 @@ pub(crate) unsafe fn get_pixel<'a>(
                 distance: slice.get_unchecked(offset),
             }
         }
+        Rs2Format::Disparity16 => {
+            let size = data_size_in_bytes / std::mem::size_of::<u16>();
+            let stride = stride_in_bytes / std::mem::size_of::<u16>();
+            let slice = slice::from_raw_parts(data.cast::<u16>(), size);
+            let offset = (row * stride) + col;
+
+            PixelKind::Disparity16 {
+                disparity: slice.get_unchecked(offset),
+            }
+        }
         Rs2Format::Disparity32 => {
             let size = data_size_in_bytes / std::mem::size_of::<f32>();
             let stride = stride_in_bytes / std::mem::size_of::<f32>();
@@ -307,3 +322,38 @@ pub(crate) unsafe fn get_pixel<'a>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disparity_pixels_are_read_at_stride() {
+        // Two rows of two pixels, each row padded by one pixel.
+        let disparity16: [u16; 6] = [1, 2, 0, 3, 4, 0];
+        let pixel = unsafe {
+            get_pixel(
+                Rs2Format::Disparity16,
+                std::mem::size_of_val(&disparity16),
+                disparity16.as_ptr().cast::<c_void>(),
+                3 * std::mem::size_of::<u16>(),
+                1,
+                1,
+            )
+        };
+        assert!(matches!(pixel, PixelKind::Disparity16 { disparity: &4 }));
+
+        let disparity32: [f32; 6] = [1.0, 2.0, 0.0, 3.0, 4.0, 0.0];
+        let pixel = unsafe {
+            get_pixel(
+                Rs2Format::Disparity32,
+                std::mem::size_of_val(&disparity32),
+                disparity32.as_ptr().cast::<c_void>(),
+                3 * std::mem::size_of::<f32>(),
+                0,
+                1,
+            )
+        };
+        assert!(matches!(pixel, PixelKind::Disparity32 { disparity } if *disparity == 3.0));
+    }
+}
@@ -2,7 +2,10 @@
 //!
 //! A Points frame is a RealSense point cloud storage class.
 
-use super::prelude::{CouldNotGetFrameSensorError, FrameCategory, FrameConstructionError, FrameEx};
+use super::prelude::{
+    copy_frame_data, hash_frame_data, CouldNotGetFrameSensorError, FrameCategory,
+    FrameConstructionError, FrameEx,
+};
 use crate::{
     check_rs2_error,
     kind::{Rs2Extension, Rs2FrameMetadata, Rs2StreamKind, Rs2TimestampDomain},
@@ -131,6 +134,23 @@ impl FrameEx for PointsFrame {
         }
     }
 
+    fn to_owned_bytes(&self) -> Result<(Vec<u8>, StreamProfile)> {
+        let bytes = unsafe { copy_frame_data(self.frame_ptr)? };
+        Ok((bytes, self.frame_stream_profile.try_clone()?))
+    }
+
+    fn content_hash(&self) -> u64 {
+        unsafe { hash_frame_data(self.frame_ptr) }
+    }
+
+    fn keep(&self) {
+        unsafe { sys::rs2_keep_frame(self.frame_ptr.as_ptr()) }
+    }
+
+    fn as_raw(&self) -> *const sys::rs2_frame {
+        self.frame_ptr.as_ptr()
+    }
+
     unsafe fn get_owned_raw(mut self) -> NonNull<sys::rs2_frame> {
         self.should_drop = false;
 
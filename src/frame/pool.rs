@@ -0,0 +1,122 @@
+//! A pool of reusable byte buffers for copying frame data out of librealsense2.
+//!
+//! Frames are owned by librealsense2 and are released back to it once dropped, so keeping data
+//! around for longer means copying it. At high framerates allocating a new buffer for every frame
+//! adds up; a [`FramePool`] hands out buffers that return to the pool when dropped, so that a
+//! steady-state capture loop performs no allocations at all.
+
+use std::{
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex},
+};
+
+/// A pool of byte buffers that can be reused across frames.
+///
+/// Cloning a pool is cheap, and all clones share the same set of buffers.
+#[derive(Debug, Clone, Default)]
+pub struct FramePool {
+    /// The buffers that are currently available for reuse.
+    buffers: Arc<Mutex<Vec<Vec<u8>>>>,
+}
+
+impl FramePool {
+    /// Create a new, empty pool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get a zero-filled buffer of `size` bytes.
+    ///
+    /// Reuses a buffer from the pool if one is available, preferring one that is already large
+    /// enough. A new buffer is only allocated if the pool is empty or no buffer has the capacity
+    /// for `size` bytes.
+    pub fn get_buffer(&self, size: usize) -> PooledBuffer {
+        let mut buffer = {
+            let mut buffers = self.buffers.lock().unwrap();
+            match buffers.iter().position(|b| b.capacity() >= size) {
+                Some(i) => buffers.swap_remove(i),
+                None => buffers.pop().unwrap_or_default(),
+            }
+        };
+        buffer.clear();
+        buffer.resize(size, 0);
+
+        PooledBuffer {
+            buffer,
+            pool: Arc::clone(&self.buffers),
+        }
+    }
+
+    /// Get the number of buffers currently available for reuse.
+    pub fn available(&self) -> usize {
+        self.buffers.lock().unwrap().len()
+    }
+}
+
+/// A byte buffer borrowed from a [`FramePool`].
+///
+/// The buffer is returned to the pool it came from when dropped.
+#[derive(Debug)]
+pub struct PooledBuffer {
+    /// The underlying buffer.
+    buffer: Vec<u8>,
+    /// The buffers of the pool that this buffer is returned to.
+    pool: Arc<Mutex<Vec<Vec<u8>>>>,
+}
+
+impl Drop for PooledBuffer {
+    fn drop(&mut self) {
+        let buffer = std::mem::take(&mut self.buffer);
+        if let Ok(mut buffers) = self.pool.lock() {
+            buffers.push(buffer);
+        }
+    }
+}
+
+impl Deref for PooledBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.buffer
+    }
+}
+
+impl DerefMut for PooledBuffer {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.buffer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffers_return_to_pool_on_drop() {
+        let pool = FramePool::new();
+        assert_eq!(pool.available(), 0);
+
+        let buffer = pool.get_buffer(16);
+        assert_eq!(buffer.len(), 16);
+        assert_eq!(pool.available(), 0);
+
+        drop(buffer);
+        assert_eq!(pool.available(), 1);
+    }
+
+    #[test]
+    fn buffers_are_reused() {
+        let pool = FramePool::new();
+
+        let ptr = {
+            let mut buffer = pool.get_buffer(16);
+            buffer[0] = 1;
+            buffer.as_ptr()
+        };
+
+        let buffer = pool.get_buffer(8);
+        assert_eq!(buffer.as_ptr(), ptr);
+        assert_eq!(buffer.len(), 8);
+        assert!(buffer.iter().all(|b| *b == 0));
+    }
+}
@@ -4,7 +4,10 @@
 //! at a point in time. See the member and function declarations for how these values are stored
 //! and retrieved.
 
-use super::prelude::{CouldNotGetFrameSensorError, FrameCategory, FrameConstructionError, FrameEx};
+use super::prelude::{
+    copy_frame_data, hash_frame_data, CouldNotGetFrameSensorError, FrameCategory,
+    FrameConstructionError, FrameEx,
+};
 use crate::{
     check_rs2_error,
     kind::{Rs2Extension, Rs2FrameMetadata, Rs2StreamKind, Rs2TimestampDomain},
@@ -52,7 +55,40 @@ pub enum Confidence {
     High,
 }
 
+impl Confidence {
+    /// Get the tracker confidence of the raw pose.
+    fn of_tracker(pose: &sys::rs2_pose) -> Self {
+        Self::from_raw(pose.tracker_confidence)
+    }
+
+    /// Get the mapper confidence of the raw pose.
+    fn of_mapper(pose: &sys::rs2_pose) -> Self {
+        Self::from_raw(pose.mapper_confidence)
+    }
+
+    /// Convert a raw confidence value reported by librealsense2.
+    fn from_raw(value: std::os::raw::c_uint) -> Self {
+        match value {
+            0x0 => Confidence::Failed,
+            0x1 => Confidence::Low,
+            0x2 => Confidence::Medium,
+            0x3 => Confidence::High,
+            _ => panic!("Unknown confidence, please report a bug!"),
+        }
+    }
+}
+
 impl PoseFrame {
+    /// Column names for the rows produced by [`to_csv_row`](PoseFrame::to_csv_row).
+    pub const CSV_HEADER: &'static str = "timestamp,frame_number,\
+        translation_x,translation_y,translation_z,\
+        velocity_x,velocity_y,velocity_z,\
+        acceleration_x,acceleration_y,acceleration_z,\
+        rotation_i,rotation_j,rotation_k,rotation_r,\
+        angular_velocity_x,angular_velocity_y,angular_velocity_z,\
+        angular_acceleration_x,angular_acceleration_y,angular_acceleration_z,\
+        tracker_confidence,mapper_confidence";
+
     /// X, Y, Z values of translation, in meters (relative to initial position)
     pub fn translation(&self) -> [f32; 3] {
         let sys::rs2_vector { x, y, z } = self.data.translation;
@@ -90,25 +126,44 @@ impl PoseFrame {
     }
 
     /// Pose confidence from [`Confidence::Failed`] to [`Confidence::High`]
+    ///
+    /// The tracker reports loss of tracking as [`Confidence::Failed`] rather than as a separate
+    /// event. Relocalizations are reported as events through
+    /// [`PoseSensor::set_event_callback`](crate::sensor::PoseSensor::set_event_callback).
     pub fn tracker_confidence(&self) -> Confidence {
-        match self.data.tracker_confidence {
-            0x0 => Confidence::Failed,
-            0x1 => Confidence::Low,
-            0x2 => Confidence::Medium,
-            0x3 => Confidence::High,
-            _ => panic!("Unknown confidence, please report a bug!"),
-        }
+        Confidence::of_tracker(&self.data)
     }
 
     /// Pose map confidence from [`Confidence::Failed`] to [`Confidence::High`]
     pub fn mapper_confidence(&self) -> Confidence {
-        match self.data.tracker_confidence {
-            0x0 => Confidence::Failed,
-            0x1 => Confidence::Low,
-            0x2 => Confidence::Medium,
-            0x3 => Confidence::High,
-            _ => panic!("Unknown confidence, please report a bug!"),
-        }
+        Confidence::of_mapper(&self.data)
+    }
+
+    /// Formats the frame as a single CSV row, with columns in the order given by
+    /// [`CSV_HEADER`](PoseFrame::CSV_HEADER).
+    ///
+    /// Confidences are written as their raw values, from 0 (failed) to 3 (high).
+    pub fn to_csv_row(&self) -> String {
+        let values = self
+            .translation()
+            .iter()
+            .chain(self.velocity().iter())
+            .chain(self.acceleration().iter())
+            .chain(self.rotation().iter())
+            .chain(self.angular_velocity().iter())
+            .chain(self.angular_acceleration().iter())
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        format!(
+            "{},{},{},{},{}",
+            self.timestamp,
+            self.frame_number,
+            values,
+            self.data.tracker_confidence,
+            self.data.mapper_confidence
+        )
     }
 }
 
@@ -267,6 +322,23 @@ impl FrameEx for PoseFrame {
         }
     }
 
+    fn to_owned_bytes(&self) -> Result<(Vec<u8>, StreamProfile)> {
+        let bytes = unsafe { copy_frame_data(self.frame_ptr)? };
+        Ok((bytes, self.frame_stream_profile.try_clone()?))
+    }
+
+    fn content_hash(&self) -> u64 {
+        unsafe { hash_frame_data(self.frame_ptr) }
+    }
+
+    fn keep(&self) {
+        unsafe { sys::rs2_keep_frame(self.frame_ptr.as_ptr()) }
+    }
+
+    fn as_raw(&self) -> *const sys::rs2_frame {
+        self.frame_ptr.as_ptr()
+    }
+
     unsafe fn get_owned_raw(mut self) -> NonNull<sys::rs2_frame> {
         self.should_drop = false;
 
@@ -282,4 +354,31 @@ mod tests {
     fn frame_has_correct_kind() {
         assert_eq!(PoseFrame::kind(), Rs2StreamKind::Pose);
     }
+
+    #[test]
+    fn mapper_confidence_is_read_from_the_mapper() {
+        let vector = sys::rs2_vector {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        let pose = sys::rs2_pose {
+            translation: vector,
+            velocity: vector,
+            acceleration: vector,
+            rotation: sys::rs2_quaternion {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+                w: 1.0,
+            },
+            angular_velocity: vector,
+            angular_acceleration: vector,
+            tracker_confidence: 0x1,
+            mapper_confidence: 0x3,
+        };
+
+        assert!(matches!(Confidence::of_tracker(&pose), Confidence::Low));
+        assert!(matches!(Confidence::of_mapper(&pose), Confidence::High));
+    }
 }
@@ -5,7 +5,10 @@
 //! with the wildcard describing the specialization that goes with that type.
 
 use crate::{
-    kind::{Rs2Exception, Rs2Extension, Rs2FrameMetadata, Rs2StreamKind, Rs2TimestampDomain},
+    check_rs2_error,
+    kind::{
+        Rs2Exception, Rs2Extension, Rs2Format, Rs2FrameMetadata, Rs2StreamKind, Rs2TimestampDomain,
+    },
     sensor::Sensor,
     stream_profile::StreamProfile,
 };
@@ -13,6 +16,7 @@ use anyhow::Result;
 use realsense_sys as sys;
 use std::ptr::NonNull;
 use thiserror::Error;
+use xxhash_rust::xxh3::xxh3_64;
 
 /// How many bits are in a byte? Who can truly say.
 pub const BITS_PER_BYTE: i32 = 8;
@@ -51,9 +55,39 @@ pub enum FrameConstructionError {
     /// Could not get the data of the frame.
     #[error("Could not get pointer to frame data. Type: {0}; Reason: {1}")]
     CouldNotGetData(Rs2Exception, String),
+    /// Could not add a reference to the frame.
+    #[error("Could not add a reference to the frame. Type: {0}; Reason: {1}")]
+    CouldNotAddReference(Rs2Exception, String),
     /// Could not get the number of points in a Points frame.
     #[error("Could not get number of points: Type: {0}; Reason: {1}")]
     CouldNotGetPointCount(Rs2Exception, String),
+    /// The frame data is smaller than its dimensions require, e.g. a frame truncated during USB
+    /// transfer.
+    #[error("Frame data is truncated. Expected at least {expected} bytes, got {actual} bytes.")]
+    DataSizeMismatch {
+        /// The number of bytes required by the frame's stride and height.
+        expected: usize,
+        /// The number of bytes actually reported for the frame data.
+        actual: usize,
+    },
+    /// The frame data is in a format that the requested frame type cannot hold, e.g. a color
+    /// frame routed into a depth-only processing block.
+    #[error("Frame of type {extension:?} cannot hold data in format {format:?}.")]
+    UnsupportedFormat {
+        /// The extension of the frame type that was requested.
+        extension: Rs2Extension,
+        /// The format of the frame data.
+        format: Rs2Format,
+    },
+    /// The frame is a video frame without any pixels, e.g. a malformed frame from a device or a
+    /// recording.
+    #[error("Video frame has no pixels. Width: {width}; Height: {height}")]
+    ZeroDimensions {
+        /// The width reported for the frame, in pixels.
+        width: usize,
+        /// The height reported for the frame, in pixels.
+        height: usize,
+    },
 }
 
 /// Occurs when certain data cannot be derived from a Depth frame.
@@ -65,6 +99,18 @@ pub enum DepthError {
     /// Cannot derive the depth units used.
     #[error("Could not get depth units. Type: {0}; Reason: {1}")]
     CouldNotGetDepthUnits(Rs2Exception, String),
+    /// The requested pixel lies outside of the frame.
+    #[error("Pixel ({col}, {row}) is outside of the {width}x{height} frame.")]
+    PixelOutOfBounds {
+        /// The requested column.
+        col: usize,
+        /// The requested row.
+        row: usize,
+        /// The width of the frame, in pixels.
+        width: usize,
+        /// The height of the frame, in pixels.
+        height: usize,
+    },
 }
 
 /// Occurs when a baseline cannot be derived from a Disparity frame.
@@ -80,6 +126,10 @@ pub struct CouldNotGetFrameSensorError(pub Rs2Exception, pub String);
 /// Describes common functionality across frame types.
 pub trait FrameEx {
     /// Get the stream profile associated with the frame.
+    ///
+    /// The profile describes the stream kind, format, index and framerate of the frame, and
+    /// (for video streams) its [resolution](StreamProfile::resolution). This is how a frame
+    /// extracted generically from a composite frame can be interpreted correctly.
     fn stream_profile(&self) -> &StreamProfile;
 
     /// Get the sensor associated with the frame.
@@ -97,11 +147,90 @@ pub trait FrameEx {
     /// Get frame metadata.
     ///
     /// Returns `None` if the `metadata_kind` is not supported by the frame type.
+    ///
+    /// Metadata is never copied out of the frame up front, so frames whose metadata is never read
+    /// do not pay for it. Instead, every call asks librealsense2 for the one value requested, which
+    /// costs two calls across the FFI boundary (`rs2_supports_frame_metadata` and
+    /// `rs2_get_frame_metadata`), each decoding the value from the metadata attached to the frame.
+    /// That is cheap next to the frame data itself, but callers that need the same value more than
+    /// once per frame should keep it rather than query it again.
     fn metadata(&self, metadata_kind: Rs2FrameMetadata) -> Option<std::os::raw::c_longlong>;
 
     /// Test whether the metadata arguemnt is supported by the frame.
     fn supports_metadata(&self, metadata_kind: Rs2FrameMetadata) -> bool;
 
+    /// Get the HDR sequence id of the frame.
+    ///
+    /// In HDR mode, consecutive frames are captured with the different exposures of a sequence,
+    /// and this identifies which exposure the frame was captured with. Returns `None` if the frame
+    /// does not carry [`Rs2FrameMetadata::SequenceIdentifier`] metadata, e.g. because HDR is off.
+    fn sequence_id(&self) -> Option<u32> {
+        self.metadata(Rs2FrameMetadata::SequenceIdentifier)
+            .map(|id| id as u32)
+    }
+
+    /// Get the offset in milliseconds from the timestamp of the frame to the host clock.
+    ///
+    /// Adding this to [`timestamp`](FrameEx::timestamp) gives the time of the frame on the host
+    /// clock of [`host_time`](crate::base::host_time). Frames in the
+    /// [`SystemTime`](Rs2TimestampDomain::SystemTime) and
+    /// [`GlobalTime`](Rs2TimestampDomain::GlobalTime) domains are already on the host clock, so
+    /// the offset is zero. For frames on the [`HardwareClock`](Rs2TimestampDomain::HardwareClock)
+    /// the offset is estimated from the time the frame arrived at the host, so it also includes the
+    /// transport latency of the frame; prefer global time, which librealsense2 corrects for
+    /// latency, when it is available.
+    ///
+    /// Returns `None` for hardware clock frames that do not carry
+    /// [`Rs2FrameMetadata::TimeOfArrival`] metadata.
+    fn host_clock_offset(&self) -> Option<f64> {
+        match self.timestamp_domain() {
+            Rs2TimestampDomain::SystemTime | Rs2TimestampDomain::GlobalTime => Some(0.0),
+            Rs2TimestampDomain::HardwareClock => {
+                let arrival = self.metadata(Rs2FrameMetadata::TimeOfArrival)?;
+                Some(arrival as f64 - self.timestamp())
+            }
+        }
+    }
+
+    /// Copy the frame data and stream profile into owned values.
+    ///
+    /// The returned bytes and stream profile hold no reference to the underlying frame, so they can
+    /// be sent to another thread (e.g. over a [`std::sync::mpsc`] channel) and kept for as long as
+    /// needed, even after the pipeline has been stopped. The bytes are the raw frame data, and have
+    /// to be interpreted according to the format of the stream profile.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameConstructionError::CouldNotGetDataSize`] or
+    /// [`FrameConstructionError::CouldNotGetData`] if the frame data cannot be read.
+    ///
+    /// Returns [`StreamConstructionError`](crate::stream_profile::StreamConstructionError) if the
+    /// stream profile cannot be copied.
+    fn to_owned_bytes(&self) -> Result<(Vec<u8>, StreamProfile)>;
+
+    /// Compute a hash of the frame data.
+    ///
+    /// The hash is computed with xxHash (XXH3, 64 bits) directly over the frame buffer, without
+    /// copying it. Frames with the same contents hash to the same value regardless of their
+    /// stream profile or timestamp, so this is a cheap way to compare e.g. recorded frames
+    /// against the same frames played back, or to find duplicate frames. Frames whose data cannot
+    /// be read hash as if they were empty.
+    fn content_hash(&self) -> u64;
+
+    /// Keep the frame alive outside of the frame pool of its stream.
+    ///
+    /// librealsense2 recycles frames from a fixed-size pool, and drops incoming frames while every
+    /// frame in the pool is still held. Frames that are kept for a while, e.g. a history of past
+    /// frames, should be marked with this so that they no longer count against the pool.
+    fn keep(&self);
+
+    /// Get the underlying frame pointer for this frame, without taking ownership of it.
+    ///
+    /// This is an escape hatch for calling `realsense-sys` functions that this crate does not
+    /// wrap yet. The pointer is only valid for as long as `self` is alive, and must not be
+    /// released; use [`get_owned_raw`](FrameEx::get_owned_raw) to take ownership instead.
+    fn as_raw(&self) -> *const sys::rs2_frame;
+
     /// Get (and own) the underlying frame pointer for this frame.
     ///
     /// This is primarily useful for passing this frame forward to a processing block or blocks
@@ -115,6 +244,51 @@ pub trait FrameEx {
     unsafe fn get_owned_raw(self) -> NonNull<sys::rs2_frame>;
 }
 
+/// Borrow the data held by an `rs2_frame`.
+///
+/// # Safety
+///
+/// `frame_ptr` must point to a valid frame for the lifetime `'a`.
+unsafe fn frame_data<'a>(
+    frame_ptr: NonNull<sys::rs2_frame>,
+) -> Result<&'a [u8], FrameConstructionError> {
+    let mut err = std::ptr::null_mut::<sys::rs2_error>();
+    let size = sys::rs2_get_frame_data_size(frame_ptr.as_ptr(), &mut err);
+    check_rs2_error!(err, FrameConstructionError::CouldNotGetDataSize)?;
+
+    let data_ptr = sys::rs2_get_frame_data(frame_ptr.as_ptr(), &mut err);
+    check_rs2_error!(err, FrameConstructionError::CouldNotGetData)?;
+
+    if data_ptr.is_null() || size <= 0 {
+        return Ok(&[]);
+    }
+
+    Ok(std::slice::from_raw_parts(
+        data_ptr.cast::<u8>(),
+        size as usize,
+    ))
+}
+
+/// Copy the data held by an `rs2_frame` into an owned buffer.
+///
+/// # Safety
+///
+/// `frame_ptr` must point to a valid frame for the duration of this call.
+pub(crate) unsafe fn copy_frame_data(
+    frame_ptr: NonNull<sys::rs2_frame>,
+) -> Result<Vec<u8>, FrameConstructionError> {
+    frame_data(frame_ptr).map(<[u8]>::to_vec)
+}
+
+/// Hash the data held by an `rs2_frame`, as described by [`FrameEx::content_hash`].
+///
+/// # Safety
+///
+/// `frame_ptr` must point to a valid frame for the duration of this call.
+pub(crate) unsafe fn hash_frame_data(frame_ptr: NonNull<sys::rs2_frame>) -> u64 {
+    xxh3_64(frame_data(frame_ptr).unwrap_or(&[]))
+}
+
 /// A trait for specifying which runtime stream kinds can be held within a frame type
 ///
 /// This trait changes some of the semantics for how to think about librealsense2 frames. The
@@ -137,4 +311,12 @@ pub trait FrameCategory {
 
     /// Predicate for checking if the RS2 frame's stream has the same kind as the frame category.
     fn has_correct_kind(&self) -> bool;
+
+    /// Predicate for checking if the frame category can hold data in the given format.
+    ///
+    /// Categories whose data accessors assume a particular layout override this; all others accept
+    /// any format.
+    fn supports_format(_format: Rs2Format) -> bool {
+        true
+    }
 }
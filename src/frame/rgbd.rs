@@ -0,0 +1,225 @@
+//! Fusion of aligned color and depth frames into a single RGB-D image.
+//!
+//! Many perception models take a single RGB-D image as input. Assembling one from
+//! [`PixelKind`] iteration on every frame is both slow and easy to get wrong, so
+//! [`fuse_rgbd`] does this once, producing tightly packed arrays.
+
+use super::{image::ColorFrame, image::DepthFrame, pixel::PixelKind, prelude::FrameEx};
+use crate::kind::Rs2Format;
+use anyhow::Result;
+use thiserror::Error;
+
+/// Errors that can occur when fusing color and depth frames.
+#[derive(Error, Debug, PartialEq)]
+pub enum RgbdError {
+    /// The color and depth frames have different dimensions, e.g. because they were not aligned.
+    #[error("Color frame is {color_width}x{color_height} but depth frame is {depth_width}x{depth_height}. Align the frames first.")]
+    DimensionMismatch {
+        /// The width of the color frame.
+        color_width: usize,
+        /// The height of the color frame.
+        color_height: usize,
+        /// The width of the depth frame.
+        depth_width: usize,
+        /// The height of the depth frame.
+        depth_height: usize,
+    },
+    /// The color frame is not in an RGB or BGR format.
+    #[error("Unsupported color format: {0:?}")]
+    UnsupportedColorFormat(Rs2Format),
+    /// The depth frame is not in the Z16 format.
+    #[error("Unsupported depth format: {0:?}")]
+    UnsupportedDepthFormat(Rs2Format),
+}
+
+/// An RGB-D image assembled from aligned color and depth frames.
+///
+/// Color and depth are stored as separate, tightly packed, row-major arrays. Use
+/// [`to_interleaved`](RgbdImage::to_interleaved) for a single interleaved buffer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RgbdImage {
+    /// The width of the image in pixels.
+    pub width: usize,
+    /// The height of the image in pixels.
+    pub height: usize,
+    /// The color of each pixel, as `[r, g, b]` triplets.
+    pub rgb: Vec<u8>,
+    /// The depth of each pixel in meters, with `0.0` meaning there is no depth data.
+    pub depth: Vec<f32>,
+}
+
+impl RgbdImage {
+    /// Interleave the color and depth into `[r, g, b, d]` values for each pixel.
+    ///
+    /// Color channels are scaled to `[0, 1]`, while depth stays in meters.
+    pub fn to_interleaved(&self) -> Vec<f32> {
+        self.rgb
+            .chunks_exact(3)
+            .zip(self.depth.iter())
+            .flat_map(|(rgb, d)| {
+                [
+                    rgb[0] as f32 / 255.0,
+                    rgb[1] as f32 / 255.0,
+                    rgb[2] as f32 / 255.0,
+                    *d,
+                ]
+            })
+            .collect()
+    }
+}
+
+/// Fuse a color frame and a depth frame into a single [`RgbdImage`].
+///
+/// The frames must be aligned to one another (see
+/// [`Align`](crate::processing_blocks::align::Align)) so that pixels at the same coordinates
+/// correspond. The color frame may be in any of the RGB(A) or BGR(A) formats, and the depth frame
+/// must be in [`Rs2Format::Z16`].
+///
+/// # Errors
+///
+/// Returns [`RgbdError::DimensionMismatch`] if the frames have different dimensions.
+///
+/// Returns [`RgbdError::UnsupportedColorFormat`] or [`RgbdError::UnsupportedDepthFormat`] if
+/// either frame is not in a supported format.
+///
+/// Returns an error if the [depth units](DepthFrame::depth_units) of the depth frame cannot be
+/// determined.
+pub fn fuse_rgbd(color: &ColorFrame, depth: &DepthFrame) -> Result<RgbdImage> {
+    if color.width() != depth.width() || color.height() != depth.height() {
+        return Err(RgbdError::DimensionMismatch {
+            color_width: color.width(),
+            color_height: color.height(),
+            depth_width: depth.width(),
+            depth_height: depth.height(),
+        }
+        .into());
+    }
+
+    let color_format = color.stream_profile().format();
+    if !matches!(
+        color_format,
+        Rs2Format::Rgb8 | Rs2Format::Rgba8 | Rs2Format::Bgr8 | Rs2Format::Bgra8
+    ) {
+        return Err(RgbdError::UnsupportedColorFormat(color_format).into());
+    }
+
+    let depth_format = depth.stream_profile().format();
+    if depth_format != Rs2Format::Z16 {
+        return Err(RgbdError::UnsupportedDepthFormat(depth_format).into());
+    }
+
+    let units = depth.depth_units()?;
+
+    let mut rgb = Vec::with_capacity(color.width() * color.height() * 3);
+    for pixel in color.iter() {
+        rgb.extend_from_slice(&rgb_of(&pixel));
+    }
+
+    let depth_m = match depth.depth_data() {
+        Some(data) => data.iter().map(|d| *d as f32 * units).collect(),
+        None => depth
+            .iter()
+            .map(|pixel| match pixel {
+                PixelKind::Z16 { depth } => *depth as f32 * units,
+                _ => 0.0,
+            })
+            .collect(),
+    };
+
+    Ok(RgbdImage {
+        width: color.width(),
+        height: color.height(),
+        rgb,
+        depth: depth_m,
+    })
+}
+
+/// Get the `[r, g, b]` channels of a color pixel, or black if the pixel is not an RGB(A) or BGR(A)
+/// pixel.
+fn rgb_of(pixel: &PixelKind) -> [u8; 3] {
+    match pixel {
+        PixelKind::Rgb8 { r, g, b }
+        | PixelKind::Rgba8 { r, g, b, .. }
+        | PixelKind::Bgr8 { r, g, b }
+        | PixelKind::Bgra8 { r, g, b, .. } => [**r, **g, **b],
+        _ => [0, 0, 0],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        frame::prelude::FrameCategory,
+        kind::{Rs2Extension, Rs2StreamKind},
+    };
+
+    #[test]
+    fn fused_frames_have_correct_category() {
+        assert_eq!(ColorFrame::extension(), Rs2Extension::VideoFrame);
+        assert_eq!(ColorFrame::kind(), Rs2StreamKind::Color);
+        assert_eq!(DepthFrame::extension(), Rs2Extension::DepthFrame);
+        assert_eq!(DepthFrame::kind(), Rs2StreamKind::Depth);
+    }
+
+    #[test]
+    fn bgr_pixels_are_reordered_to_rgb() {
+        let (r, g, b, a) = (1, 2, 3, 255);
+
+        assert_eq!(
+            rgb_of(&PixelKind::Rgb8 {
+                r: &r,
+                g: &g,
+                b: &b
+            }),
+            [1, 2, 3]
+        );
+        assert_eq!(
+            rgb_of(&PixelKind::Rgba8 {
+                r: &r,
+                g: &g,
+                b: &b,
+                a: &a
+            }),
+            [1, 2, 3]
+        );
+        assert_eq!(
+            rgb_of(&PixelKind::Bgr8 {
+                b: &b,
+                g: &g,
+                r: &r
+            }),
+            [1, 2, 3]
+        );
+        assert_eq!(
+            rgb_of(&PixelKind::Bgra8 {
+                b: &b,
+                g: &g,
+                r: &r,
+                a: &a
+            }),
+            [1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn other_pixels_are_black() {
+        let depth = 1000;
+        assert_eq!(rgb_of(&PixelKind::Z16 { depth: &depth }), [0, 0, 0]);
+    }
+
+    #[test]
+    fn interleaves_scaled_color_with_depth() {
+        let image = RgbdImage {
+            width: 2,
+            height: 1,
+            rgb: vec![255, 0, 51, 0, 255, 0],
+            depth: vec![1.5, 0.0],
+        };
+
+        assert_eq!(
+            image.to_interleaved(),
+            vec![1.0, 0.0, 0.2, 1.5, 0.0, 1.0, 0.0, 0.0]
+        );
+    }
+}
@@ -0,0 +1,265 @@
+//! A buffer holding the most recent frames of a stream.
+//!
+//! Temporal algorithms such as optical flow or frame differencing need a short history of frames.
+//! Frames are reference counted by librealsense2, so holding on to them does not copy their data,
+//! but frames held for longer than a single iteration have to be taken out of the frame pool of
+//! their stream (see [`FrameEx::keep`]) or the stream stalls once the pool runs out.
+//! [`RollingFrameBuffer`] takes care of this.
+
+use super::prelude::FrameEx;
+use std::collections::{vec_deque, VecDeque};
+
+/// A buffer holding the last `capacity` frames pushed into it.
+///
+/// Frames are held from oldest to newest. Once the buffer is full, pushing a new frame evicts the
+/// oldest one, which releases it back to librealsense2 unless it is used by the caller.
+#[derive(Debug)]
+pub struct RollingFrameBuffer<F> {
+    /// The frames held by the buffer, from oldest to newest.
+    frames: VecDeque<F>,
+    /// The number of frames the buffer holds before it evicts the oldest.
+    capacity: usize,
+}
+
+impl<F> RollingFrameBuffer<F>
+where
+    F: FrameEx,
+{
+    /// Create an empty buffer holding up to `capacity` frames.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(
+            capacity > 0,
+            "A rolling frame buffer must hold at least one frame."
+        );
+        Self {
+            frames: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Add `frame` as the newest frame of the buffer.
+    ///
+    /// Returns the oldest frame if the buffer was full and it had to be evicted.
+    pub fn push(&mut self, frame: F) -> Option<F> {
+        frame.keep();
+
+        let evicted = if self.frames.len() == self.capacity {
+            self.frames.pop_front()
+        } else {
+            None
+        };
+        self.frames.push_back(frame);
+        evicted
+    }
+
+    /// Get the frame pushed `age` frames before the newest one.
+    ///
+    /// An `age` of `0` gives the newest frame. Returns `None` if the buffer holds `age` frames or
+    /// fewer.
+    pub fn get(&self, age: usize) -> Option<&F> {
+        let index = self.frames.len().checked_sub(age + 1)?;
+        self.frames.get(index)
+    }
+
+    /// Get the newest frame, if any.
+    pub fn latest(&self) -> Option<&F> {
+        self.frames.back()
+    }
+
+    /// Get the oldest frame, if any.
+    pub fn oldest(&self) -> Option<&F> {
+        self.frames.front()
+    }
+
+    /// Iterate over the frames of the buffer, from oldest to newest.
+    pub fn iter(&self) -> vec_deque::Iter<'_, F> {
+        self.frames.iter()
+    }
+
+    /// Get the number of frames currently held.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Predicate for whether the buffer holds no frames.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Predicate for whether the buffer holds `capacity` frames, so the next push evicts one.
+    pub fn is_full(&self) -> bool {
+        self.frames.len() == self.capacity
+    }
+
+    /// Get the number of frames the buffer holds before it evicts the oldest.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Release every frame held by the buffer.
+    pub fn clear(&mut self) {
+        self.frames.clear();
+    }
+}
+
+impl<'a, F> IntoIterator for &'a RollingFrameBuffer<F>
+where
+    F: FrameEx,
+{
+    type Item = &'a F;
+    type IntoIter = vec_deque::Iter<'a, F>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        kind::{Rs2FrameMetadata, Rs2TimestampDomain},
+        sensor::Sensor,
+        stream_profile::StreamProfile,
+    };
+    use anyhow::Result;
+    use realsense_sys as sys;
+    use std::{cell::Cell, ptr::NonNull};
+
+    /// A frame that only knows its frame number, and whether it was kept.
+    #[derive(Debug)]
+    struct TestFrame {
+        /// The frame number.
+        number: u64,
+        /// Whether [`FrameEx::keep`] was called on the frame.
+        kept: Cell<bool>,
+    }
+
+    impl TestFrame {
+        /// Create a frame with the given frame number that has not been kept.
+        fn new(number: u64) -> Self {
+            Self {
+                number,
+                kept: Cell::new(false),
+            }
+        }
+    }
+
+    impl FrameEx for TestFrame {
+        fn stream_profile(&self) -> &StreamProfile {
+            unimplemented!()
+        }
+
+        fn sensor(&self) -> Result<Sensor> {
+            unimplemented!()
+        }
+
+        fn frame_number(&self) -> u64 {
+            self.number
+        }
+
+        fn timestamp(&self) -> f64 {
+            unimplemented!()
+        }
+
+        fn timestamp_domain(&self) -> Rs2TimestampDomain {
+            unimplemented!()
+        }
+
+        fn metadata(&self, _metadata_kind: Rs2FrameMetadata) -> Option<std::os::raw::c_longlong> {
+            unimplemented!()
+        }
+
+        fn supports_metadata(&self, _metadata_kind: Rs2FrameMetadata) -> bool {
+            unimplemented!()
+        }
+
+        fn to_owned_bytes(&self) -> Result<(Vec<u8>, StreamProfile)> {
+            unimplemented!()
+        }
+
+        fn content_hash(&self) -> u64 {
+            unimplemented!()
+        }
+
+        fn keep(&self) {
+            self.kept.set(true);
+        }
+
+        fn as_raw(&self) -> *const sys::rs2_frame {
+            unimplemented!()
+        }
+
+        unsafe fn get_owned_raw(self) -> NonNull<sys::rs2_frame> {
+            unimplemented!()
+        }
+    }
+
+    /// Get the frame numbers of the frames in `buffer`, from oldest to newest.
+    fn frame_numbers(buffer: &RollingFrameBuffer<TestFrame>) -> Vec<u64> {
+        buffer.iter().map(|f| f.frame_number()).collect()
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_capacity_is_rejected() {
+        RollingFrameBuffer::<TestFrame>::new(0);
+    }
+
+    #[test]
+    fn pushed_frames_are_kept() {
+        let mut buffer = RollingFrameBuffer::new(2);
+        buffer.push(TestFrame::new(0));
+        assert!(buffer.latest().unwrap().kept.get());
+    }
+
+    #[test]
+    fn oldest_frame_is_evicted_once_full() {
+        let mut buffer = RollingFrameBuffer::new(3);
+        for number in 0..3 {
+            assert!(buffer.push(TestFrame::new(number)).is_none());
+        }
+        assert!(buffer.is_full());
+        assert_eq!(buffer.len(), buffer.capacity());
+
+        let evicted = buffer.push(TestFrame::new(3)).unwrap();
+        assert_eq!(evicted.frame_number(), 0);
+        assert_eq!(frame_numbers(&buffer), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn frames_are_looked_up_by_age() {
+        let mut buffer = RollingFrameBuffer::new(3);
+        assert!(buffer.is_empty());
+        assert!(buffer.latest().is_none());
+        assert!(buffer.oldest().is_none());
+        assert!(buffer.get(0).is_none());
+
+        for number in 0..5 {
+            buffer.push(TestFrame::new(number));
+        }
+
+        assert_eq!(buffer.latest().unwrap().frame_number(), 4);
+        assert_eq!(buffer.oldest().unwrap().frame_number(), 2);
+        assert_eq!(buffer.get(0).unwrap().frame_number(), 4);
+        assert_eq!(buffer.get(1).unwrap().frame_number(), 3);
+        assert_eq!(buffer.get(2).unwrap().frame_number(), 2);
+        assert!(buffer.get(3).is_none());
+    }
+
+    #[test]
+    fn clear_releases_every_frame() {
+        let mut buffer = RollingFrameBuffer::new(2);
+        buffer.push(TestFrame::new(0));
+        buffer.push(TestFrame::new(1));
+
+        buffer.clear();
+        assert!(buffer.is_empty());
+        assert!(!buffer.is_full());
+        assert_eq!(buffer.capacity(), 2);
+    }
+}
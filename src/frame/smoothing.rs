@@ -0,0 +1,187 @@
+//! Confidence-weighted spatial smoothing of depth frames.
+//!
+//! The spatial filter processing block smooths depth with an edge-preserving filter, but treats
+//! every valid depth value as equally trustworthy. Devices with a confidence stream, such as the
+//! L515, report how reliable each depth value is; [`smooth_depth`] uses that to weight each depth
+//! value in a local average, so that unreliable values contribute less to their neighbours.
+
+use super::{
+    image::{ConfidenceFrame, DepthFrame},
+    pixel::PixelKind,
+    prelude::FrameEx,
+};
+use crate::kind::Rs2Format;
+use anyhow::Result;
+use thiserror::Error;
+
+/// Errors that can occur when smoothing a depth frame.
+#[derive(Error, Debug, PartialEq)]
+pub enum SmoothingError {
+    /// The depth and confidence frames have different dimensions.
+    #[error("Depth frame is {depth_width}x{depth_height} but confidence frame is {confidence_width}x{confidence_height}.")]
+    DimensionMismatch {
+        /// The width of the depth frame.
+        depth_width: usize,
+        /// The height of the depth frame.
+        depth_height: usize,
+        /// The width of the confidence frame.
+        confidence_width: usize,
+        /// The height of the confidence frame.
+        confidence_height: usize,
+    },
+    /// The depth frame is not in the Z16 format.
+    #[error("Unsupported depth format: {0:?}")]
+    UnsupportedDepthFormat(Rs2Format),
+}
+
+/// Smooth a depth frame with a confidence-weighted average over a square window.
+///
+/// Each pixel with depth data is replaced by the average of the depth values in the
+/// `(2 * radius + 1) x (2 * radius + 1)` window around it, with each value weighted by its
+/// confidence. Pixels with no depth data, or with a confidence of zero, do not contribute to the
+/// average. Without a `confidence` frame every depth value is weighted equally.
+///
+/// The result is the depth of each pixel in meters, in row-major order, with `0.0` meaning there is
+/// no depth data. Holes are left as they are; use the
+/// [`HoleFilling`](crate::processing_blocks::hole_filling::HoleFilling) block to fill them.
+///
+/// # Errors
+///
+/// Returns [`SmoothingError::DimensionMismatch`] if the confidence frame does not have the
+/// dimensions of the depth frame.
+///
+/// Returns [`SmoothingError::UnsupportedDepthFormat`] if the depth frame is not in
+/// [`Rs2Format::Z16`].
+///
+/// Returns an error if the [depth units](DepthFrame::depth_units) of the depth frame cannot be
+/// determined.
+pub fn smooth_depth(
+    depth: &DepthFrame,
+    confidence: Option<&ConfidenceFrame>,
+    radius: usize,
+) -> Result<Vec<f32>> {
+    let (width, height) = (depth.width(), depth.height());
+
+    let depth_format = depth.stream_profile().format();
+    if depth_format != Rs2Format::Z16 {
+        return Err(SmoothingError::UnsupportedDepthFormat(depth_format).into());
+    }
+
+    let weights = match confidence {
+        Some(confidence) => {
+            if confidence.width() != width || confidence.height() != height {
+                return Err(SmoothingError::DimensionMismatch {
+                    depth_width: width,
+                    depth_height: height,
+                    confidence_width: confidence.width(),
+                    confidence_height: confidence.height(),
+                }
+                .into());
+            }
+            confidence
+                .iter()
+                .map(|pixel| match pixel {
+                    PixelKind::Raw8 { val } => f32::from(*val),
+                    _ => 0.0,
+                })
+                .collect()
+        }
+        None => vec![1.0; width * height],
+    };
+
+    let units = depth.depth_units()?;
+    let depth_m: Vec<f32> = match depth.depth_data() {
+        Some(data) => data.iter().map(|d| f32::from(*d) * units).collect(),
+        None => depth
+            .iter()
+            .map(|pixel| match pixel {
+                PixelKind::Z16 { depth } => f32::from(*depth) * units,
+                _ => 0.0,
+            })
+            .collect(),
+    };
+
+    Ok(weighted_box_average(
+        &depth_m, &weights, width, height, radius,
+    ))
+}
+
+/// Average `depth` over a square window of `radius` around each pixel, weighted by `weights`.
+///
+/// Pixels with a depth or weight of zero do not contribute, and pixels with no depth stay at zero.
+fn weighted_box_average(
+    depth: &[f32],
+    weights: &[f32],
+    width: usize,
+    height: usize,
+    radius: usize,
+) -> Vec<f32> {
+    // Summed-area tables of the weights and the weighted depth, so that the sum over any window
+    // takes constant time regardless of the radius.
+    let stride = width + 1;
+    let mut weight_sums = vec![0.0f64; stride * (height + 1)];
+    let mut depth_sums = vec![0.0f64; stride * (height + 1)];
+    for row in 0..height {
+        for col in 0..width {
+            let i = row * width + col;
+            let weight = if depth[i] > 0.0 {
+                f64::from(weights[i])
+            } else {
+                0.0
+            };
+
+            let at = (row + 1) * stride + col + 1;
+            weight_sums[at] = weight + weight_sums[at - 1] + weight_sums[at - stride]
+                - weight_sums[at - stride - 1];
+            depth_sums[at] =
+                weight * f64::from(depth[i]) + depth_sums[at - 1] + depth_sums[at - stride]
+                    - depth_sums[at - stride - 1];
+        }
+    }
+
+    let window_sum = |sums: &[f64], top: usize, left: usize, bottom: usize, right: usize| {
+        sums[bottom * stride + right] - sums[top * stride + right] - sums[bottom * stride + left]
+            + sums[top * stride + left]
+    };
+
+    let mut smoothed = vec![0.0; width * height];
+    for row in 0..height {
+        for col in 0..width {
+            let i = row * width + col;
+            if depth[i] <= 0.0 {
+                continue;
+            }
+
+            let (top, bottom) = (row.saturating_sub(radius), (row + radius + 1).min(height));
+            let (left, right) = (col.saturating_sub(radius), (col + radius + 1).min(width));
+            let weight = window_sum(&weight_sums, top, left, bottom, right);
+            if weight > 0.0 {
+                smoothed[i] = (window_sum(&depth_sums, top, left, bottom, right) / weight) as f32;
+            }
+        }
+    }
+    smoothed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confident_values_dominate_the_average() {
+        // A 3x1 strip, averaged over the whole strip from the middle pixel.
+        let depth = [1.0, 2.0, 4.0];
+        let weights = [1.0, 1.0, 2.0];
+        let smoothed = weighted_box_average(&depth, &weights, 3, 1, 1);
+        assert_eq!(smoothed[1], (1.0 + 2.0 + 8.0) / 4.0);
+    }
+
+    #[test]
+    fn holes_neither_contribute_nor_get_filled() {
+        let depth = [2.0, 0.0, 4.0, 6.0];
+        let weights = [1.0, 1.0, 0.0, 1.0];
+        let smoothed = weighted_box_average(&depth, &weights, 4, 1, 1);
+        // The hole stays a hole, and the zero-weight value is replaced by its neighbours.
+        assert_eq!(smoothed, vec![2.0, 0.0, 6.0, 6.0]);
+    }
+}
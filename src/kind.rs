@@ -57,21 +57,29 @@
 
 mod camera_info;
 mod color_scheme;
+mod depth_camera_model;
 mod distortion_model;
+mod downsample_mode;
 mod exception;
 mod extension;
 mod format;
 mod frame_metadata;
 mod hole_filling;
+mod inter_cam_sync_mode;
+mod log_severity;
+mod notification_category;
 mod option;
 mod persistence_control;
+mod playback_status;
 mod product_line;
 mod stream_kind;
 mod timestamp_domain;
 
 pub use camera_info::Rs2CameraInfo;
 pub use color_scheme::ColorScheme;
+pub use depth_camera_model::DepthCameraModel;
 pub use distortion_model::Rs2DistortionModel;
+pub use downsample_mode::DownsampleMode;
 pub use exception::Rs2Exception;
 pub use extension::{
     Rs2Extension, DEVICE_EXTENSIONS, FILTER_EXTENSIONS, FRAME_EXTENSIONS, MISC_EXTENSIONS,
@@ -80,8 +88,15 @@ pub use extension::{
 pub use format::Rs2Format;
 pub use frame_metadata::Rs2FrameMetadata;
 pub use hole_filling::HoleFillingMode;
-pub use option::{OptionSetError, Rs2Option, Rs2OptionRange, Rs2Rs400VisualPreset};
+pub use inter_cam_sync_mode::InterCamSyncMode;
+pub use log_severity::Rs2LogSeverity;
+pub use notification_category::Rs2NotificationCategory;
+pub use option::{
+    OptionSetError, Rs2DigitalGain, Rs2EmitterFrequency, Rs2HostPerfMode, Rs2L500VisualPreset,
+    Rs2Option, Rs2OptionRange, Rs2Rs400VisualPreset,
+};
 pub use persistence_control::PersistenceControl;
+pub use playback_status::PlaybackStatus;
 pub use product_line::Rs2ProductLine;
 pub use stream_kind::Rs2StreamKind;
 pub use timestamp_domain::Rs2TimestampDomain;
@@ -0,0 +1,87 @@
+//! The enumeration of D400 depth camera models with distinct depth tuning.
+
+/// An enumeration of D400 depth camera models, grouped by their working range.
+///
+/// Every D400 camera reports the same [`Rs2ProductLine::D400`](crate::kind::Rs2ProductLine::D400),
+/// but the models are tuned for very different distances: the D405 is a close-range camera that
+/// works from a few centimeters, whereas the D455 has a wider baseline for long range. The model
+/// determines sensible defaults for the depth units and for the range of a
+/// [`Threshold`](crate::processing_blocks::threshold::Threshold) filter.
+///
+/// The ranges are the ideal ranges given in the Intel RealSense D400 datasheet, and are a starting
+/// point rather than hard limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DepthCameraModel {
+    /// The D405 close-range camera.
+    D405,
+    /// The D415 camera, with rolling shutter depth sensors.
+    D415,
+    /// The D435 family of cameras, e.g. the D435, D435i and D435f.
+    D435,
+    /// The D455 family of long-range cameras, e.g. the D455, D456 and D457.
+    D455,
+}
+
+impl DepthCameraModel {
+    /// Determine the model from the name of a device, as given by
+    /// [`Rs2CameraInfo::Name`](crate::kind::Rs2CameraInfo::Name).
+    ///
+    /// Returns `None` if the name does not belong to any of the models, e.g. for an L515.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_uppercase();
+        if name.contains("D405") {
+            Some(DepthCameraModel::D405)
+        } else if name.contains("D415") {
+            Some(DepthCameraModel::D415)
+        } else if name.contains("D435") {
+            Some(DepthCameraModel::D435)
+        } else if name.contains("D455") || name.contains("D456") || name.contains("D457") {
+            Some(DepthCameraModel::D455)
+        } else {
+            None
+        }
+    }
+
+    /// Get the recommended size of one unit of depth in meters.
+    ///
+    /// The D405 uses a tenth of a millimeter so that its close-range precision is not lost to
+    /// quantization; every other model uses millimeters.
+    pub fn depth_units(self) -> f32 {
+        match self {
+            DepthCameraModel::D405 => 0.0001,
+            _ => 0.001,
+        }
+    }
+
+    /// Get the ideal working range of the model as `(min, max)` in meters.
+    pub fn ideal_range(self) -> (f32, f32) {
+        match self {
+            DepthCameraModel::D405 => (0.07, 0.5),
+            DepthCameraModel::D415 => (0.5, 3.0),
+            DepthCameraModel::D435 => (0.3, 3.0),
+            DepthCameraModel::D455 => (0.6, 6.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn model_is_found_in_device_name() {
+        assert_eq!(
+            DepthCameraModel::from_name("Intel RealSense D405"),
+            Some(DepthCameraModel::D405)
+        );
+        assert_eq!(
+            DepthCameraModel::from_name("Intel RealSense D435I"),
+            Some(DepthCameraModel::D435)
+        );
+        assert_eq!(
+            DepthCameraModel::from_name("Intel RealSense D457"),
+            Some(DepthCameraModel::D455)
+        );
+        assert_eq!(DepthCameraModel::from_name("Intel RealSense L515"), None);
+    }
+}
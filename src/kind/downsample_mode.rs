@@ -0,0 +1,20 @@
+//! The ways of combining depth pixels when downsampling a depth frame on the host.
+
+/// How [`DepthFrame::downsample`](crate::frame::DepthFrame::downsample) turns each block of pixels
+/// into a single pixel.
+///
+/// Like [`ColorScheme`](crate::kind::ColorScheme), this does not map to a librealsense2 type.
+/// Neither mode smooths the image the way the decimation processing block does with its median
+/// filter, so depth edges stay where they are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DownsampleMode {
+    /// Keep the top-left pixel of each block, and discard the rest.
+    ///
+    /// This is the cheapest mode, and never produces depth values that were not measured.
+    Nearest,
+    /// Average the pixels of each block that have depth data.
+    ///
+    /// Pixels without depth data are ignored rather than averaged in as zero, so a block only has
+    /// no depth data if none of its pixels do.
+    Area,
+}
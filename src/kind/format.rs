@@ -1,7 +1,9 @@
 //! Enumeration of frame data format & layout
 
 use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::FromPrimitive;
 use realsense_sys as sys;
+use std::{ffi::CStr, fmt};
 
 /// A type representing all possible data formats for raw frame data
 #[repr(i32)]
@@ -115,10 +117,41 @@ pub enum Rs2Format {
     // Count = sys::rs2_format_RS2_FORMAT_COUNT,
 }
 
+impl Rs2Format {
+    /// Get the format as a CStr.
+    pub fn to_cstr(self) -> &'static CStr {
+        unsafe {
+            let ptr = sys::rs2_format_to_string(self as sys::rs2_format);
+            CStr::from_ptr(ptr)
+        }
+    }
+
+    /// Get the format as a str.
+    pub fn to_str(self) -> &'static str {
+        self.to_cstr().to_str().unwrap()
+    }
+
+    /// Look up a format by its name, as given by [`Rs2Format::to_str`].
+    ///
+    /// Names are compared case-insensitively, e.g. both `"RGB8"` and `"rgb8"` give
+    /// [`Rs2Format::Rgb8`]. Returns `None` if no format has the given name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        (0..sys::rs2_format_RS2_FORMAT_COUNT as i32)
+            .filter_map(Rs2Format::from_i32)
+            .find(|format| format.to_str().eq_ignore_ascii_case(name))
+    }
+}
+
+impl fmt::Display for Rs2Format {
+    /// Formats the format with the name librealsense2 gives it, e.g. `Z16` or `RGB8`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.to_str())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use num_traits::FromPrimitive;
 
     #[test]
     fn all_variants_exist() {
@@ -130,4 +163,22 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn formats_display_their_names() {
+        assert_eq!(Rs2Format::Z16.to_string(), "Z16");
+        assert_eq!(Rs2Format::Rgb8.to_string(), "RGB8");
+    }
+
+    #[test]
+    fn formats_round_trip_through_their_names() {
+        for i in 0..sys::rs2_format_RS2_FORMAT_COUNT as i32 {
+            if let Some(format) = Rs2Format::from_i32(i) {
+                assert_eq!(Rs2Format::from_name(format.to_str()), Some(format));
+            }
+        }
+
+        assert_eq!(Rs2Format::from_name("rgb8"), Some(Rs2Format::Rgb8));
+        assert_eq!(Rs2Format::from_name("Not A Name"), None);
+    }
 }
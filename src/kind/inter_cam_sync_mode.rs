@@ -0,0 +1,25 @@
+//! The enumeration of inter-camera hardware synchronization modes.
+
+use num_derive::{FromPrimitive, ToPrimitive};
+
+/// An enumeration of the hardware synchronization modes set through
+/// [`Rs2Option::InterCamSyncMode`](crate::kind::Rs2Option::InterCamSyncMode).
+///
+/// Cameras in a synchronized rig are connected through their sync connectors. One camera (or an
+/// external signal generator) drives the trigger, and the others follow it, so that their depth
+/// frames are captured at the same instant.
+///
+/// The modes are supported by D400 devices with a sync connector (e.g. the D415, D435, D435i and
+/// D455) and by L500 devices; not every device supports every mode.
+#[repr(i32)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InterCamSyncMode {
+    /// The camera runs on its own clock, and neither sends nor follows a trigger.
+    Default = 0,
+    /// The camera sends a trigger on every depth frame to the cameras following it.
+    Master = 1,
+    /// The depth sensor of the camera follows an external trigger.
+    Slave = 2,
+    /// Every sensor of the camera follows an external trigger.
+    FullSlave = 3,
+}
@@ -0,0 +1,65 @@
+//! Enumeration describing the category of notifications sent by a sensor.
+
+use num_derive::{FromPrimitive, ToPrimitive};
+use realsense_sys as sys;
+use std::ffi::CStr;
+
+/// Enumeration of the categories of notifications that librealsense2 sends about a sensor.
+#[repr(i32)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rs2NotificationCategory {
+    /// Frames did not arrive within the expected time.
+    FramesTimeout = sys::rs2_notification_category_RS2_NOTIFICATION_CATEGORY_FRAMES_TIMEOUT as i32,
+    /// A frame arrived corrupted.
+    FrameCorrupted =
+        sys::rs2_notification_category_RS2_NOTIFICATION_CATEGORY_FRAME_CORRUPTED as i32,
+    /// The hardware reported an error.
+    HardwareError = sys::rs2_notification_category_RS2_NOTIFICATION_CATEGORY_HARDWARE_ERROR as i32,
+    /// The hardware reported an event that is not an error.
+    HardwareEvent = sys::rs2_notification_category_RS2_NOTIFICATION_CATEGORY_HARDWARE_EVENT as i32,
+    /// An error of unknown origin occurred.
+    UnknownError = sys::rs2_notification_category_RS2_NOTIFICATION_CATEGORY_UNKNOWN_ERROR as i32,
+    /// The firmware of the device is older than the recommended version.
+    FirmwareUpdateRecommended =
+        sys::rs2_notification_category_RS2_NOTIFICATION_CATEGORY_FIRMWARE_UPDATE_RECOMMENDED as i32,
+    /// A pose sensor relocalized, i.e. recognized a previously mapped location and corrected its
+    /// pose accordingly.
+    PoseRelocalization =
+        sys::rs2_notification_category_RS2_NOTIFICATION_CATEGORY_POSE_RELOCALIZATION as i32,
+    /* Not included since this just tells us the total number of categories
+     *
+     * Count = sys::rs2_notification_category_RS2_NOTIFICATION_CATEGORY_COUNT, */
+}
+
+impl Rs2NotificationCategory {
+    /// Get the notification category variant as a `&CStr`
+    pub fn as_cstr(&self) -> &'static CStr {
+        unsafe {
+            let ptr =
+                sys::rs2_notification_category_to_string(*self as sys::rs2_notification_category);
+            CStr::from_ptr(ptr)
+        }
+    }
+
+    /// Get the notification category variant as a `&str`
+    pub fn as_str(&self) -> &'static str {
+        self.as_cstr().to_str().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::FromPrimitive;
+
+    #[test]
+    fn all_variants_exist() {
+        for i in 0..sys::rs2_notification_category_RS2_NOTIFICATION_CATEGORY_COUNT as i32 {
+            assert!(
+                Rs2NotificationCategory::from_i32(i).is_some(),
+                "Rs2NotificationCategory variant for ordinal {} does not exist.",
+                i,
+            );
+        }
+    }
+}
@@ -11,8 +11,9 @@
 
 use super::Rs2Exception;
 use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::FromPrimitive;
 use realsense_sys as sys;
-use std::ffi::CStr;
+use std::{ffi::CStr, fmt};
 use thiserror::Error;
 
 /// Occur when an option cannot be set.
@@ -24,6 +25,19 @@ pub enum OptionSetError {
     /// The requested option is read-only and cannot be set.
     #[error("Option is read only.")]
     OptionIsReadOnly,
+    /// The requested value is outside of the range of values accepted for this option.
+    #[error("Option value {value} is out of range. Expected a value in [{min}, {max}].")]
+    OptionValueOutOfRange {
+        /// The value that was rejected.
+        value: f32,
+        /// The minimum value accepted for this option.
+        min: f32,
+        /// The maximum value accepted for this option.
+        max: f32,
+    },
+    /// No option has the requested name.
+    #[error("No option is named \"{0}\".")]
+    UnknownOptionName(String),
     /// The requested option could not be set. Reason is reported by the sensor.
     #[error("Could not set option. Type: {0}; Reason: {1}")]
     CouldNotSetOption(Rs2Exception, String),
@@ -126,7 +140,8 @@ pub enum Rs2Option {
     /// - `3`: enable LED
     EmitterEnabled = sys::rs2_option_RS2_OPTION_EMITTER_ENABLED as i32,
     /// Set the number of frames the user is allowed to keep per stream.
-    /// Trying to hold on to more frames will cause frame drops.
+    /// Trying to hold on to more frames will cause frame drops. A value of zero only keeps the
+    /// latest frame, see [`Sensor::set_low_latency_mode`](crate::sensor::Sensor::set_low_latency_mode).
     FramesQueueSize = sys::rs2_option_RS2_OPTION_FRAMES_QUEUE_SIZE as i32,
     /// Get the total number of detected frame drops from all streams.
     TotalFrameDrops = sys::rs2_option_RS2_OPTION_TOTAL_FRAME_DROPS as i32,
@@ -301,11 +316,87 @@ pub enum Rs2Option {
 ///
 /// The visual presets are recommended sets of option presets for a depth camera. The preset selection
 /// varies between devices and sensors.
+///
+/// Set through [`Rs2Option::VisualPreset`] on a D400 depth sensor, see
+/// [`DepthSensor`](crate::sensor::DepthSensor).
 #[repr(i32)]
 #[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Rs2Rs400VisualPreset {
+    /// Options have been changed from any of the other presets.
+    Custom = sys::rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_CUSTOM as i32,
+    /// Default visual preset.
+    Default = sys::rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_DEFAULT as i32,
+    /// Preset for hand tracking and gesture recognition.
+    Hand = sys::rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_HAND as i32,
     /// High accuracy visual preset.
     HighAccuracy = sys::rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_HIGH_ACCURACY as i32,
+    /// Preset with a high fill rate, at the cost of accuracy.
+    HighDensity = sys::rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_HIGH_DENSITY as i32,
+    /// Preset balancing fill rate and accuracy.
+    MediumDensity = sys::rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_MEDIUM_DENSITY as i32,
+    /// Preset removing the projected IR pattern from the infrared streams.
+    RemoveIrPattern = sys::rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_REMOVE_IR_PATTERN as i32,
+}
+
+/// The enumeration of L500 camera visual presets.
+///
+/// Set through [`Rs2Option::VisualPreset`] on an L500 depth sensor, see
+/// [`L500DepthSensor`](crate::sensor::L500DepthSensor).
+#[repr(i32)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rs2L500VisualPreset {
+    /// Options have been changed from any of the other presets.
+    Custom = sys::rs2_l500_visual_preset_RS2_L500_VISUAL_PRESET_CUSTOM as i32,
+    /// Default visual preset.
+    Default = sys::rs2_l500_visual_preset_RS2_L500_VISUAL_PRESET_DEFAULT as i32,
+    /// Preset for scenes with no ambient light, e.g. indoors.
+    NoAmbient = sys::rs2_l500_visual_preset_RS2_L500_VISUAL_PRESET_NO_AMBIENT as i32,
+    /// Preset for scenes with low ambient light.
+    LowAmbient = sys::rs2_l500_visual_preset_RS2_L500_VISUAL_PRESET_LOW_AMBIENT as i32,
+    /// Preset maximizing the range of the sensor.
+    MaxRange = sys::rs2_l500_visual_preset_RS2_L500_VISUAL_PRESET_MAX_RANGE as i32,
+    /// Preset for objects close to the sensor.
+    ShortRange = sys::rs2_l500_visual_preset_RS2_L500_VISUAL_PRESET_SHORT_RANGE as i32,
+    /// Preset chosen automatically based on the scene.
+    Automatic = sys::rs2_l500_visual_preset_RS2_L500_VISUAL_PRESET_AUTOMATIC as i32,
+}
+
+/// The enumeration of digital gain values, set through [`Rs2Option::DigitalGain`].
+#[repr(i32)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rs2DigitalGain {
+    /// Digital gain is chosen automatically.
+    Auto = sys::rs2_digital_gain_RS2_DIGITAL_GAIN_AUTO as i32,
+    /// High digital gain.
+    High = sys::rs2_digital_gain_RS2_DIGITAL_GAIN_HIGH as i32,
+    /// Low digital gain.
+    Low = sys::rs2_digital_gain_RS2_DIGITAL_GAIN_LOW as i32,
+}
+
+/// The enumeration of host performance modes, set through [`Rs2Option::HostPerformance`].
+#[repr(i32)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rs2HostPerfMode {
+    /// Use the device's default settings.
+    Default = sys::rs2_host_perf_mode_RS2_HOST_PERF_DEFAULT as i32,
+    /// Optimize device settings for a host with low performance.
+    Low = sys::rs2_host_perf_mode_RS2_HOST_PERF_LOW as i32,
+    /// Optimize device settings for a host with high performance.
+    High = sys::rs2_host_perf_mode_RS2_HOST_PERF_HIGH as i32,
+}
+
+/// The enumeration of IR emitter frequencies, set through [`Rs2Option::EmitterFrequency`].
+///
+/// Cameras whose projected patterns overlap can interfere with each other. Running the emitters of
+/// neighbouring cameras at different frequencies is one way of reducing that cross-talk on the
+/// cameras that support it, e.g. the D455.
+#[repr(i32)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rs2EmitterFrequency {
+    /// The emitter is modulated at 57 kHz.
+    Khz57 = sys::rs2_emitter_frequency_mode_RS2_EMITTER_FREQUENCY_57_KHZ as i32,
+    /// The emitter is modulated at 91 kHz.
+    Khz91 = sys::rs2_emitter_frequency_mode_RS2_EMITTER_FREQUENCY_91_KHZ as i32,
 }
 
 impl Rs2Option {
@@ -321,15 +412,29 @@ impl Rs2Option {
     pub fn to_str(self) -> &'static str {
         self.to_cstr().to_str().unwrap()
     }
+
+    /// Look up an option by its human-readable name, as given by [`Rs2Option::to_str`].
+    ///
+    /// Names are compared case-insensitively, e.g. both `"Laser Power"` and `"laser power"` give
+    /// [`Rs2Option::LaserPower`]. Returns `None` if no option has the given name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        (0..sys::rs2_option_RS2_OPTION_COUNT as i32)
+            .filter_map(Rs2Option::from_i32)
+            .find(|option| option.to_str().eq_ignore_ascii_case(name))
+    }
 }
 
-impl ToString for Rs2Option {
-    fn to_string(&self) -> String {
-        self.to_str().to_owned()
+impl fmt::Display for Rs2Option {
+    /// Formats the option with its human-readable name, e.g. `Laser Power`.
+    ///
+    /// The name can be turned back into the option with [`Rs2Option::from_name`].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.to_str())
     }
 }
 
 /// The range of available values of a supported option.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Rs2OptionRange {
     /// The minimum value which will be accepted for this option
     pub min: f32,
@@ -342,6 +447,30 @@ pub struct Rs2OptionRange {
     pub default: f32,
 }
 
+impl Rs2OptionRange {
+    /// Predicate for whether `value` lies within `[min, max]`.
+    pub fn contains(&self, value: f32) -> bool {
+        (self.min..=self.max).contains(&value)
+    }
+
+    /// Check that `value` lies within `[min, max]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptionSetError::OptionValueOutOfRange`] if `value` lies outside of the range.
+    pub fn validate(&self, value: f32) -> Result<(), OptionSetError> {
+        if self.contains(value) {
+            Ok(())
+        } else {
+            Err(OptionSetError::OptionValueOutOfRange {
+                value,
+                min: self.min,
+                max: self.max,
+            })
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -370,4 +499,92 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn all_rs400_visual_presets_exist() {
+        for i in 0..sys::rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_COUNT as i32 {
+            assert!(
+                Rs2Rs400VisualPreset::from_i32(i).is_some(),
+                "Rs2Rs400VisualPreset variant for ordinal {} does not exist.",
+                i,
+            );
+        }
+    }
+
+    #[test]
+    fn all_l500_visual_presets_exist() {
+        for i in 0..sys::rs2_l500_visual_preset_RS2_L500_VISUAL_PRESET_COUNT as i32 {
+            assert!(
+                Rs2L500VisualPreset::from_i32(i).is_some(),
+                "Rs2L500VisualPreset variant for ordinal {} does not exist.",
+                i,
+            );
+        }
+    }
+
+    #[test]
+    fn all_host_perf_modes_exist() {
+        for i in 0..sys::rs2_host_perf_mode_RS2_HOST_PERF_COUNT as i32 {
+            assert!(
+                Rs2HostPerfMode::from_i32(i).is_some(),
+                "Rs2HostPerfMode variant for ordinal {} does not exist.",
+                i,
+            );
+        }
+    }
+
+    #[test]
+    fn all_emitter_frequencies_exist() {
+        for i in 0..sys::rs2_emitter_frequency_mode_RS2_EMITTER_FREQUENCY_COUNT as i32 {
+            assert!(
+                Rs2EmitterFrequency::from_i32(i).is_some(),
+                "Rs2EmitterFrequency variant for ordinal {} does not exist.",
+                i,
+            );
+        }
+    }
+
+    #[test]
+    fn option_range_validates_bounds() {
+        let range = Rs2OptionRange {
+            min: 2.0,
+            max: 8.0,
+            step: 1.0,
+            default: 2.0,
+        };
+
+        assert!(range.validate(2.0).is_ok());
+        assert!(range.validate(8.0).is_ok());
+        assert_eq!(
+            range.validate(9.0),
+            Err(OptionSetError::OptionValueOutOfRange {
+                value: 9.0,
+                min: 2.0,
+                max: 8.0,
+            })
+        );
+        assert!(!range.contains(f32::NAN));
+    }
+
+    #[test]
+    fn options_round_trip_through_their_names() {
+        for i in 0..sys::rs2_option_RS2_OPTION_COUNT as i32 {
+            if let Some(option) = Rs2Option::from_i32(i) {
+                assert_eq!(Rs2Option::from_name(option.to_str()), Some(option));
+            }
+        }
+
+        assert_eq!(
+            Rs2Option::from_name(&Rs2Option::LaserPower.to_str().to_uppercase()),
+            Some(Rs2Option::LaserPower)
+        );
+        assert_eq!(Rs2Option::from_name("Not An Option"), None);
+    }
+
+    #[test]
+    fn options_display_their_names() {
+        let option = Rs2Option::LaserPower;
+        assert_eq!(option.to_string(), option.to_str());
+        assert_eq!(Rs2Option::from_name(&option.to_string()), Some(option));
+    }
 }
@@ -0,0 +1,39 @@
+//! Enumeration describing the status of a playback device.
+
+use num_derive::{FromPrimitive, ToPrimitive};
+use realsense_sys as sys;
+
+/// Enumeration of the states a playback device can be in while streaming from a recorded file.
+#[repr(i32)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlaybackStatus {
+    /// The status of the playback could not be determined.
+    Unknown = sys::rs2_playback_status_RS2_PLAYBACK_STATUS_UNKNOWN as i32,
+    /// Frames are being read from the file.
+    Playing = sys::rs2_playback_status_RS2_PLAYBACK_STATUS_PLAYING as i32,
+    /// Playback is paused, and can be resumed.
+    Paused = sys::rs2_playback_status_RS2_PLAYBACK_STATUS_PAUSED as i32,
+    /// Playback has stopped, either because it was stopped explicitly or because the end of the
+    /// file was reached.
+    Stopped = sys::rs2_playback_status_RS2_PLAYBACK_STATUS_STOPPED as i32,
+    /* Not included since this just tells us the total number of statuses
+     *
+     * Count = sys::rs2_playback_status_RS2_PLAYBACK_STATUS_COUNT, */
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::FromPrimitive;
+
+    #[test]
+    fn all_variants_exist() {
+        for i in 0..sys::rs2_playback_status_RS2_PLAYBACK_STATUS_COUNT as i32 {
+            assert!(
+                PlaybackStatus::from_i32(i).is_some(),
+                "PlaybackStatus variant for ordinal {} does not exist.",
+                i,
+            );
+        }
+    }
+}
@@ -45,3 +45,80 @@ pub enum Rs2ProductLine {
     /// Any device that has a depth feed
     Depth = sys::RS2_PRODUCT_LINE_DEPTH,
 }
+
+impl Rs2ProductLine {
+    /// The product line of tracking devices.
+    ///
+    /// librealsense2 defines `RS2_PRODUCT_LINE_TRACKING` as an alias of
+    /// [`T200`](Rs2ProductLine::T200), so it cannot be a distinct variant.
+    pub const TRACKING: Rs2ProductLine = Rs2ProductLine::T200;
+
+    /// Predicate for whether `other` is part of this product line.
+    ///
+    /// Grouping product lines such as [`Depth`](Rs2ProductLine::Depth) or
+    /// [`Any`](Rs2ProductLine::Any) contain the individual product lines they are composed of,
+    /// e.g. `Rs2ProductLine::Depth.contains(Rs2ProductLine::D400)` is true. Every product line
+    /// contains itself.
+    pub fn contains(self, other: Rs2ProductLine) -> bool {
+        let mask = self as u32;
+        let other = other as u32;
+        mask & other == other
+    }
+
+    /// Parse the product line reported by a device under
+    /// [`Rs2CameraInfo::ProductLine`](crate::kind::Rs2CameraInfo::ProductLine).
+    ///
+    /// Returns `None` if the value does not name a known product line.
+    pub(crate) fn from_info(value: &str) -> Option<Self> {
+        match value {
+            "D400" => Some(Rs2ProductLine::D400),
+            "SR300" => Some(Rs2ProductLine::Sr300),
+            "L500" => Some(Rs2ProductLine::L500),
+            "T200" => Some(Rs2ProductLine::T200),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_contains_depth_product_lines() {
+        assert!(Rs2ProductLine::Depth.contains(Rs2ProductLine::D400));
+        assert!(Rs2ProductLine::Depth.contains(Rs2ProductLine::Sr300));
+        assert!(Rs2ProductLine::Depth.contains(Rs2ProductLine::L500));
+        assert!(!Rs2ProductLine::Depth.contains(Rs2ProductLine::T200));
+        assert!(!Rs2ProductLine::Depth.contains(Rs2ProductLine::TRACKING));
+    }
+
+    #[test]
+    fn any_contains_every_product_line() {
+        assert!(Rs2ProductLine::Any.contains(Rs2ProductLine::NonIntel));
+        assert!(Rs2ProductLine::Any.contains(Rs2ProductLine::Depth));
+        assert!(Rs2ProductLine::AnyIntel.contains(Rs2ProductLine::T200));
+        assert!(!Rs2ProductLine::AnyIntel.contains(Rs2ProductLine::NonIntel));
+    }
+
+    #[test]
+    fn parses_product_line_info() {
+        assert_eq!(
+            Rs2ProductLine::from_info("D400"),
+            Some(Rs2ProductLine::D400)
+        );
+        assert_eq!(
+            Rs2ProductLine::from_info("SR300"),
+            Some(Rs2ProductLine::Sr300)
+        );
+        assert_eq!(
+            Rs2ProductLine::from_info("L500"),
+            Some(Rs2ProductLine::L500)
+        );
+        assert_eq!(
+            Rs2ProductLine::from_info("T200"),
+            Some(Rs2ProductLine::T200)
+        );
+        assert_eq!(Rs2ProductLine::from_info("D500"), None);
+    }
+}
@@ -3,7 +3,9 @@
 //! Streams are different types of data provided by RealSense devices.
 
 use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::FromPrimitive;
 use realsense_sys as sys;
+use std::{ffi::CStr, fmt};
 
 /// The enumeration of possible stream kinds.
 ///
@@ -41,28 +43,41 @@ pub enum Rs2StreamKind {
      * Count = sys::rs2_stream_RS2_STREAM_COUNT, */
 }
 
-impl std::fmt::Display for Rs2StreamKind {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let string = match self {
-            Rs2StreamKind::Any => "Any",
-            Rs2StreamKind::Depth => "Depth",
-            Rs2StreamKind::Color => "Color",
-            Rs2StreamKind::Infrared => "Infrared",
-            Rs2StreamKind::Fisheye => "Fisheye",
-            Rs2StreamKind::Gyro => "Gyro",
-            Rs2StreamKind::Accel => "Accel",
-            Rs2StreamKind::Gpio => "Gpio",
-            Rs2StreamKind::Pose => "Pose",
-            Rs2StreamKind::Confidence => "Confidence",
-        };
-        write!(f, "{}", string)
+impl Rs2StreamKind {
+    /// Get the stream kind as a CStr.
+    pub fn to_cstr(self) -> &'static CStr {
+        unsafe {
+            let ptr = sys::rs2_stream_to_string(self as sys::rs2_stream);
+            CStr::from_ptr(ptr)
+        }
+    }
+
+    /// Get the stream kind as a str.
+    pub fn to_str(self) -> &'static str {
+        self.to_cstr().to_str().unwrap()
+    }
+
+    /// Look up a stream kind by its name, as given by [`Rs2StreamKind::to_str`].
+    ///
+    /// Names are compared case-insensitively, e.g. both `"Depth"` and `"depth"` give
+    /// [`Rs2StreamKind::Depth`]. Returns `None` if no stream kind has the given name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        (0..sys::rs2_stream_RS2_STREAM_COUNT as i32)
+            .filter_map(Rs2StreamKind::from_i32)
+            .find(|kind| kind.to_str().eq_ignore_ascii_case(name))
+    }
+}
+
+impl fmt::Display for Rs2StreamKind {
+    /// Formats the stream kind with the name librealsense2 gives it, e.g. `Depth`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.to_str())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use num_traits::FromPrimitive;
 
     #[test]
     fn all_variants_exist() {
@@ -74,4 +89,25 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn stream_kinds_display_their_names() {
+        assert_eq!(Rs2StreamKind::Depth.to_string(), "Depth");
+        assert_eq!(Rs2StreamKind::Infrared.to_string(), "Infrared");
+    }
+
+    #[test]
+    fn stream_kinds_round_trip_through_their_names() {
+        for i in 0..sys::rs2_stream_RS2_STREAM_COUNT as i32 {
+            if let Some(kind) = Rs2StreamKind::from_i32(i) {
+                assert_eq!(Rs2StreamKind::from_name(kind.to_str()), Some(kind));
+            }
+        }
+
+        assert_eq!(
+            Rs2StreamKind::from_name("depth"),
+            Some(Rs2StreamKind::Depth)
+        );
+        assert_eq!(Rs2StreamKind::from_name("Not A Name"), None);
+    }
 }
@@ -3,20 +3,25 @@
 #![allow(clippy::all)]
 
 pub mod base;
+pub mod capture;
 pub mod config;
 pub mod context;
 pub mod device;
 pub mod device_hub;
 pub mod docs;
 mod error;
+pub mod fps;
 pub mod frame;
 pub mod kind;
+pub mod logging;
 pub mod pipeline;
 pub mod processing_blocks;
 pub mod sensor;
+pub mod software_device;
 pub mod stream_profile;
 
 /// The module collects common used traits from this crate.
 pub mod prelude {
     pub use crate::frame::{FrameCategory, FrameEx};
+    pub use crate::processing_blocks::options::ProcessingBlockOptions;
 }
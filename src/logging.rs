@@ -0,0 +1,446 @@
+//! Functions for configuring the logging of librealsense2.
+//!
+//! librealsense2 holds a single, process-wide logger. Messages can be written to the console
+//! and / or to a file, each with its own minimum [`Rs2LogSeverity`]. Enabling a log output
+//! returns a [`LogGuard`], which resets the logger once dropped so that the configuration does not
+//! outlive the code that set it up.
+//!
+//! Messages can also be collected into a bounded in-memory buffer with [`log_to_buffer`], and
+//! taken out with [`drain_pending_logs`] on the application's own schedule, e.g. to show them in a
+//! GUI console, or forwarded to the [`log`] crate with [`inject_rs_log_to_rust`] and
+//! [`inject_rs_log_filtered`].
+
+use crate::{
+    base::from_path,
+    check_rs2_error,
+    kind::{Rs2Exception, Rs2LogSeverity},
+};
+use anyhow::Result;
+use num_traits::FromPrimitive;
+use realsense_sys as sys;
+use std::{collections::VecDeque, ffi::CStr, os::raw::c_void, path::Path, sync::Mutex};
+use thiserror::Error;
+
+/// Type describing errors that can occur when configuring the librealsense2 logger.
+#[derive(Error, Debug, PartialEq)]
+pub enum LoggingError {
+    /// Could not log to the console.
+    #[error("Could not log to the console. Type: {0}; Reason: {1}")]
+    CouldNotLogToConsole(Rs2Exception, String),
+    /// Could not log to the file.
+    #[error("Could not log to the file. Type: {0}; Reason: {1}")]
+    CouldNotLogToFile(Rs2Exception, String),
+    /// Could not log to the in-memory buffer.
+    #[error("Could not log to the buffer. Type: {0}; Reason: {1}")]
+    CouldNotLogToBuffer(Rs2Exception, String),
+    /// Could not forward log messages to the `log` crate.
+    #[error("Could not forward log messages to the log crate. Type: {0}; Reason: {1}")]
+    CouldNotLogToRust(Rs2Exception, String),
+    /// The minimum severity of a range is more severe than its maximum.
+    #[error("Invalid severity range: {min:?} is more severe than {max:?}.")]
+    InvalidSeverityRange {
+        /// The requested minimum severity.
+        min: Rs2LogSeverity,
+        /// The requested maximum severity.
+        max: Rs2LogSeverity,
+    },
+    /// Could not reset the logger.
+    #[error("Could not reset the logger. Type: {0}; Reason: {1}")]
+    CouldNotResetLogger(Rs2Exception, String),
+}
+
+/// A message logged by librealsense2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogMessage {
+    /// The severity the message was logged with.
+    pub severity: Rs2LogSeverity,
+    /// The message itself.
+    pub message: String,
+    /// The message formatted by librealsense2, including e.g. the time it was logged.
+    pub full_message: String,
+    /// The librealsense2 source file that logged the message.
+    pub filename: String,
+    /// The line in `filename` that logged the message.
+    pub line_number: u32,
+}
+
+/// The messages logged to the buffer that have not been drained yet.
+struct LogBuffer {
+    /// The messages, oldest first.
+    messages: VecDeque<LogMessage>,
+    /// The number of messages held before the oldest message is evicted.
+    capacity: usize,
+}
+
+/// The buffer filled by [`log_to_buffer`] and emptied by [`drain_pending_logs`].
+static PENDING_LOGS: Mutex<LogBuffer> = Mutex::new(LogBuffer {
+    messages: VecDeque::new(),
+    capacity: 0,
+});
+
+/// A guard that resets the librealsense2 logger once dropped.
+///
+/// Returned by [`log_to_console`] and [`log_to_file`]. Since librealsense2 only holds a single
+/// logger, dropping any guard stops _all_ log outputs, including those enabled by other guards
+/// that are still alive. Use [`std::mem::forget`] on the guard to keep logging for the rest of the
+/// process.
+#[must_use = "the logger is reset as soon as the guard is dropped"]
+#[derive(Debug)]
+pub struct LogGuard {
+    /// Prevents construction outside of this module.
+    _private: (),
+}
+
+impl Drop for LogGuard {
+    fn drop(&mut self) {
+        let _ = reset_logger();
+    }
+}
+
+/// Log librealsense2 messages of at least `min_severity` to the console.
+///
+/// # Errors
+///
+/// Returns [`LoggingError::CouldNotLogToConsole`] if console logging cannot be enabled.
+///
+pub fn log_to_console(min_severity: Rs2LogSeverity) -> Result<LogGuard, LoggingError> {
+    unsafe {
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        sys::rs2_log_to_console(min_severity as sys::rs2_log_severity, &mut err);
+        check_rs2_error!(err, LoggingError::CouldNotLogToConsole)?;
+    }
+    Ok(LogGuard { _private: () })
+}
+
+/// Log librealsense2 messages of at least `min_severity` to the file at `file`.
+///
+/// # Errors
+///
+/// Returns [`NulError`](std::ffi::NulError) if the provided file path cannot be cleanly
+/// represented as a [`CString`](std::ffi::CString).
+///
+/// Returns [`LoggingError::CouldNotLogToFile`] if file logging cannot be enabled, e.g. if the
+/// file cannot be opened.
+///
+pub fn log_to_file<P>(min_severity: Rs2LogSeverity, file: P) -> Result<LogGuard>
+where
+    P: AsRef<Path>,
+{
+    let path = from_path(file)?;
+    unsafe {
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        sys::rs2_log_to_file(
+            min_severity as sys::rs2_log_severity,
+            path.as_ptr(),
+            &mut err,
+        );
+        check_rs2_error!(err, LoggingError::CouldNotLogToFile)?;
+    }
+    Ok(LogGuard { _private: () })
+}
+
+/// Log librealsense2 messages of at least `min_severity` to an in-memory buffer.
+///
+/// The buffer holds up to `capacity` messages; once it is full, the oldest message is evicted for
+/// each new one. Take messages out of the buffer with [`drain_pending_logs`]. Calling this again
+/// changes the capacity of the same buffer rather than creating a new one.
+///
+/// # Errors
+///
+/// Returns [`LoggingError::CouldNotLogToBuffer`] if the buffer cannot be registered with the
+/// logger.
+///
+pub fn log_to_buffer(
+    min_severity: Rs2LogSeverity,
+    capacity: usize,
+) -> Result<LogGuard, LoggingError> {
+    if let Ok(mut buffer) = PENDING_LOGS.lock() {
+        buffer.capacity = capacity;
+        let excess = buffer.messages.len().saturating_sub(capacity);
+        buffer.messages.drain(..excess);
+    }
+
+    unsafe {
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        sys::rs2_log_to_callback(
+            min_severity as sys::rs2_log_severity,
+            Some(on_log_message),
+            std::ptr::null_mut(),
+            &mut err,
+        );
+        check_rs2_error!(err, LoggingError::CouldNotLogToBuffer)?;
+    }
+    Ok(LogGuard { _private: () })
+}
+
+/// Take every message out of the buffer filled by [`log_to_buffer`], oldest first.
+pub fn drain_pending_logs() -> Vec<LogMessage> {
+    match PENDING_LOGS.lock() {
+        Ok(mut buffer) => buffer.messages.drain(..).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Push a message onto the buffer, evicting the oldest message if the buffer is full.
+fn push_log_message(message: LogMessage) {
+    if let Ok(mut buffer) = PENDING_LOGS.lock() {
+        if buffer.capacity == 0 {
+            return;
+        }
+        if buffer.messages.len() >= buffer.capacity {
+            buffer.messages.pop_front();
+        }
+        buffer.messages.push_back(message);
+    }
+}
+
+/// Convert a string returned for a log message into an owned `String`.
+///
+/// # Safety
+///
+/// `ptr` must be null, or point to a nul-terminated string that is valid for the duration of this
+/// call.
+unsafe fn message_str(ptr: *const std::os::raw::c_char, err: *mut sys::rs2_error) -> String {
+    if err.as_ref().is_some() {
+        sys::rs2_free_error(err);
+        return String::new();
+    }
+    if ptr.is_null() {
+        return String::new();
+    }
+    CStr::from_ptr(ptr).to_string_lossy().into_owned()
+}
+
+/// Get the message formatted by librealsense2, including e.g. the time it was logged.
+///
+/// # Safety
+///
+/// `msg` must point to a valid log message for the duration of this call.
+unsafe fn get_full_log_message(msg: *const sys::rs2_log_message) -> String {
+    let mut err = std::ptr::null_mut::<sys::rs2_error>();
+    let ptr = sys::rs2_get_full_log_message(msg, &mut err);
+    message_str(ptr, err)
+}
+
+/// Read the contents of the log message at `msg`, logged with the given severity.
+///
+/// # Safety
+///
+/// `msg` must point to a valid log message for the duration of this call.
+unsafe fn read_log_message(
+    severity: sys::rs2_log_severity,
+    msg: *const sys::rs2_log_message,
+) -> LogMessage {
+    let mut err = std::ptr::null_mut::<sys::rs2_error>();
+    let message = message_str(sys::rs2_get_raw_log_message(msg, &mut err), err);
+
+    let mut err = std::ptr::null_mut::<sys::rs2_error>();
+    let filename = message_str(sys::rs2_get_log_message_filename(msg, &mut err), err);
+
+    let mut err = std::ptr::null_mut::<sys::rs2_error>();
+    let line_number = sys::rs2_get_log_message_line_number(msg, &mut err);
+    if err.as_ref().is_some() {
+        sys::rs2_free_error(err);
+    }
+
+    LogMessage {
+        severity: Rs2LogSeverity::from_i32(severity as i32).unwrap_or(Rs2LogSeverity::Info),
+        message,
+        full_message: get_full_log_message(msg),
+        filename,
+        line_number,
+    }
+}
+
+/// The callback registered with librealsense2 by [`log_to_buffer`].
+unsafe extern "C" fn on_log_message(
+    severity: sys::rs2_log_severity,
+    msg: *const sys::rs2_log_message,
+    _arg: *mut c_void,
+) {
+    push_log_message(read_log_message(severity, msg));
+}
+
+/// Forward librealsense2 messages of at least the severity matching `level` to the [`log`] crate.
+///
+/// This is equivalent to [`inject_rs_log_filtered`] with [`Rs2LogSeverity::Fatal`] as the
+/// maximum severity. [`log::Level::Trace`] forwards the same messages as [`log::Level::Debug`].
+///
+/// # Errors
+///
+/// Returns [`LoggingError::CouldNotLogToRust`] if the callback cannot be registered with the
+/// logger.
+///
+pub fn inject_rs_log_to_rust(level: log::Level) -> Result<LogGuard, LoggingError> {
+    inject_rs_log_filtered(severity_from_level(level), Rs2LogSeverity::Fatal)
+}
+
+/// Forward librealsense2 messages with a severity between `min` and `max` (inclusive) to the
+/// [`log`] crate.
+///
+/// Messages are logged with the `realsense` target. Unlike filtering in the `log` facade, this
+/// discards messages before they are formatted, and can express ranges such as
+/// [`Warn`](Rs2LogSeverity::Warn) to [`Fatal`](Rs2LogSeverity::Fatal) to capture only warnings
+/// and errors.
+///
+/// # Errors
+///
+/// Returns [`LoggingError::InvalidSeverityRange`] if `min` is more severe than `max`.
+///
+/// Returns [`LoggingError::CouldNotLogToRust`] if the callback cannot be registered with the
+/// logger.
+///
+pub fn inject_rs_log_filtered(
+    min: Rs2LogSeverity,
+    max: Rs2LogSeverity,
+) -> Result<LogGuard, LoggingError> {
+    if min > max {
+        return Err(LoggingError::InvalidSeverityRange { min, max });
+    }
+
+    unsafe {
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        // The maximum severity is small enough to be passed as the callback argument itself.
+        sys::rs2_log_to_callback(
+            min as sys::rs2_log_severity,
+            Some(on_log_message_to_rust),
+            max as usize as *mut c_void,
+            &mut err,
+        );
+        check_rs2_error!(err, LoggingError::CouldNotLogToRust)?;
+    }
+    Ok(LogGuard { _private: () })
+}
+
+/// Get the least librealsense2 severity that is logged at `level` in the [`log`] crate.
+fn severity_from_level(level: log::Level) -> Rs2LogSeverity {
+    match level {
+        log::Level::Error => Rs2LogSeverity::Error,
+        log::Level::Warn => Rs2LogSeverity::Warn,
+        log::Level::Info => Rs2LogSeverity::Info,
+        log::Level::Debug | log::Level::Trace => Rs2LogSeverity::Debug,
+    }
+}
+
+/// Get the [`log`] crate level that messages of the librealsense2 `severity` are logged at.
+///
+/// Returns `None` for [`Rs2LogSeverity::None`], which messages are never logged with.
+fn level_from_severity(severity: Rs2LogSeverity) -> Option<log::Level> {
+    match severity {
+        Rs2LogSeverity::Debug => Some(log::Level::Debug),
+        Rs2LogSeverity::Info => Some(log::Level::Info),
+        Rs2LogSeverity::Warn => Some(log::Level::Warn),
+        Rs2LogSeverity::Error | Rs2LogSeverity::Fatal => Some(log::Level::Error),
+        Rs2LogSeverity::None => None,
+    }
+}
+
+/// The callback registered with librealsense2 by [`inject_rs_log_filtered`].
+///
+/// `arg` holds the maximum severity of messages to forward.
+unsafe extern "C" fn on_log_message_to_rust(
+    severity: sys::rs2_log_severity,
+    msg: *const sys::rs2_log_message,
+    arg: *mut c_void,
+) {
+    let max = arg as usize as sys::rs2_log_severity;
+    if severity > max {
+        return;
+    }
+
+    let message = read_log_message(severity, msg);
+    if let Some(level) = level_from_severity(message.severity) {
+        log::log!(
+            target: "realsense",
+            level,
+            "{} ({}:{})",
+            message.message,
+            message.filename,
+            message.line_number
+        );
+    }
+}
+
+/// Reset the librealsense2 logger, stopping all log outputs.
+///
+/// This is called automatically when a [`LogGuard`] is dropped.
+///
+/// # Errors
+///
+/// Returns [`LoggingError::CouldNotResetLogger`] if the logger cannot be reset.
+///
+pub fn reset_logger() -> Result<(), LoggingError> {
+    unsafe {
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        sys::rs2_reset_logger(&mut err);
+        check_rs2_error!(err, LoggingError::CouldNotResetLogger)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logger_resets_after_guard_is_dropped() {
+        let file = std::env::temp_dir().join("realsense-rust-log-guard.log");
+        let guard = log_to_file(Rs2LogSeverity::Error, &file).unwrap();
+        drop(guard);
+
+        assert!(reset_logger().is_ok());
+        let _ = std::fs::remove_file(file);
+    }
+
+    #[test]
+    fn buffer_evicts_oldest_messages_and_drains_in_order() {
+        let guard = log_to_buffer(Rs2LogSeverity::None, 2).unwrap();
+        drain_pending_logs();
+
+        for i in 0..3 {
+            push_log_message(LogMessage {
+                severity: Rs2LogSeverity::Warn,
+                message: i.to_string(),
+                full_message: String::new(),
+                filename: String::new(),
+                line_number: 0,
+            });
+        }
+
+        let messages: Vec<_> = drain_pending_logs()
+            .into_iter()
+            .map(|m| m.message)
+            .collect();
+        assert_eq!(messages, vec!["1", "2"]);
+        assert!(drain_pending_logs().is_empty());
+        drop(guard);
+    }
+
+    #[test]
+    fn filtered_injection_rejects_inverted_range() {
+        assert_eq!(
+            inject_rs_log_filtered(Rs2LogSeverity::Error, Rs2LogSeverity::Warn).unwrap_err(),
+            LoggingError::InvalidSeverityRange {
+                min: Rs2LogSeverity::Error,
+                max: Rs2LogSeverity::Warn,
+            }
+        );
+    }
+
+    #[test]
+    fn levels_and_severities_round_trip() {
+        for level in [
+            log::Level::Error,
+            log::Level::Warn,
+            log::Level::Info,
+            log::Level::Debug,
+        ] {
+            assert_eq!(level_from_severity(severity_from_level(level)), Some(level));
+        }
+        assert_eq!(
+            level_from_severity(Rs2LogSeverity::Fatal),
+            Some(log::Level::Error)
+        );
+        assert_eq!(level_from_severity(Rs2LogSeverity::None), None);
+    }
+}
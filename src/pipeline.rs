@@ -17,9 +17,13 @@
 //!
 
 mod active;
+mod aligned;
 mod inactive;
+mod playback;
 mod profile;
 
 pub use active::{ActivePipeline, FrameWaitError};
+pub use aligned::AlignedFrames;
 pub use inactive::{InactivePipeline, PipelineActivationError, PipelineConstructionError};
-pub use profile::{PipelineProfile, PipelineProfileConstructionError};
+pub use playback::{PlaybackPipeline, PlaybackPipelineConstructionError};
+pub use profile::{PipelineProfile, PipelineProfileConstructionError, StreamMismatch};
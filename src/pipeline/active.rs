@@ -1,10 +1,16 @@
 //! Defines the pipeline type.
 
-use super::{inactive::InactivePipeline, profile::PipelineProfile};
-use crate::{check_rs2_error, frame::CompositeFrame, kind::Rs2Exception};
+use super::{aligned::AlignedFrames, inactive::InactivePipeline, profile::PipelineProfile};
+use crate::{
+    check_rs2_error,
+    device_hub::DeviceHub,
+    frame::CompositeFrame,
+    kind::{Rs2Exception, Rs2Extension, Rs2StreamKind},
+    processing_blocks::{align::Align, errors::ProcessingBlockConstructionError},
+};
 use anyhow::Result;
 use realsense_sys as sys;
-use std::{ptr::NonNull, task::Poll, time::Duration};
+use std::{ffi::CString, ptr::NonNull, task::Poll, time::Duration};
 use thiserror::Error;
 
 /// Enumeration over possible errors that can occur when waiting for a frame.
@@ -19,6 +25,15 @@ pub enum FrameWaitError {
     /// The associated function timed out while waiting for frames.
     #[error("Timed out while waiting for frame.")]
     DidTimeoutBeforeFrameArrival,
+    /// The device of the pipeline was disconnected, and librealsense2 could not reconnect to it.
+    ///
+    /// Unlike a timeout, waiting again will not deliver any frames. Stop the pipeline and start it
+    /// again once the device is back, e.g. after [`DeviceHub::wait_for_device`].
+    #[error("The device was disconnected while waiting for frames.")]
+    DeviceDisconnected,
+    /// Playback of a recorded file was paused, so no frames arrive until it is resumed.
+    #[error("Playback was paused while waiting for frames.")]
+    PlaybackPaused,
 }
 
 /// Type representing an "active" pipeline which is configured and can acquire frames.
@@ -29,6 +44,10 @@ pub struct ActivePipeline {
     /// The pipeline's profile, which contains the device the pipeline is configured for alongside
     /// the stream profiles for streams in the pipeline.
     profile: PipelineProfile,
+    /// The serial number of the device that the pipeline is bound to, if any.
+    device_serial: Option<CString>,
+    /// A hub of the pipeline's context, used to tell whether the device is still connected.
+    device_hub: Option<DeviceHub>,
 }
 
 impl Drop for ActivePipeline {
@@ -45,10 +64,17 @@ impl ActivePipeline {
     /// Constructs a new active pipeline from the constituent components
     ///
     /// This is only to be used / called from the [`InactivePipeline`] type.
-    pub(crate) fn new(pipeline_ptr: NonNull<sys::rs2_pipeline>, profile: PipelineProfile) -> Self {
+    pub(crate) fn new(
+        pipeline_ptr: NonNull<sys::rs2_pipeline>,
+        profile: PipelineProfile,
+        device_serial: Option<CString>,
+        device_hub: Option<DeviceHub>,
+    ) -> Self {
         Self {
             pipeline_ptr,
             profile,
+            device_serial,
+            device_hub,
         }
     }
 
@@ -60,7 +86,7 @@ impl ActivePipeline {
     /// Stop the pipeline.
     ///
     /// This method consumes the pipeline instance and returns pipeline markered inactive.
-    pub fn stop(self) -> InactivePipeline {
+    pub fn stop(mut self) -> InactivePipeline {
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
 
@@ -71,7 +97,11 @@ impl ActivePipeline {
             // dealing with the error (and thus returning a result type) is superfluous here.
             sys::rs2_pipeline_stop(self.pipeline_ptr.as_ptr(), &mut err);
 
-            let inactive = InactivePipeline::new(self.pipeline_ptr);
+            let inactive = InactivePipeline::new(
+                self.pipeline_ptr,
+                self.device_serial.take(),
+                self.device_hub.take(),
+            );
 
             std::mem::forget(self);
             inactive
@@ -97,6 +127,9 @@ impl ActivePipeline {
     ///
     /// Returns [`FrameWaitError::DidTimeoutBeforeFrameArrival`] if the thread waits more than
     /// `timeout_ms` (in milliseconds) without returning a frame.
+    ///
+    /// Returns [`FrameWaitError::DeviceDisconnected`] if the device was disconnected, and
+    /// librealsense2 could not reconnect to it within `timeout_ms`.
     pub fn wait(&mut self, timeout_ms: Option<Duration>) -> Result<CompositeFrame, FrameWaitError> {
         let timeout_ms = match timeout_ms {
             Some(d) => d.as_millis() as u32,
@@ -121,12 +154,14 @@ impl ActivePipeline {
                 timeout_ms,
                 &mut err,
             );
-            check_rs2_error!(err, FrameWaitError::DidErrorDuringFrameWait)?;
+            check_rs2_error!(err, FrameWaitError::DidErrorDuringFrameWait)
+                .map_err(|e| self.classify_disconnect(e))?;
 
             if did_get_frame != 0 {
                 Ok(CompositeFrame::from(NonNull::new(frame).unwrap()))
             } else {
-                Err(FrameWaitError::DidTimeoutBeforeFrameArrival)
+                // librealsense2 reports a device that it could not reconnect to as a timeout.
+                Err(self.classify_disconnect(FrameWaitError::DidTimeoutBeforeFrameArrival))
             }
         }
     }
@@ -141,6 +176,10 @@ impl ActivePipeline {
     ///
     /// Returns [`FrameWaitError::DidErrorDuringFramePoll`] if an internal error occurs while
     /// polling for the next frame.
+    ///
+    /// Returns [`FrameWaitError::DeviceDisconnected`] if polling failed because the device was
+    /// disconnected. A disconnected device that has not caused an error is only noticed by
+    /// [`ActivePipeline::wait`], since polling keeps returning [`Poll::Pending`].
     pub fn poll(&mut self) -> Result<Poll<CompositeFrame>, FrameWaitError> {
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
@@ -150,7 +189,8 @@ impl ActivePipeline {
                 &mut frame_ptr,
                 &mut err,
             );
-            check_rs2_error!(err, FrameWaitError::DidErrorDuringFramePoll)?;
+            check_rs2_error!(err, FrameWaitError::DidErrorDuringFramePoll)
+                .map_err(|e| self.classify_disconnect(e))?;
 
             if did_get_frame != 0 {
                 Ok(Poll::Ready(CompositeFrame::from(
@@ -161,4 +201,70 @@ impl ActivePipeline {
             }
         }
     }
+
+    /// Predicate for whether the device of the pipeline is no longer connected.
+    ///
+    /// Playback and software devices are never connected to the context, so they are never
+    /// considered disconnected. Without a device hub, the device is assumed to be connected.
+    fn is_device_disconnected(&self) -> bool {
+        let device = self.profile.device();
+        match &self.device_hub {
+            Some(hub) => {
+                !device.is_extendable_to(Rs2Extension::Playback)
+                    && !device.is_extendable_to(Rs2Extension::SoftwareDevice)
+                    && !hub.is_device_connected(device)
+            }
+            None => false,
+        }
+    }
+
+    /// Turn `error` into [`FrameWaitError::DeviceDisconnected`] if it was caused by the device
+    /// being disconnected.
+    fn classify_disconnect(&self, error: FrameWaitError) -> FrameWaitError {
+        let disconnected = match &error {
+            FrameWaitError::DidErrorDuringFrameWait(kind, _)
+            | FrameWaitError::DidErrorDuringFramePoll(kind, _) => {
+                *kind == Rs2Exception::CameraDisconnected || self.is_device_disconnected()
+            }
+            FrameWaitError::DidTimeoutBeforeFrameArrival => self.is_device_disconnected(),
+            FrameWaitError::DeviceDisconnected => true,
+            FrameWaitError::PlaybackPaused => false,
+        };
+
+        if disconnected {
+            FrameWaitError::DeviceDisconnected
+        } else {
+            error
+        }
+    }
+
+    /// Iterate over frames from the pipeline, aligned to the stream of kind `align_to`.
+    ///
+    /// This owns an [`Align`] processing block, and is equivalent to calling
+    /// [`ActivePipeline::wait`] followed by [`Align::queue`] and [`Align::wait`] for each set of
+    /// frames. `timeout` applies to both waits.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if the align processing block cannot be
+    /// created.
+    pub fn frames_aligned_to(
+        &mut self,
+        align_to: Rs2StreamKind,
+        timeout: Duration,
+    ) -> Result<AlignedFrames<'_>, ProcessingBlockConstructionError> {
+        let align = Align::new(align_to, 1)?;
+        Ok(AlignedFrames::new(self, align, timeout))
+    }
+
+    /// Get the underlying low-level pointer to the pipeline object.
+    ///
+    /// # Safety
+    ///
+    /// This method is not intended to be called or used outside of the crate itself. Be warned, it
+    /// is _undefined behaviour_ to call [`realsense_sys::rs2_delete_pipeline`] on this pointer. If
+    /// you do, you risk a double-free error when the [`ActivePipeline`] struct itself is dropped.
+    pub(crate) unsafe fn get_raw(&self) -> NonNull<sys::rs2_pipeline> {
+        self.pipeline_ptr
+    }
 }
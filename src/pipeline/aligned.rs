@@ -0,0 +1,51 @@
+//! Defines an iterator over aligned frames from an active pipeline.
+
+use super::active::ActivePipeline;
+use crate::{frame::CompositeFrame, processing_blocks::align::Align};
+use anyhow::Result;
+use std::time::Duration;
+
+/// An iterator that waits for frames from an [`ActivePipeline`] and aligns them to a stream.
+///
+/// Constructed with [`ActivePipeline::frames_aligned_to`]. The iterator never ends on its own;
+/// each call to `next` blocks until the next set of frames has been received and aligned, or
+/// returns an error if either step fails or times out.
+#[derive(Debug)]
+pub struct AlignedFrames<'a> {
+    /// The pipeline that frames are received from.
+    pipeline: &'a mut ActivePipeline,
+    /// The processing block that aligns each set of frames.
+    align: Align,
+    /// The time to wait for frames from the pipeline, and again for the aligned frames.
+    timeout: Duration,
+}
+
+impl<'a> AlignedFrames<'a> {
+    /// Constructs a new iterator from its constituent components.
+    ///
+    /// This is only to be used / called from the [`ActivePipeline`] type.
+    pub(crate) fn new(pipeline: &'a mut ActivePipeline, align: Align, timeout: Duration) -> Self {
+        Self {
+            pipeline,
+            align,
+            timeout,
+        }
+    }
+}
+
+impl<'a> Iterator for AlignedFrames<'a> {
+    type Item = Result<CompositeFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let aligned = self
+            .pipeline
+            .wait(Some(self.timeout))
+            .map_err(anyhow::Error::from)
+            .and_then(|frames| {
+                self.align.queue(frames)?;
+                Ok(self.align.wait(self.timeout)?)
+            });
+
+        Some(aligned)
+    }
+}
@@ -1,10 +1,17 @@
 //! Type for representing an "inactive" pipeline which is unconfigured and cannot acquire frames.
 
 use super::{active::ActivePipeline, profile::PipelineProfile};
-use crate::{check_rs2_error, config::Config, context::Context, kind::Rs2Exception};
+use crate::{
+    check_rs2_error,
+    config::Config,
+    context::Context,
+    device::Device,
+    device_hub::DeviceHub,
+    kind::{OptionSetError, Rs2CameraInfo, Rs2Exception, Rs2Option},
+};
 use anyhow::Result;
 use realsense_sys as sys;
-use std::{convert::TryFrom, ptr::NonNull};
+use std::{convert::TryFrom, ffi::CString, ptr::NonNull};
 use thiserror::Error;
 
 /// Enumeration of possible errors that can occur during pipeline construction.
@@ -13,6 +20,9 @@ pub enum PipelineConstructionError {
     /// The pipeline could not be created from the context.
     #[error("Could not create the pipeline from the provided context. Type: {0}; Reason {1}")]
     CouldNotCreatePipelineFromContext(Rs2Exception, String),
+    /// The device that the pipeline should be bound to has no serial number.
+    #[error("The device to bind the pipeline to has no serial number.")]
+    DeviceHasNoSerialNumber,
 }
 
 /// Enumeration of possible errors that can occur when trying to start the pipeline.
@@ -26,6 +36,19 @@ pub enum PipelineActivationError {
     /// See [`InactivePipeline::can_resolve`] for more information.
     #[error("Config cannot be resolved by any active devices / stream combinations.")]
     ConfigCannotBeResolved,
+    /// The configuration has all streams disabled, and none enabled again since.
+    ///
+    /// See [`Config::disable_all_streams`].
+    #[error("Config has all streams disabled; enable at least one stream to start the pipeline.")]
+    NoStreamsEnabled,
+    /// An option recorded with [`Config::set_sensor_option`] could not be set on the device.
+    #[error("Could not set sensor option {option:?}: {error}")]
+    CouldNotSetSensorOption {
+        /// The option that could not be set.
+        option: Rs2Option,
+        /// The reason the option could not be set.
+        error: OptionSetError,
+    },
 }
 
 /// A type describing an "inactive" pipeline which is unconfigured and cannot acquire frames.
@@ -33,6 +56,10 @@ pub enum PipelineActivationError {
 pub struct InactivePipeline {
     /// A (non-null) pointer to the pipeline.
     pipeline_ptr: NonNull<sys::rs2_pipeline>,
+    /// The serial number of the device that the pipeline is bound to, if any.
+    device_serial: Option<CString>,
+    /// A hub of the pipeline's context, used to tell whether the device is still connected.
+    device_hub: Option<DeviceHub>,
 }
 
 impl Drop for InactivePipeline {
@@ -59,7 +86,13 @@ impl TryFrom<&Context> for InactivePipeline {
                 PipelineConstructionError::CouldNotCreatePipelineFromContext
             )?;
 
-            Ok(Self::new(NonNull::new(pipeline_ptr).unwrap()))
+            // Without a device hub a disconnect is reported as a timeout, so this is not fatal.
+            let device_hub = context.create_device_hub().ok();
+            Ok(Self::new(
+                NonNull::new(pipeline_ptr).unwrap(),
+                None,
+                device_hub,
+            ))
         }
     }
 }
@@ -68,17 +101,85 @@ impl InactivePipeline {
     /// Constructs a new inactive pipeline from the constituent components
     ///
     /// This is only to be used / called from the [`ActivePipeline`] type.
-    pub(crate) fn new(pipeline_ptr: NonNull<sys::rs2_pipeline>) -> Self {
-        Self { pipeline_ptr }
+    pub(crate) fn new(
+        pipeline_ptr: NonNull<sys::rs2_pipeline>,
+        device_serial: Option<CString>,
+        device_hub: Option<DeviceHub>,
+    ) -> Self {
+        Self {
+            pipeline_ptr,
+            device_serial,
+            device_hub,
+        }
+    }
+
+    /// Create a pipeline from the context that is bound to a specific device.
+    ///
+    /// Every config the pipeline is started with is restricted to `device`, as if
+    /// [`Config::enable_device_from_serial`] had been called with the device's serial number. If
+    /// the pipeline is started without a config, a default config for the device is used. The
+    /// binding is kept when the pipeline is stopped and started again.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PipelineConstructionError::DeviceHasNoSerialNumber`] if the serial number of the
+    /// device cannot be read.
+    ///
+    /// Returns [`PipelineConstructionError::CouldNotCreatePipelineFromContext`] if the pipeline
+    /// cannot be created from the context.
+    pub fn for_device(context: &Context, device: &Device) -> Result<Self> {
+        let serial = device
+            .info(Rs2CameraInfo::SerialNumber)
+            .ok_or(PipelineConstructionError::DeviceHasNoSerialNumber)?
+            .to_owned();
+
+        let mut pipeline = Self::try_from(context)?;
+        pipeline.device_serial = Some(serial);
+        Ok(pipeline)
     }
 
     /// Start the pipeline with an optional config.
     ///
-    /// The method consumes inactive pipeline itself, and returns the started pipeine.
-    pub fn start(self, config: Option<Config>) -> Result<ActivePipeline> {
-        unsafe {
+    /// The method consumes inactive pipeline itself, and returns the started pipeine. If the
+    /// pipeline is [bound to a device](InactivePipeline::for_device), the config is restricted to
+    /// that device before starting. Once started, any options recorded with
+    /// [`Config::set_sensor_option`] are set on the sensors of the device.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PipelineActivationError::NoStreamsEnabled`] if the config has all streams
+    /// disabled.
+    ///
+    /// Returns [`PipelineActivationError::ConfigCannotBeResolved`] if the config cannot be
+    /// resolved.
+    ///
+    /// Returns [`PipelineActivationError::CouldNotStartPipelineError`] if the pipeline fails to
+    /// start.
+    ///
+    /// Returns [`PipelineActivationError::CouldNotSetSensorOption`] if a recorded option is not
+    /// supported by any sensor of the device, or cannot be set. The pipeline is stopped again in
+    /// that case.
+    pub fn start(mut self, config: Option<Config>) -> Result<ActivePipeline> {
+        let config = match &self.device_serial {
+            Some(serial) => {
+                let mut conf = config.unwrap_or_else(Config::new);
+                conf.enable_device_from_serial(serial)?;
+                Some(conf)
+            }
+            None => config,
+        };
+
+        let sensor_options = config
+            .as_ref()
+            .map(|conf| conf.sensor_options().to_vec())
+            .unwrap_or_default();
+
+        let active = unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
             let profile_ptr = if let Some(conf) = config {
+                if conf.has_no_streams() {
+                    return Err(anyhow::anyhow!(PipelineActivationError::NoStreamsEnabled));
+                }
                 if !self.can_resolve(&conf) {
                     return Err(anyhow::anyhow!(
                         PipelineActivationError::ConfigCannotBeResolved
@@ -96,11 +197,37 @@ impl InactivePipeline {
             check_rs2_error!(err, PipelineActivationError::CouldNotStartPipelineError)?;
 
             let profile = PipelineProfile::try_from(NonNull::new(profile_ptr).unwrap())?;
-            let active = ActivePipeline::new(self.pipeline_ptr, profile);
+            let active = ActivePipeline::new(
+                self.pipeline_ptr,
+                profile,
+                self.device_serial.take(),
+                self.device_hub.take(),
+            );
 
             std::mem::forget(self);
-            Ok(active)
+            active
+        };
+
+        let mut sensors = active.profile().device().sensors();
+        for (option, value) in sensor_options {
+            let mut was_set = false;
+            for sensor in sensors.iter_mut().filter(|s| s.supports_option(option)) {
+                sensor.set_option(option, value).map_err(|error| {
+                    PipelineActivationError::CouldNotSetSensorOption { option, error }
+                })?;
+                was_set = true;
+            }
+
+            if !was_set {
+                return Err(PipelineActivationError::CouldNotSetSensorOption {
+                    option,
+                    error: OptionSetError::OptionNotSupported,
+                }
+                .into());
+            }
         }
+
+        Ok(active)
     }
 
     /// Resolve a configuration and get the corresponding pipeline profile.
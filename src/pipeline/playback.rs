@@ -0,0 +1,178 @@
+//! Defines a pipeline that streams every frame of a recorded file.
+
+use super::{active::ActivePipeline, inactive::InactivePipeline, FrameWaitError};
+use crate::{
+    check_rs2_error,
+    config::Config,
+    context::Context,
+    device::{Device, PlaybackDevice},
+    frame::CompositeFrame,
+    kind::{PlaybackStatus, Rs2Exception},
+};
+use anyhow::Result;
+use realsense_sys as sys;
+use std::{
+    convert::TryFrom,
+    path::Path,
+    ptr::NonNull,
+    sync::mpsc::{self, Receiver},
+    task::Poll,
+    time::Duration,
+};
+use thiserror::Error;
+
+/// How long to wait for a frame before checking whether the status of the playback has changed.
+const PLAYBACK_WAIT_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Type describing errors that can occur when constructing a playback pipeline.
+#[derive(Error, Debug)]
+pub enum PlaybackPipelineConstructionError {
+    /// Could not get the device that the pipeline is streaming from.
+    #[error("Could not get the playback device from the pipeline. Type: {0}; Reason: {1}")]
+    CouldNotGetDevice(Rs2Exception, String),
+    /// The device that the pipeline is streaming from is not a playback device.
+    #[error("The pipeline is not streaming from a playback device.")]
+    NotAPlaybackDevice,
+}
+
+/// A pipeline streaming every frame of a recorded file, e.g. for offline dataset processing.
+///
+/// Unlike a pipeline started with [`Config::enable_device_from_file`] directly, playback is not
+/// real-time, so no frames are dropped if they are processed slower than they were recorded, and
+/// the file is not looped, so the end of the file can be detected.
+pub struct PlaybackPipeline {
+    /// The pipeline streaming from the file.
+    pipeline: ActivePipeline,
+    /// The device reading frames from the file.
+    device: PlaybackDevice,
+    /// The statuses reported by the status changed callback of the device, in order.
+    statuses: Receiver<PlaybackStatus>,
+}
+
+impl PlaybackPipeline {
+    /// Start streaming every frame of the recorded file at `file`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`NulError`](std::ffi::NulError) if the provided file path cannot be cleanly
+    /// represented as a [`CString`](std::ffi::CString).
+    ///
+    /// Returns any error that [`InactivePipeline::start`] can return, e.g. if the file cannot be
+    /// opened.
+    ///
+    /// Returns [`PlaybackPipelineConstructionError`] if the playback device cannot be obtained
+    /// from the started pipeline.
+    ///
+    /// Returns [`PlaybackError`](crate::device::PlaybackError) if playback cannot be switched
+    /// out of real-time, or its status cannot be followed.
+    pub fn from_file<P>(context: &Context, file: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let mut config = Config::new();
+        config.enable_device_from_file(file, false)?;
+
+        let pipeline = InactivePipeline::try_from(context)?.start(Some(config))?;
+
+        // The device in the pipeline profile is only lent out, so get our own handle to it.
+        let device = unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let profile_ptr =
+                sys::rs2_pipeline_get_active_profile(pipeline.get_raw().as_ptr(), &mut err);
+            check_rs2_error!(err, PlaybackPipelineConstructionError::CouldNotGetDevice)?;
+
+            let device_ptr = sys::rs2_pipeline_profile_get_device(profile_ptr, &mut err);
+            sys::rs2_delete_pipeline_profile(profile_ptr);
+            check_rs2_error!(err, PlaybackPipelineConstructionError::CouldNotGetDevice)?;
+
+            Device::from(NonNull::new(device_ptr).unwrap())
+        };
+
+        let mut device = PlaybackDevice::try_from(device)
+            .map_err(|_| PlaybackPipelineConstructionError::NotAPlaybackDevice)?;
+        device.set_real_time(false)?;
+
+        let (sender, statuses) = mpsc::channel();
+        let initial = sender.clone();
+        device.set_status_changed_callback(move |status| {
+            // The pipeline may be dropped before the device stops reporting.
+            let _ = sender.send(status);
+        })?;
+        // Playback of a short file may have stopped before the callback was registered.
+        let _ = initial.send(device.status());
+
+        Ok(Self {
+            pipeline,
+            device,
+            statuses,
+        })
+    }
+
+    /// Get the device reading frames from the file.
+    pub fn device(&self) -> &PlaybackDevice {
+        &self.device
+    }
+
+    /// Get the pipeline streaming from the file.
+    pub fn pipeline(&mut self) -> &mut ActivePipeline {
+        &mut self.pipeline
+    }
+
+    /// Invoke `f` on every remaining frame of the file, returning once the end of the file has
+    /// been reached.
+    ///
+    /// The end of the file is detected from the status changes reported by the playback device,
+    /// so a slow file read is not mistaken for the end of the file. Playback that has stopped for
+    /// any other reason also ends processing, and so does reaching the end of a looped recording.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`FrameWaitError::DidErrorDuringFrameWait`] or
+    /// [`FrameWaitError::DidErrorDuringFramePoll`] if an internal error occurs while waiting for
+    /// frames.
+    ///
+    /// Returns [`FrameWaitError::PlaybackPaused`] if playback is paused, since no frames arrive
+    /// until it is resumed. Call this again to carry on once it has been.
+    pub fn process_all(&mut self, mut f: impl FnMut(CompositeFrame)) -> Result<(), FrameWaitError> {
+        loop {
+            match self.pipeline.wait(Some(PLAYBACK_WAIT_TIMEOUT)) {
+                Ok(frames) => f(frames),
+                Err(FrameWaitError::DidTimeoutBeforeFrameArrival) => {}
+                Err(e) => return Err(e),
+            }
+
+            match self.status_change() {
+                Some(PlaybackStatus::Stopped) => break,
+                Some(PlaybackStatus::Paused) => return Err(FrameWaitError::PlaybackPaused),
+                _ => {}
+            }
+        }
+
+        // Frames read just before playback stopped may still be queued.
+        while let Poll::Ready(frames) = self.pipeline.poll()? {
+            f(frames);
+        }
+        Ok(())
+    }
+
+    /// Take the status changes reported since the last call, and summarize them.
+    ///
+    /// Returns [`PlaybackStatus::Stopped`] if playback stopped at any point, even if it has
+    /// started again since, as happens at the end of a looped recording. Otherwise returns the
+    /// latest status, or `None` if the status has not changed.
+    fn status_change(&self) -> Option<PlaybackStatus> {
+        let mut latest = None;
+        for status in self.statuses.try_iter() {
+            if status == PlaybackStatus::Stopped {
+                return Some(status);
+            }
+            latest = Some(status);
+        }
+        latest
+    }
+
+    /// Stop streaming from the file.
+    pub fn stop(self) -> InactivePipeline {
+        self.pipeline.stop()
+    }
+}
@@ -1,6 +1,12 @@
 //! Defines a type which holds the device & streams associated with an `ActivePipeline`.
 
-use crate::{check_rs2_error, device::Device, kind::Rs2Exception, stream_profile::StreamProfile};
+use crate::{
+    check_rs2_error,
+    config::{Config, StreamAttributes},
+    device::Device,
+    kind::{Rs2Exception, Rs2Format, Rs2StreamKind},
+    stream_profile::StreamProfile,
+};
 use anyhow::Result;
 use realsense_sys as sys;
 use std::{convert::TryFrom, ptr::NonNull};
@@ -34,6 +40,129 @@ pub enum PipelineProfileConstructionError {
     CouldNotRetrieveStreamCount(Rs2Exception, String),
 }
 
+/// A difference between a stream requested in a [`Config`] and the stream that was resolved.
+///
+/// Returned by [`PipelineProfile::diff`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamMismatch {
+    /// No stream of the requested kind (and index, if one was requested) was resolved.
+    Missing {
+        /// The requested stream kind.
+        stream: Rs2StreamKind,
+        /// The requested stream index, if any.
+        index: Option<usize>,
+    },
+    /// The stream was resolved with a different resolution than requested.
+    Resolution {
+        /// The stream kind.
+        stream: Rs2StreamKind,
+        /// The requested `(width, height)`, where zero is "any".
+        requested: (usize, usize),
+        /// The resolved `(width, height)`.
+        resolved: (usize, usize),
+    },
+    /// The stream was resolved with a different format than requested.
+    Format {
+        /// The stream kind.
+        stream: Rs2StreamKind,
+        /// The requested format.
+        requested: Rs2Format,
+        /// The resolved format.
+        resolved: Rs2Format,
+    },
+    /// The stream was resolved with a different framerate than requested.
+    ///
+    /// This is common when a device is connected over USB2, which cannot carry every framerate.
+    Framerate {
+        /// The stream kind.
+        stream: Rs2StreamKind,
+        /// The requested framerate.
+        requested: usize,
+        /// The resolved framerate.
+        resolved: usize,
+    },
+}
+
+impl std::fmt::Display for StreamMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StreamMismatch::Missing {
+                stream,
+                index: Some(index),
+            } => write!(f, "{} stream {} was not resolved", stream, index),
+            StreamMismatch::Missing {
+                stream,
+                index: None,
+            } => write!(f, "{} stream was not resolved", stream),
+            StreamMismatch::Resolution {
+                stream,
+                requested,
+                resolved,
+            } => write!(
+                f,
+                "{} stream resolved at {}x{} instead of {}x{}",
+                stream, resolved.0, resolved.1, requested.0, requested.1
+            ),
+            StreamMismatch::Format {
+                stream,
+                requested,
+                resolved,
+            } => write!(
+                f,
+                "{} stream resolved as {} instead of {}",
+                stream, resolved, requested
+            ),
+            StreamMismatch::Framerate {
+                stream,
+                requested,
+                resolved,
+            } => write!(
+                f,
+                "{} stream resolved at {} fps instead of {} fps",
+                stream, resolved, requested
+            ),
+        }
+    }
+}
+
+/// Compare the attributes of a requested stream against those of the stream resolved for it.
+///
+/// Wildcards in `requested` never mismatch, and neither do dimensions that are unknown (zero) in
+/// `resolved`, as is the case for streams without images.
+fn compare_attributes(
+    requested: &StreamAttributes,
+    resolved: &StreamAttributes,
+) -> Vec<StreamMismatch> {
+    let stream = requested.stream;
+    let mut mismatches = Vec::new();
+
+    let has_resolution = resolved.width != 0 && resolved.height != 0;
+    let width_differs = requested.width != 0 && requested.width != resolved.width;
+    let height_differs = requested.height != 0 && requested.height != resolved.height;
+    if has_resolution && (width_differs || height_differs) {
+        mismatches.push(StreamMismatch::Resolution {
+            stream,
+            requested: (requested.width, requested.height),
+            resolved: (resolved.width, resolved.height),
+        });
+    }
+    if requested.format != Rs2Format::Any && requested.format != resolved.format {
+        mismatches.push(StreamMismatch::Format {
+            stream,
+            requested: requested.format,
+            resolved: resolved.format,
+        });
+    }
+    if requested.framerate != 0 && requested.framerate != resolved.framerate {
+        mismatches.push(StreamMismatch::Framerate {
+            stream,
+            requested: requested.framerate,
+            resolved: resolved.framerate,
+        });
+    }
+    mismatches
+}
+
 impl TryFrom<NonNull<sys::rs2_pipeline_profile>> for PipelineProfile {
     type Error = anyhow::Error;
 
@@ -100,4 +229,102 @@ impl PipelineProfile {
     pub fn streams(&self) -> &Vec<StreamProfile> {
         &self.streams
     }
+
+    /// Report where the streams of this profile differ from the streams requested in `requested`.
+    ///
+    /// librealsense2 resolves a configuration to the closest streams the device supports, which
+    /// need not be exactly the streams that were asked for. E.g. a device connected over USB2 may
+    /// only offer a lower framerate. Each stream enabled with
+    /// [`Config::enable_stream`](crate::config::Config::enable_stream) is matched against a
+    /// resolved stream of the same kind (and index, if one was requested), and every attribute
+    /// that differs is reported. Attributes left for librealsense2 to pick are never reported.
+    ///
+    /// An empty result means that every requested stream was resolved exactly as asked. Streams
+    /// enabled with [`Config::enable_all_streams`](crate::config::Config::enable_all_streams) or
+    /// through a device serial or recording cannot be compared, and are not reported.
+    pub fn diff(&self, requested: &Config) -> Vec<StreamMismatch> {
+        let mut used = vec![false; self.streams.len()];
+        let mut mismatches = Vec::new();
+
+        for request in requested.requested_streams() {
+            let stream = request.attributes.stream;
+            let found = self.streams.iter().enumerate().position(|(i, profile)| {
+                !used[i]
+                    && profile.kind() == stream
+                    && request.index.map_or(true, |index| profile.index() == index)
+            });
+
+            let i = match found {
+                Some(i) => i,
+                None => {
+                    mismatches.push(StreamMismatch::Missing {
+                        stream,
+                        index: request.index,
+                    });
+                    continue;
+                }
+            };
+            used[i] = true;
+
+            let profile = &self.streams[i];
+            let (width, height) = profile.resolution().unwrap_or((0, 0));
+            let resolved = StreamAttributes {
+                stream,
+                width,
+                height,
+                format: profile.format(),
+                framerate: usize::try_from(profile.framerate()).unwrap_or(0),
+            };
+            mismatches.extend(compare_attributes(&request.attributes, &resolved));
+        }
+        mismatches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attributes(
+        width: usize,
+        height: usize,
+        format: Rs2Format,
+        framerate: usize,
+    ) -> StreamAttributes {
+        StreamAttributes {
+            stream: Rs2StreamKind::Depth,
+            width,
+            height,
+            format,
+            framerate,
+        }
+    }
+
+    #[test]
+    fn wildcards_never_mismatch() {
+        let requested = attributes(0, 0, Rs2Format::Any, 0);
+        let resolved = attributes(640, 480, Rs2Format::Z16, 30);
+        assert!(compare_attributes(&requested, &resolved).is_empty());
+    }
+
+    #[test]
+    fn downgraded_framerate_is_reported() {
+        let requested = attributes(640, 480, Rs2Format::Z16, 90);
+        let resolved = attributes(640, 480, Rs2Format::Z16, 30);
+        assert_eq!(
+            compare_attributes(&requested, &resolved),
+            vec![StreamMismatch::Framerate {
+                stream: Rs2StreamKind::Depth,
+                requested: 90,
+                resolved: 30,
+            }]
+        );
+    }
+
+    #[test]
+    fn every_differing_attribute_is_reported() {
+        let requested = attributes(1280, 0, Rs2Format::Z16, 30);
+        let resolved = attributes(848, 480, Rs2Format::Y8, 15);
+        assert_eq!(compare_attributes(&requested, &resolved).len(), 3);
+    }
 }
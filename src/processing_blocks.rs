@@ -1,8 +1,22 @@
 //! Processing blocks available to the RealSense lib
+//!
+//! None of the processing blocks implement `Clone`: each owns a librealsense2 processing block
+//! and frame queue, which are deleted when it is dropped, and creating new ones can fail. To run
+//! a second block with the same settings, construct another one and copy the options over, e.g.
+//! with [`dump_options`](options::ProcessingBlockOptions::dump_options) and
+//! [`set_option`](options::ProcessingBlockOptions::set_option).
 
 pub mod align;
+pub mod chain;
 pub mod decimation;
+pub mod disparity;
 pub mod errors;
 pub mod hole_filling;
+pub mod options;
+pub mod overflow;
+mod queue;
+pub mod recommended;
+pub mod sequence_id;
 pub mod spatial;
+pub mod temporal;
 pub mod threshold;
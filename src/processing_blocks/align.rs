@@ -7,37 +7,74 @@ use crate::{
     check_rs2_error,
     frame::CompositeFrame,
     kind::Rs2StreamKind,
-    processing_blocks::errors::{ProcessFrameError, ProcessingBlockConstructionError},
+    processing_blocks::{
+        errors::{ProcessFrameError, ProcessingBlockConstructionError},
+        options::{sealed::OptionsPtr, ProcessingBlockOptions},
+        overflow::{sealed::ProcessingQueueRef, QueueOverflow},
+        queue::ProcessingQueue,
+    },
 };
 use anyhow::Result;
 use realsense_sys as sys;
 use std::{convert::TryFrom, ptr::NonNull, task::Poll, time::Duration};
 
 /// Processing Block and Frame Queue for aligning a stream to a certain [StreamKind]
-#[derive(Debug, Clone)]
+///
+/// An `Align` is meant to be constructed once and reused for every set of frames: the processing
+/// block keeps the mapping between the streams from one call to the next, so it only has to be
+/// recomputed when the stream profiles change.
+#[derive(Debug)]
 pub struct Align {
+    /// The kind of stream that the other streams are aligned to.
+    align_to: Rs2StreamKind,
+    /// Whether a set of frames holding the stream being aligned to has been queued yet.
+    ///
+    /// The target stream is only checked for on the first queued frames, since the streams of a
+    /// pipeline do not change while it is running.
+    target_validated: bool,
     /// The processing block for the "Align" method
     processing_block: NonNull<sys::rs2_processing_block>,
     /// The frame queue upon which the processing block will deposit aligned frames. We check this
     /// for completed block operations.
-    processing_queue: NonNull<sys::rs2_frame_queue>,
+    processing_queue: ProcessingQueue,
 }
 
 impl Drop for Align {
     fn drop(&mut self) {
         unsafe {
-            sys::rs2_delete_frame_queue(self.processing_queue.as_ptr());
             sys::rs2_delete_processing_block(self.processing_block.as_ptr());
         }
     }
 }
 
+impl OptionsPtr for Align {
+    fn options_ptr(&self) -> NonNull<sys::rs2_options> {
+        self.processing_block.cast::<sys::rs2_options>()
+    }
+}
+
+impl ProcessingBlockOptions for Align {}
+
+impl ProcessingQueueRef for Align {
+    fn processing_queue(&self) -> &ProcessingQueue {
+        &self.processing_queue
+    }
+
+    fn processing_queue_mut(&mut self) -> &mut ProcessingQueue {
+        &mut self.processing_queue
+    }
+}
+
+impl QueueOverflow for Align {}
+
 impl Align {
     /// Create a new Align object
     pub fn new(
         align_to: Rs2StreamKind,
         processing_queue_size: i32,
     ) -> Result<Self, ProcessingBlockConstructionError> {
+        ProcessingBlockConstructionError::check_queue_size(processing_queue_size)?;
+
         let (processing_block, processing_queue) = unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
 
@@ -47,28 +84,47 @@ impl Align {
                 ProcessingBlockConstructionError::CouldNotCreateProcessingBlock
             )?;
 
-            let queue_ptr = sys::rs2_create_frame_queue(processing_queue_size, &mut err);
-            check_rs2_error!(
-                err,
-                ProcessingBlockConstructionError::CouldNotCreateProcessingQueue
-            )?;
-
-            sys::rs2_start_processing_queue(ptr, queue_ptr, &mut err);
-            check_rs2_error!(
-                err,
-                ProcessingBlockConstructionError::CouldNotStartProcessingQueue
-            )?;
-            (NonNull::new(ptr).unwrap(), NonNull::new(queue_ptr).unwrap())
+            let processing_queue =
+                ProcessingQueue::start(ptr, processing_queue_size).map_err(|e| {
+                    sys::rs2_delete_processing_block(ptr);
+                    e
+                })?;
+            (NonNull::new(ptr).unwrap(), processing_queue)
         };
 
         Ok(Self {
+            align_to,
+            target_validated: false,
             processing_block,
             processing_queue,
         })
     }
 
-    /// Own and process the composite frame and return the aligned frames.
+    /// Get the kind of stream that the other streams are aligned to.
+    pub fn align_to(&self) -> Rs2StreamKind {
+        self.align_to
+    }
+
+    /// Own the composite frame and queue it to be aligned to the
+    /// [`align_to`](Self::align_to) stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::MissingAlignTarget`] if these are the first frames queued and
+    /// they do not include the stream being aligned to. librealsense2 would otherwise pass them
+    /// through unaligned.
+    ///
+    /// Returns [`ProcessFrameError::QueueFull`] if the processing queue is full and the overflow
+    /// policy is [`Reject`](crate::processing_blocks::overflow::QueueOverflowPolicy::Reject).
     pub fn queue(&mut self, frames: CompositeFrame) -> Result<(), ProcessFrameError> {
+        if !self.target_validated {
+            if !frames.contains_stream(self.align_to) {
+                return Err(ProcessFrameError::MissingAlignTarget(self.align_to));
+            }
+            self.target_validated = true;
+        }
+
+        self.processing_queue.check_overflow()?;
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
             sys::rs2_process_frame(
@@ -76,7 +132,7 @@ impl Align {
                 frames.get_owned_raw().as_ptr(),
                 &mut err,
             );
-            check_rs2_error!(err, |kind, context| { ProcessFrameError { kind, context } })?;
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
             Ok(())
         }
     }
@@ -89,7 +145,7 @@ impl Align {
 
             let aligned_frame =
                 sys::rs2_wait_for_frame(self.processing_queue.as_ptr(), timeout_millis, &mut err);
-            check_rs2_error!(err, |kind, context| { ProcessFrameError { kind, context } })?;
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
             Ok(CompositeFrame::from(NonNull::new(aligned_frame).unwrap()))
         }
     }
@@ -103,7 +159,7 @@ impl Align {
                 sys::rs2_poll_for_frame(self.processing_queue.as_ptr(), &mut frame, &mut err);
 
             // Check for errors
-            check_rs2_error!(err, |kind, context| { ProcessFrameError { kind, context } })?;
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
 
             // Check for queue readiness
             if is_ready == 0 {
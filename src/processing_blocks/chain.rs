@@ -0,0 +1,268 @@
+//! A chain of processing blocks applied to depth frames one after the other
+//!
+//! Based on the recommended order of post-processing filters here:
+//! https://github.com/IntelRealSense/librealsense/blob/4673a37d981164af8eeb8e296e430fc1427e008d/doc/post-processing-filters.md?plain=1#L111
+
+use crate::{
+    check_rs2_error,
+    frame::{DepthFrame, FrameEx},
+    kind::Rs2Option,
+    processing_blocks::{
+        errors::{ProcessFrameError, ProcessingBlockConstructionError},
+        queue::{output_frame, ProcessingQueue},
+    },
+};
+use anyhow::Result;
+use realsense_sys as sys;
+use std::{convert::TryInto, ptr::NonNull, thread, time::Duration};
+
+/// The decimation magnitude used by [`FilterChain::recommended_depth`].
+pub const RECOMMENDED_DECIMATION_MAGNITUDE: f32 = 2.0;
+
+/// A single processing block of a [`FilterChain`], and the queue it deposits its results on.
+#[derive(Debug)]
+struct FilterStage {
+    /// The processing block of the stage.
+    processing_block: NonNull<sys::rs2_processing_block>,
+    /// The frame queue upon which the processing block deposits its results.
+    processing_queue: ProcessingQueue,
+}
+
+impl Drop for FilterStage {
+    fn drop(&mut self) {
+        unsafe {
+            sys::rs2_delete_processing_block(self.processing_block.as_ptr());
+        }
+    }
+}
+
+impl FilterStage {
+    /// Create a stage from the result of one of the `rs2_create_*` processing block functions.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` and `err` must have been returned by the same `rs2_create_*` call.
+    unsafe fn new(
+        ptr: *mut sys::rs2_processing_block,
+        err: *mut sys::rs2_error,
+    ) -> Result<Self, ProcessingBlockConstructionError> {
+        check_rs2_error!(
+            err,
+            ProcessingBlockConstructionError::CouldNotCreateProcessingBlock
+        )?;
+
+        // Frames are processed one at a time, so the queue never holds more than one result.
+        let processing_queue = ProcessingQueue::start(ptr, 1).map_err(|e| {
+            sys::rs2_delete_processing_block(ptr);
+            e
+        })?;
+        Ok(Self {
+            processing_block: NonNull::new(ptr).unwrap(),
+            processing_queue,
+        })
+    }
+
+    /// Process `frame` and wait for the result.
+    ///
+    /// Takes ownership of `frame`, and hands ownership of the result to the caller.
+    ///
+    /// # Safety
+    ///
+    /// `frame` must point to a valid frame owned by the caller.
+    unsafe fn process(
+        &mut self,
+        frame: NonNull<sys::rs2_frame>,
+        timeout: Duration,
+    ) -> Result<NonNull<sys::rs2_frame>, ProcessFrameError> {
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        sys::rs2_process_frame(self.processing_block.as_ptr(), frame.as_ptr(), &mut err);
+        check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
+
+        self.processing_queue.wait(timeout)
+    }
+}
+
+/// A chain of processing blocks that depth frames are passed through in order.
+///
+/// Unlike the individual processing blocks, which each have a `queue` / `wait` cycle, a chain
+/// processes one frame at a time with [`process`](Self::process), feeding the result of each
+/// block straight into the next. This makes it possible to pass frames through stages in the
+/// disparity domain without converting them between frame types in between.
+#[derive(Debug)]
+pub struct FilterChain {
+    /// The stages of the chain, in the order they are applied.
+    stages: Vec<FilterStage>,
+}
+
+impl FilterChain {
+    /// Create the chain of filters that Intel recommends for post-processing depth.
+    ///
+    /// The filters are, in order:
+    ///
+    /// 1. Decimation, with a magnitude of [`RECOMMENDED_DECIMATION_MAGNITUDE`]
+    /// 2. Depth to disparity
+    /// 3. Spatial
+    /// 4. Temporal
+    /// 5. Disparity to depth
+    /// 6. Hole filling
+    ///
+    /// The spatial and temporal filters are applied in the disparity domain, where depth noise is
+    /// uniform. All filters other than decimation use the librealsense2 defaults.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if any of the filters cannot be created, or if
+    /// the decimation magnitude cannot be set.
+    pub fn recommended_depth() -> Result<Self, ProcessingBlockConstructionError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+
+            let decimation =
+                FilterStage::new(sys::rs2_create_decimation_filter_block(&mut err), err)?;
+            sys::rs2_set_option(
+                decimation
+                    .processing_block
+                    .cast::<sys::rs2_options>()
+                    .as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (Rs2Option::FilterMagnitude as i32).try_into().unwrap(),
+                RECOMMENDED_DECIMATION_MAGNITUDE,
+                &mut err,
+            );
+            check_rs2_error!(err, ProcessingBlockConstructionError::CouldNotSetOption)?;
+
+            let to_disparity =
+                FilterStage::new(sys::rs2_create_disparity_transform_block(1, &mut err), err)?;
+            let spatial = FilterStage::new(sys::rs2_create_spatial_filter_block(&mut err), err)?;
+            let temporal = FilterStage::new(sys::rs2_create_temporal_filter_block(&mut err), err)?;
+            let to_depth =
+                FilterStage::new(sys::rs2_create_disparity_transform_block(0, &mut err), err)?;
+            let hole_filling =
+                FilterStage::new(sys::rs2_create_hole_filling_filter_block(&mut err), err)?;
+
+            Ok(Self {
+                stages: vec![
+                    decimation,
+                    to_disparity,
+                    spatial,
+                    temporal,
+                    to_depth,
+                    hole_filling,
+                ],
+            })
+        }
+    }
+
+    /// Get the number of filters in the chain.
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// Predicate for whether the chain has no filters, and returns frames unchanged.
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Pass `frame` through every filter of the chain in order, and return the result.
+    ///
+    /// `timeout` applies to waiting for the result of each filter in turn.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError`] if any filter fails to process the frame or times out, or if
+    /// the result of the chain is not a depth frame.
+    pub fn process(
+        &mut self,
+        frame: DepthFrame,
+        timeout: Duration,
+    ) -> Result<DepthFrame, ProcessFrameError> {
+        unsafe {
+            let mut frame_ptr = frame.get_owned_raw();
+            for stage in &mut self.stages {
+                frame_ptr = stage.process(frame_ptr, timeout)?;
+            }
+            output_frame(frame_ptr)
+        }
+    }
+
+    /// Pass a batch of frames through chains of filters, using up to `threads` threads.
+    ///
+    /// The frames are split into `threads` contiguous runs, and each run is passed through its
+    /// own chain, created with `make_chain`, on a thread of its own. Results are returned in the
+    /// order of `frames`, and a frame that fails to process does not stop the rest of the batch.
+    ///
+    /// This suits offline processing, e.g. of the frames of a recording, where each frame can be
+    /// processed independently. Stateful filters such as the temporal filter only see the frames
+    /// of their own run, so their history restarts at the boundaries between runs. Use
+    /// [`process`](Self::process) on a single chain if that matters.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError`] if any of the chains cannot be created.
+    pub fn process_batch<F>(
+        make_chain: F,
+        frames: Vec<DepthFrame>,
+        threads: usize,
+        timeout: Duration,
+    ) -> Result<Vec<Result<DepthFrame, ProcessFrameError>>, ProcessingBlockConstructionError>
+    where
+        F: Fn() -> Result<FilterChain, ProcessingBlockConstructionError> + Sync,
+    {
+        let run_len = ((frames.len() + threads.max(1) - 1) / threads.max(1)).max(1);
+        let mut runs = Vec::new();
+        let mut frames = frames.into_iter().peekable();
+        while frames.peek().is_some() {
+            runs.push(frames.by_ref().take(run_len).collect::<Vec<_>>());
+        }
+
+        let make_chain = &make_chain;
+        let results = thread::scope(|scope| {
+            let handles: Vec<_> = runs
+                .into_iter()
+                .map(|run| {
+                    scope.spawn(move || {
+                        let mut chain = make_chain()?;
+                        Ok(run
+                            .into_iter()
+                            .map(|frame| chain.process(frame, timeout))
+                            .collect::<Vec<_>>())
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| match handle.join() {
+                    Ok(results) => results,
+                    Err(panic) => std::panic::resume_unwind(panic),
+                })
+                .collect::<Result<Vec<_>, ProcessingBlockConstructionError>>()
+        })?;
+
+        Ok(results.into_iter().flatten().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recommended_depth_chain_has_every_filter() {
+        let chain = FilterChain::recommended_depth().unwrap();
+        assert_eq!(chain.len(), 6);
+        assert!(!chain.is_empty());
+    }
+
+    #[test]
+    fn empty_batch_needs_no_chains() {
+        let results = FilterChain::process_batch(
+            || panic!("No chain should be created for an empty batch."),
+            Vec::new(),
+            4,
+            Duration::from_secs(1),
+        )
+        .unwrap();
+        assert!(results.is_empty());
+    }
+}
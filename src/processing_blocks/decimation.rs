@@ -6,35 +6,65 @@
 use crate::{
     check_rs2_error,
     frame::{DepthFrame, FrameEx},
-    processing_blocks::errors::{ProcessFrameError, ProcessingBlockConstructionError},
+    kind::{OptionSetError, Rs2Option},
+    processing_blocks::{
+        errors::{ProcessFrameError, ProcessingBlockConstructionError},
+        options::{sealed::OptionsPtr, ProcessingBlockOptions},
+        overflow::{sealed::ProcessingQueueRef, QueueOverflow},
+        queue::{output_frame, ProcessingQueue},
+    },
 };
 use anyhow::Result;
 use realsense_sys as sys;
-use std::{convert::TryFrom, ptr::NonNull, task::Poll, time::Duration};
+use std::{convert::TryFrom, ops::RangeInclusive, ptr::NonNull, task::Poll, time::Duration};
+
+/// The range of decimation magnitudes accepted by [`Decimation::set_magnitude`].
+pub const DECIMATION_MAGNITUDE_RANGE: RangeInclusive<f32> = 2.0..=8.0;
 
 /// Applies decimation filter and plots modified params. Note that due to the modified frame size,
 /// the decimated frame replaces the original one.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct Decimation {
     /// The processing block for the "Decimation" method
     processing_block: NonNull<sys::rs2_processing_block>,
     /// The frame queue upon which the processing block will deposit decimated frames. We check this
     /// for completed block operations.
-    processing_queue: NonNull<sys::rs2_frame_queue>,
+    processing_queue: ProcessingQueue,
 }
 
 impl Drop for Decimation {
     fn drop(&mut self) {
         unsafe {
-            sys::rs2_delete_frame_queue(self.processing_queue.as_ptr());
             sys::rs2_delete_processing_block(self.processing_block.as_ptr());
         }
     }
 }
 
+impl OptionsPtr for Decimation {
+    fn options_ptr(&self) -> NonNull<sys::rs2_options> {
+        self.processing_block.cast::<sys::rs2_options>()
+    }
+}
+
+impl ProcessingBlockOptions for Decimation {}
+
+impl ProcessingQueueRef for Decimation {
+    fn processing_queue(&self) -> &ProcessingQueue {
+        &self.processing_queue
+    }
+
+    fn processing_queue_mut(&mut self) -> &mut ProcessingQueue {
+        &mut self.processing_queue
+    }
+}
+
+impl QueueOverflow for Decimation {}
+
 impl Decimation {
     /// Create a new Decimation object
     pub fn new(processing_queue_size: i32) -> Result<Self, ProcessingBlockConstructionError> {
+        ProcessingBlockConstructionError::check_queue_size(processing_queue_size)?;
+
         let (processing_block, processing_queue) = unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
 
@@ -44,18 +74,12 @@ impl Decimation {
                 ProcessingBlockConstructionError::CouldNotCreateProcessingBlock
             )?;
 
-            let queue_ptr = sys::rs2_create_frame_queue(processing_queue_size, &mut err);
-            check_rs2_error!(
-                err,
-                ProcessingBlockConstructionError::CouldNotCreateProcessingQueue
-            )?;
-
-            sys::rs2_start_processing_queue(ptr, queue_ptr, &mut err);
-            check_rs2_error!(
-                err,
-                ProcessingBlockConstructionError::CouldNotStartProcessingQueue
-            )?;
-            (NonNull::new(ptr).unwrap(), NonNull::new(queue_ptr).unwrap())
+            let processing_queue =
+                ProcessingQueue::start(ptr, processing_queue_size).map_err(|e| {
+                    sys::rs2_delete_processing_block(ptr);
+                    e
+                })?;
+            (NonNull::new(ptr).unwrap(), processing_queue)
         };
 
         Ok(Self {
@@ -64,8 +88,14 @@ impl Decimation {
         })
     }
 
-    /// Own and process the composite frame and return the decimated frames.
+    /// Own the depth frame and queue it to be decimated.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::QueueFull`] if the processing queue is full and the overflow
+    /// policy is [`Reject`](crate::processing_blocks::overflow::QueueOverflowPolicy::Reject).
     pub fn queue(&mut self, frame: DepthFrame) -> Result<(), ProcessFrameError> {
+        self.processing_queue.check_overflow()?;
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
             sys::rs2_process_frame(
@@ -73,7 +103,7 @@ impl Decimation {
                 frame.get_owned_raw().as_ptr(),
                 &mut err,
             );
-            check_rs2_error!(err, |kind, context| { ProcessFrameError { kind, context } })?;
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
             Ok(())
         }
     }
@@ -85,8 +115,8 @@ impl Decimation {
             let timeout_millis = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
             let decimated_frame =
                 sys::rs2_wait_for_frame(self.processing_queue.as_ptr(), timeout_millis, &mut err);
-            check_rs2_error!(err, |kind, context| { ProcessFrameError { kind, context } })?;
-            Ok(DepthFrame::try_from(NonNull::new(decimated_frame).unwrap()).unwrap())
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
+            output_frame(NonNull::new(decimated_frame).unwrap())
         }
     }
 
@@ -99,16 +129,39 @@ impl Decimation {
                 sys::rs2_poll_for_frame(self.processing_queue.as_ptr(), &mut frame, &mut err);
 
             // Check for errors
-            check_rs2_error!(err, |kind, context| { ProcessFrameError { kind, context } })?;
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
 
             // Check for queue readiness
             if is_ready == 0 {
                 Ok(Poll::Pending)
             } else {
-                Ok(Poll::Ready(
-                    DepthFrame::try_from(NonNull::new(frame).unwrap()).unwrap(),
-                ))
+                let frame = output_frame(NonNull::new(frame).unwrap())?;
+                Ok(Poll::Ready(frame))
             }
         }
     }
+
+    /// Sets the decimation magnitude (the factor by which each axis of the frame is scaled down).
+    ///
+    /// This maps to [`Rs2Option::FilterMagnitude`] on the underlying processing block. A
+    /// magnitude of `n` replaces each `n x n` block of pixels with a single pixel, so the
+    /// resulting frame is `1 / n` of the original width and height.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptionSetError::OptionValueOutOfRange`] if `magnitude` is not within
+    /// [`DECIMATION_MAGNITUDE_RANGE`].
+    ///
+    /// Otherwise returns any error that [`ProcessingBlockOptions::set_option`] can return.
+    pub fn set_magnitude(&mut self, magnitude: f32) -> Result<(), OptionSetError> {
+        if !DECIMATION_MAGNITUDE_RANGE.contains(&magnitude) {
+            return Err(OptionSetError::OptionValueOutOfRange {
+                value: magnitude,
+                min: *DECIMATION_MAGNITUDE_RANGE.start(),
+                max: *DECIMATION_MAGNITUDE_RANGE.end(),
+            });
+        }
+
+        self.set_option(Rs2Option::FilterMagnitude, magnitude)
+    }
 }
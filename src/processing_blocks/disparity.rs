@@ -0,0 +1,144 @@
+//! Processing block that transforms the depth stream into disparity
+//!
+//! Based on an example here:
+//! https://github.com/IntelRealSense/librealsense/blob/4673a37d981164af8eeb8e296e430fc1427e008d/doc/post-processing-filters.md?plain=1#L111
+
+use crate::{
+    check_rs2_error,
+    frame::{DepthFrame, DisparityFrame, FrameEx},
+    processing_blocks::{
+        errors::{ProcessFrameError, ProcessingBlockConstructionError},
+        options::{sealed::OptionsPtr, ProcessingBlockOptions},
+        overflow::{sealed::ProcessingQueueRef, QueueOverflow},
+        queue::{output_frame, ProcessingQueue},
+    },
+};
+use anyhow::Result;
+use realsense_sys as sys;
+use std::{convert::TryFrom, ptr::NonNull, task::Poll, time::Duration};
+
+/// Transforms depth frames into disparity frames.
+///
+/// Disparity is inversely proportional to depth, so filters such as [`Spatial`] and [`Temporal`]
+/// are often applied in the disparity domain, where the magnitude of depth noise is uniform.
+///
+/// [`Spatial`]: crate::processing_blocks::spatial::Spatial
+/// [`Temporal`]: crate::processing_blocks::temporal::Temporal
+#[derive(Debug)]
+pub struct DisparityTransform {
+    /// The processing block for the "Disparity Transform" method
+    processing_block: NonNull<sys::rs2_processing_block>,
+    /// The frame queue upon which the processing block will deposit disparity frames. We check this
+    /// for completed block operations.
+    processing_queue: ProcessingQueue,
+}
+
+impl Drop for DisparityTransform {
+    fn drop(&mut self) {
+        unsafe {
+            sys::rs2_delete_processing_block(self.processing_block.as_ptr());
+        }
+    }
+}
+
+impl OptionsPtr for DisparityTransform {
+    fn options_ptr(&self) -> NonNull<sys::rs2_options> {
+        self.processing_block.cast::<sys::rs2_options>()
+    }
+}
+
+impl ProcessingBlockOptions for DisparityTransform {}
+
+impl ProcessingQueueRef for DisparityTransform {
+    fn processing_queue(&self) -> &ProcessingQueue {
+        &self.processing_queue
+    }
+
+    fn processing_queue_mut(&mut self) -> &mut ProcessingQueue {
+        &mut self.processing_queue
+    }
+}
+
+impl QueueOverflow for DisparityTransform {}
+
+impl DisparityTransform {
+    /// Create a new DisparityTransform object, transforming depth into disparity
+    pub fn new(processing_queue_size: i32) -> Result<Self, ProcessingBlockConstructionError> {
+        ProcessingBlockConstructionError::check_queue_size(processing_queue_size)?;
+
+        let (processing_block, processing_queue) = unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+
+            let ptr = sys::rs2_create_disparity_transform_block(1, &mut err);
+            check_rs2_error!(
+                err,
+                ProcessingBlockConstructionError::CouldNotCreateProcessingBlock
+            )?;
+
+            let processing_queue =
+                ProcessingQueue::start(ptr, processing_queue_size).map_err(|e| {
+                    sys::rs2_delete_processing_block(ptr);
+                    e
+                })?;
+            (NonNull::new(ptr).unwrap(), processing_queue)
+        };
+
+        Ok(Self {
+            processing_block,
+            processing_queue,
+        })
+    }
+
+    /// Own the depth frame and queue it to be transformed into a disparity frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::QueueFull`] if the processing queue is full and the overflow
+    /// policy is [`Reject`](crate::processing_blocks::overflow::QueueOverflowPolicy::Reject).
+    pub fn queue(&mut self, frame: DepthFrame) -> Result<(), ProcessFrameError> {
+        self.processing_queue.check_overflow()?;
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_process_frame(
+                self.processing_block.as_ptr(),
+                frame.get_owned_raw().as_ptr(),
+                &mut err,
+            );
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
+            Ok(())
+        }
+    }
+
+    /// Wait to receive the results of the processing block
+    pub fn wait(&mut self, timeout: Duration) -> Result<DisparityFrame, ProcessFrameError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let timeout_millis = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+            let disparity_frame =
+                sys::rs2_wait_for_frame(self.processing_queue.as_ptr(), timeout_millis, &mut err);
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
+            output_frame(NonNull::new(disparity_frame).unwrap())
+        }
+    }
+
+    /// Poll to receive the results of the processing block
+    pub fn poll(&mut self) -> Result<Poll<DisparityFrame>, ProcessFrameError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let mut frame = std::ptr::null_mut::<sys::rs2_frame>();
+            let is_ready =
+                sys::rs2_poll_for_frame(self.processing_queue.as_ptr(), &mut frame, &mut err);
+
+            // Check for errors
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
+
+            // Check for queue readiness
+            if is_ready == 0 {
+                Ok(Poll::Pending)
+            } else {
+                let frame = output_frame(NonNull::new(frame).unwrap())?;
+                Ok(Poll::Ready(frame))
+            }
+        }
+    }
+}
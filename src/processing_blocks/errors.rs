@@ -1,6 +1,7 @@
 //! Errors that can occur when handling processing blocks.
 
-use crate::kind::Rs2Exception;
+use crate::kind::{Rs2Exception, Rs2StreamKind};
+use std::fmt::Display;
 use thiserror::Error;
 
 /// Enumerations of possible errors that can occur when creating a Processing Block
@@ -17,14 +18,81 @@ pub enum ProcessingBlockConstructionError {
     /// Could not start processing the queue
     #[error("Could not start processing the queue. Type: {0}; Reason: {1}")]
     CouldNotStartProcessingQueue(Rs2Exception, String),
+
+    /// Could not set an option of the processing block
+    #[error("Could not set an option of the processing block. Type: {0}; Reason: {1}")]
+    CouldNotSetOption(Rs2Exception, String),
+
+    /// The processing queue size is not positive, so the queue could not hold any results
+    #[error("Invalid processing queue size: {0}. The size must be at least 1.")]
+    InvalidQueueSize(i32),
+}
+
+impl ProcessingBlockConstructionError {
+    /// Check that a processing queue of `size` can hold at least one result.
+    ///
+    /// librealsense2 does not reject a queue that cannot hold anything, which only shows later as
+    /// frames that never come out of the processing block.
+    pub(crate) fn check_queue_size(size: i32) -> Result<(), Self> {
+        if size > 0 {
+            Ok(())
+        } else {
+            Err(ProcessingBlockConstructionError::InvalidQueueSize(size))
+        }
+    }
 }
 
 /// Enumerations of possible errors that can occur when processing a Processing Block
 #[derive(Debug, Clone, Error, PartialEq, Eq)]
-#[error("Kind: {kind}; Reason: {context}")]
-pub struct ProcessFrameError {
-    /// The RS2 exception that occurred
-    pub kind: Rs2Exception,
-    /// The context in which the error occurred
-    pub context: String,
+pub enum ProcessFrameError {
+    /// librealsense2 could not process the frame, or could not hand out the result.
+    #[error("Could not process frame. Type: {0}; Reason: {1}")]
+    CouldNotProcessFrame(Rs2Exception, String),
+
+    /// Could not get another handle to the frame to hand to the processing block
+    #[error("Could not add a reference to the frame. Type: {0}; Reason: {1}")]
+    CouldNotAddFrameReference(Rs2Exception, String),
+
+    /// The processing block produced a frame that cannot be converted into its output frame type
+    #[error("Processing block produced an invalid frame: {0}")]
+    InvalidOutputFrame(String),
+
+    /// The processing queue already holds this many results, and the block rejects new frames
+    /// when it is full
+    #[error(
+        "The processing queue is full ({0} results). Take results out before queueing more frames."
+    )]
+    QueueFull(i32),
+
+    /// The frames queued to be aligned do not include the stream they are to be aligned to
+    #[error("Cannot align to the {0:?} stream: the frames do not include it.")]
+    MissingAlignTarget(Rs2StreamKind),
+}
+
+impl ProcessFrameError {
+    /// Describe a processed frame that could not be converted into the block's output frame type.
+    pub(crate) fn invalid_output<E: Display>(error: E) -> Self {
+        ProcessFrameError::InvalidOutputFrame(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queue_size_must_be_positive() {
+        assert_eq!(
+            ProcessingBlockConstructionError::check_queue_size(1),
+            Ok(())
+        );
+        assert_eq!(
+            ProcessingBlockConstructionError::check_queue_size(0),
+            Err(ProcessingBlockConstructionError::InvalidQueueSize(0))
+        );
+        assert_eq!(
+            ProcessingBlockConstructionError::check_queue_size(-5),
+            Err(ProcessingBlockConstructionError::InvalidQueueSize(-5))
+        );
+    }
 }
@@ -5,41 +5,95 @@
 
 use crate::{
     check_rs2_error,
-    frame::{DepthFrame, FrameEx},
-    kind::{OptionSetError, Rs2Option},
-    processing_blocks::errors::{ProcessFrameError, ProcessingBlockConstructionError},
+    frame::{DepthFrame, DisparityFrame, FrameEx},
+    processing_blocks::{
+        errors::{ProcessFrameError, ProcessingBlockConstructionError},
+        options::{sealed::OptionsPtr, ProcessingBlockOptions},
+        overflow::{sealed::ProcessingQueueRef, QueueOverflow},
+        queue::{output_frame, ProcessingQueue},
+    },
 };
 use anyhow::Result;
 use realsense_sys as sys;
-use std::{
-    convert::{TryFrom, TryInto},
-    ptr::NonNull,
-    task::Poll,
-    time::Duration,
-};
+use std::{convert::TryFrom, marker::PhantomData, ptr::NonNull, task::Poll, time::Duration};
+
+/// Crate-private plumbing that restricts [`HoleFillingInput`] to the frame types of this crate.
+pub(crate) mod sealed {
+    use crate::frame::{DepthFrame, DisparityFrame};
+
+    /// Marks the frame types that hole filling is defined for.
+    pub trait Sealed {}
+
+    impl Sealed for DepthFrame {}
+    impl Sealed for DisparityFrame {}
+}
+
+/// Frame types that a [`HoleFilling`] block can process.
+///
+/// Hole filling works the same way on depth and disparity, so the block can be placed on either
+/// side of a depth to disparity transform.
+pub trait HoleFillingInput:
+    sealed::Sealed + FrameEx + TryFrom<NonNull<sys::rs2_frame>, Error = anyhow::Error>
+{
+}
+
+impl HoleFillingInput for DepthFrame {}
+impl HoleFillingInput for DisparityFrame {}
 
 /// Processing Block and Frame Queue for hole-filling a stream to a certain [StreamKind]
-#[derive(Debug, Clone)]
-pub struct HoleFilling {
+///
+/// The block processes [`DepthFrame`]s by default. To fill holes in the disparity domain instead,
+/// e.g. before transforming back to depth, create a `HoleFilling<DisparityFrame>`:
+///
+/// ```no_run
+/// use realsense_rust::{frame::DisparityFrame, processing_blocks::hole_filling::HoleFilling};
+///
+/// let mut filter = HoleFilling::<DisparityFrame>::new(1).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct HoleFilling<F = DepthFrame> {
     /// The processing block for the "HoleFilling" method
     processing_block: NonNull<sys::rs2_processing_block>,
     /// The frame queue upon which the processing block will deposit filled frames. We check this
     /// for completed block operations.
-    processing_queue: NonNull<sys::rs2_frame_queue>,
+    processing_queue: ProcessingQueue,
+    /// Holds the type of the frames processed by this block.
+    _phantom: PhantomData<F>,
 }
 
-impl Drop for HoleFilling {
+impl<F> Drop for HoleFilling<F> {
     fn drop(&mut self) {
         unsafe {
-            sys::rs2_delete_frame_queue(self.processing_queue.as_ptr());
             sys::rs2_delete_processing_block(self.processing_block.as_ptr());
         }
     }
 }
 
-impl HoleFilling {
+impl<F> OptionsPtr for HoleFilling<F> {
+    fn options_ptr(&self) -> NonNull<sys::rs2_options> {
+        self.processing_block.cast::<sys::rs2_options>()
+    }
+}
+
+impl<F> ProcessingBlockOptions for HoleFilling<F> {}
+
+impl<F> ProcessingQueueRef for HoleFilling<F> {
+    fn processing_queue(&self) -> &ProcessingQueue {
+        &self.processing_queue
+    }
+
+    fn processing_queue_mut(&mut self) -> &mut ProcessingQueue {
+        &mut self.processing_queue
+    }
+}
+
+impl<F> QueueOverflow for HoleFilling<F> {}
+
+impl<F: HoleFillingInput> HoleFilling<F> {
     /// Create a new HoleFilling object
     pub fn new(processing_queue_size: i32) -> Result<Self, ProcessingBlockConstructionError> {
+        ProcessingBlockConstructionError::check_queue_size(processing_queue_size)?;
+
         let (processing_block, processing_queue) = unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
 
@@ -49,28 +103,29 @@ impl HoleFilling {
                 ProcessingBlockConstructionError::CouldNotCreateProcessingBlock
             )?;
 
-            let queue_ptr = sys::rs2_create_frame_queue(processing_queue_size, &mut err);
-            check_rs2_error!(
-                err,
-                ProcessingBlockConstructionError::CouldNotCreateProcessingQueue
-            )?;
-
-            sys::rs2_start_processing_queue(ptr, queue_ptr, &mut err);
-            check_rs2_error!(
-                err,
-                ProcessingBlockConstructionError::CouldNotStartProcessingQueue
-            )?;
-            (NonNull::new(ptr).unwrap(), NonNull::new(queue_ptr).unwrap())
+            let processing_queue =
+                ProcessingQueue::start(ptr, processing_queue_size).map_err(|e| {
+                    sys::rs2_delete_processing_block(ptr);
+                    e
+                })?;
+            (NonNull::new(ptr).unwrap(), processing_queue)
         };
 
         Ok(Self {
             processing_block,
             processing_queue,
+            _phantom: PhantomData,
         })
     }
 
-    /// Own and process the depth frame and return the filled frames.
-    pub fn queue(&mut self, frame: DepthFrame) -> Result<(), ProcessFrameError> {
+    /// Own the depth or disparity frame and queue it to have its holes filled.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::QueueFull`] if the processing queue is full and the overflow
+    /// policy is [`Reject`](crate::processing_blocks::overflow::QueueOverflowPolicy::Reject).
+    pub fn queue(&mut self, frame: F) -> Result<(), ProcessFrameError> {
+        self.processing_queue.check_overflow()?;
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
             sys::rs2_process_frame(
@@ -78,26 +133,26 @@ impl HoleFilling {
                 frame.get_owned_raw().as_ptr(),
                 &mut err,
             );
-            check_rs2_error!(err, |kind, context| { ProcessFrameError { kind, context } })?;
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
             Ok(())
         }
     }
 
     /// Wait to receive the results of the processing block
-    pub fn wait(&mut self, timeout: Duration) -> Result<DepthFrame, ProcessFrameError> {
+    pub fn wait(&mut self, timeout: Duration) -> Result<F, ProcessFrameError> {
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
             let timeout_millis = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
 
             let filled_frame =
                 sys::rs2_wait_for_frame(self.processing_queue.as_ptr(), timeout_millis, &mut err);
-            check_rs2_error!(err, |kind, context| { ProcessFrameError { kind, context } })?;
-            Ok(DepthFrame::try_from(NonNull::new(filled_frame).unwrap()).unwrap())
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
+            output_frame(NonNull::new(filled_frame).unwrap())
         }
     }
 
     /// Poll to receive the results of the processing block
-    pub fn poll(&mut self) -> Result<Poll<Result<DepthFrame>>> {
+    pub fn poll(&mut self) -> Result<Poll<Result<F>>> {
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
             let mut frame = std::ptr::null_mut::<sys::rs2_frame>();
@@ -105,106 +160,16 @@ impl HoleFilling {
                 sys::rs2_poll_for_frame(self.processing_queue.as_ptr(), &mut frame, &mut err);
 
             // Check for errors
-            check_rs2_error!(err, |kind, context| { ProcessFrameError { kind, context } })?;
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
 
             // Check for queue readiness
             if is_ready == 0 {
                 Ok(Poll::Pending)
             } else {
-                Ok(Poll::Ready(DepthFrame::try_from(
-                    NonNull::new(frame).unwrap(),
-                )))
+                Ok(Poll::Ready(
+                    output_frame(NonNull::new(frame).unwrap()).map_err(Into::into),
+                ))
             }
         }
     }
-
-    /// Predicate for determining if this processing block supports a given option
-    ///
-    /// Returns true iff the option is supported by this sensor.
-    pub fn supports_option(&self, option: Rs2Option) -> bool {
-        let mut err = std::ptr::null_mut::<sys::rs2_error>();
-        let val = unsafe {
-            sys::rs2_supports_option(
-                self.processing_block.as_ptr().cast::<sys::rs2_options>(),
-                #[allow(clippy::useless_conversion)]
-                (option as i32).try_into().unwrap(),
-                &mut err,
-            )
-        };
-
-        if err.is_null() {
-            val != 0
-        } else {
-            unsafe {
-                sys::rs2_free_error(err);
-            }
-            false
-        }
-    }
-
-    /// Predicate for determining if the provided option is immutable or not.
-    ///
-    /// Returns true if the option is supported and can be mutated, otherwise false.
-    pub fn is_option_read_only(&self, option: Rs2Option) -> bool {
-        if !self.supports_option(option) {
-            return false;
-        }
-
-        let mut err = std::ptr::null_mut::<sys::rs2_error>();
-        let val = unsafe {
-            sys::rs2_is_option_read_only(
-                self.processing_block.as_ptr().cast::<sys::rs2_options>(),
-                #[allow(clippy::useless_conversion)]
-                (option as i32).try_into().unwrap(),
-                &mut err,
-            )
-        };
-
-        if err.is_null() {
-            val != 0
-        } else {
-            unsafe {
-                sys::rs2_free_error(err);
-            }
-            false
-        }
-    }
-
-    /// Sets the `value` associated with the provided `option` for the sensor.
-    ///
-    /// Returns null tuple if the option can be successfully set on the sensor, otherwise an error.
-    ///
-    /// # Errors
-    ///
-    /// Returns [`OptionSetError::OptionNotSupported`] if the option is not supported on this
-    /// sensor.
-    ///
-    /// Returns [`OptionSetError::OptionIsReadOnly`] if the option is supported but cannot be set
-    /// on this sensor.
-    ///
-    /// Returns [`OptionSetError::CouldNotSetOption`] if the option is supported and not read-only,
-    /// but could not be set for another reason (invalid value, internal exception, etc.).
-    pub fn set_option(&mut self, option: Rs2Option, value: f32) -> Result<(), OptionSetError> {
-        if !self.supports_option(option) {
-            return Err(OptionSetError::OptionNotSupported);
-        }
-
-        if self.is_option_read_only(option) {
-            return Err(OptionSetError::OptionIsReadOnly);
-        }
-
-        let mut err = std::ptr::null_mut::<sys::rs2_error>();
-        unsafe {
-            sys::rs2_set_option(
-                self.processing_block.as_ptr().cast::<sys::rs2_options>(),
-                #[allow(clippy::useless_conversion)]
-                (option as i32).try_into().unwrap(),
-                value,
-                &mut err,
-            );
-            check_rs2_error!(err, OptionSetError::CouldNotSetOption)?;
-
-            Ok(())
-        }
-    }
 }
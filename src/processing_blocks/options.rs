@@ -0,0 +1,255 @@
+//! Trait for reading and setting options on processing blocks.
+//!
+//! Every processing block in librealsense2 can be extended to the `rs2_options` interface, so the
+//! same set of option accessors applies to all of them. Rather than re-implementing these accessors
+//! on each block, they are provided once here and implemented for every block in this module.
+
+use crate::{
+    check_rs2_error,
+    kind::{OptionSetError, Rs2Option, Rs2OptionRange},
+};
+use num_traits::FromPrimitive;
+use realsense_sys as sys;
+use std::{collections::HashMap, convert::TryInto, mem::MaybeUninit};
+
+/// Crate-private plumbing that gives [`ProcessingBlockOptions`] access to the underlying pointer.
+///
+/// This keeps the raw pointer out of the public API and prevents the options trait from being
+/// implemented outside of this crate.
+pub(crate) mod sealed {
+    use realsense_sys as sys;
+    use std::ptr::NonNull;
+
+    /// Provides the low-level `rs2_options` pointer for a processing block.
+    pub trait OptionsPtr {
+        /// Get the underlying processing block pointer as an `rs2_options` pointer.
+        ///
+        /// This pointer is owned by the processing block and must not be deleted.
+        fn options_ptr(&self) -> NonNull<sys::rs2_options>;
+    }
+}
+
+/// Describes the option accessors common to every processing block.
+///
+/// Processing blocks hold options such as [`Rs2Option::FilterMagnitude`] or
+/// [`Rs2Option::HolesFill`] that tune how frames are processed. Which options are supported
+/// depends on the block, so query [`supports_option`](ProcessingBlockOptions::supports_option)
+/// before relying on any specific option.
+pub trait ProcessingBlockOptions: sealed::OptionsPtr {
+    /// Predicate for determining if this processing block supports a given option
+    ///
+    /// Returns true iff the option is supported by this processing block.
+    fn supports_option(&self, option: Rs2Option) -> bool {
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        let val = unsafe {
+            sys::rs2_supports_option(
+                self.options_ptr().as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (option as i32).try_into().unwrap(),
+                &mut err,
+            )
+        };
+
+        if err.is_null() {
+            val != 0
+        } else {
+            unsafe {
+                sys::rs2_free_error(err);
+            }
+            false
+        }
+    }
+
+    /// Predicate for determining if the provided option is immutable or not.
+    ///
+    /// Returns true if the option is supported and cannot be mutated, otherwise false.
+    fn is_option_read_only(&self, option: Rs2Option) -> bool {
+        if !self.supports_option(option) {
+            return false;
+        }
+
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        let val = unsafe {
+            sys::rs2_is_option_read_only(
+                self.options_ptr().as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (option as i32).try_into().unwrap(),
+                &mut err,
+            )
+        };
+
+        if err.is_null() {
+            val != 0
+        } else {
+            unsafe {
+                sys::rs2_free_error(err);
+            }
+            false
+        }
+    }
+
+    /// Get the value associated with the provided option for the processing block.
+    ///
+    /// Returns an `f32` value corresponding to that option, or `None` if the option is not
+    /// supported.
+    fn get_option(&self, option: Rs2Option) -> Option<f32> {
+        if !self.supports_option(option) {
+            return None;
+        }
+
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        let val = unsafe {
+            sys::rs2_get_option(
+                self.options_ptr().as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (option as i32).try_into().unwrap(),
+                &mut err,
+            )
+        };
+
+        if err.is_null() {
+            Some(val)
+        } else {
+            unsafe {
+                sys::rs2_free_error(err);
+            }
+            None
+        }
+    }
+
+    /// Gets the range for a given option.
+    ///
+    /// Returns some option range if the processing block supports the option, else `None`.
+    fn get_option_range(&self, option: Rs2Option) -> Option<Rs2OptionRange> {
+        if !self.supports_option(option) {
+            return None;
+        }
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+
+            let mut min = MaybeUninit::uninit();
+            let mut max = MaybeUninit::uninit();
+            let mut step = MaybeUninit::uninit();
+            let mut default = MaybeUninit::uninit();
+
+            sys::rs2_get_option_range(
+                self.options_ptr().as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (option as i32).try_into().unwrap(),
+                min.as_mut_ptr(),
+                max.as_mut_ptr(),
+                step.as_mut_ptr(),
+                default.as_mut_ptr(),
+                &mut err,
+            );
+
+            if err.as_ref().is_none() {
+                Some(Rs2OptionRange {
+                    min: min.assume_init(),
+                    max: max.assume_init(),
+                    step: step.assume_init(),
+                    default: default.assume_init(),
+                })
+            } else {
+                sys::rs2_free_error(err);
+                None
+            }
+        }
+    }
+
+    /// Sets the `value` associated with the provided `option` for the processing block.
+    ///
+    /// Returns null tuple if the option can be successfully set on the processing block,
+    /// otherwise an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptionSetError::OptionNotSupported`] if the option is not supported on this
+    /// processing block.
+    ///
+    /// Returns [`OptionSetError::OptionIsReadOnly`] if the option is supported but cannot be set
+    /// on this processing block.
+    ///
+    /// Returns [`OptionSetError::OptionValueOutOfRange`] if `value` is outside of the
+    /// [range](ProcessingBlockOptions::get_option_range) of the option. This is checked before
+    /// calling into librealsense2.
+    ///
+    /// Returns [`OptionSetError::CouldNotSetOption`] if the option is supported and not read-only,
+    /// but could not be set for another reason (internal exception, etc.).
+    fn set_option(&mut self, option: Rs2Option, value: f32) -> Result<(), OptionSetError> {
+        if !self.supports_option(option) {
+            return Err(OptionSetError::OptionNotSupported);
+        }
+
+        if self.is_option_read_only(option) {
+            return Err(OptionSetError::OptionIsReadOnly);
+        }
+
+        if let Some(range) = self.get_option_range(option) {
+            range.validate(value)?;
+        }
+
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        unsafe {
+            sys::rs2_set_option(
+                self.options_ptr().as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (option as i32).try_into().unwrap(),
+                value,
+                &mut err,
+            );
+            check_rs2_error!(err, OptionSetError::CouldNotSetOption)?;
+
+            Ok(())
+        }
+    }
+
+    /// Get every option supported by the processing block alongside its current value.
+    ///
+    /// This is useful for logging the exact configuration of a processing block, e.g. alongside
+    /// recorded data so that a capture can be reproduced later. Options format with their
+    /// human-readable names, and can be parsed back with [`Rs2Option::from_name`].
+    ///
+    /// Returns an empty map if an error occurs while trying to read the list of supported options.
+    /// Options that cannot be read (or that have no corresponding [`Rs2Option`] variant) are
+    /// omitted from the map.
+    fn dump_options(&self) -> HashMap<Rs2Option, f32> {
+        let mut options = HashMap::new();
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let options_list = sys::rs2_get_options_list(self.options_ptr().as_ptr(), &mut err);
+
+            if err.as_ref().is_some() {
+                sys::rs2_free_error(err);
+                return options;
+            }
+
+            let len = sys::rs2_get_options_list_size(options_list, &mut err);
+
+            if err.as_ref().is_some() {
+                sys::rs2_free_error(err);
+                sys::rs2_delete_options_list(options_list);
+                return options;
+            }
+
+            for i in 0..len {
+                let option = sys::rs2_get_option_from_list(options_list, i, &mut err);
+
+                if err.as_ref().is_some() {
+                    sys::rs2_free_error(err);
+                    err = std::ptr::null_mut();
+                    continue;
+                }
+
+                if let Some(option) = Rs2Option::from_i32(option as i32) {
+                    if let Some(value) = self.get_option(option) {
+                        options.insert(option, value);
+                    }
+                }
+            }
+            sys::rs2_delete_options_list(options_list);
+        }
+        options
+    }
+}
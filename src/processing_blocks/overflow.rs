@@ -0,0 +1,76 @@
+//! Policies for what happens when the output queue of a processing block is full
+//!
+//! Every processing block deposits its results on a frame queue of a fixed size, chosen when the
+//! block is constructed. librealsense2 never blocks when that queue is full: the oldest result is
+//! evicted to make room for the newest one. If frames are queued faster than they are taken out
+//! with `wait` or `poll`, results are therefore lost without any indication. The
+//! [`QueueOverflowPolicy`] of a block controls whether that happens silently, or whether `queue`
+//! rejects new frames until results have been taken out.
+
+/// Crate-private plumbing that gives [`QueueOverflow`] access to the queue of a processing block.
+///
+/// This keeps the queue out of the public API and prevents the overflow trait from being
+/// implemented outside of this crate.
+pub(crate) mod sealed {
+    use crate::processing_blocks::queue::ProcessingQueue;
+
+    /// Provides the output queue of a processing block.
+    pub trait ProcessingQueueRef {
+        /// Get the queue that the processing block deposits its results on.
+        fn processing_queue(&self) -> &ProcessingQueue;
+
+        /// Get the queue that the processing block deposits its results on, mutably.
+        fn processing_queue_mut(&mut self) -> &mut ProcessingQueue;
+    }
+}
+
+/// What a processing block does with a new frame when its output queue is full.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QueueOverflowPolicy {
+    /// Process the new frame, evicting the oldest result from the queue.
+    ///
+    /// This is the behaviour of librealsense2, and suits live streams where only the most recent
+    /// result matters.
+    #[default]
+    KeepLatest,
+    /// Reject the new frame without processing it, leaving the queued results untouched.
+    ///
+    /// `queue` drops the frame and returns
+    /// [`ProcessFrameError::QueueFull`](crate::processing_blocks::errors::ProcessFrameError::QueueFull)
+    /// instead, so that no result is lost without the caller knowing. Since results are only taken
+    /// out through the same block, this never waits; the caller has to drain the queue with `wait`
+    /// or `poll` before queueing again.
+    Reject,
+}
+
+/// Describes the overflow policy accessors common to every processing block.
+///
+/// The results of a block are held in a processing queue of the size given on construction. By
+/// default, when the queue is full the oldest result is silently evicted to make room; set the
+/// policy to [`QueueOverflowPolicy::Reject`] to reject new frames instead.
+pub trait QueueOverflow: sealed::ProcessingQueueRef {
+    /// Get the policy applied when a frame is queued while the processing queue is full.
+    fn overflow_policy(&self) -> QueueOverflowPolicy {
+        self.processing_queue().overflow_policy
+    }
+
+    /// Set the policy applied when a frame is queued while the processing queue is full.
+    ///
+    /// Defaults to [`QueueOverflowPolicy::KeepLatest`].
+    fn set_overflow_policy(&mut self, policy: QueueOverflowPolicy) {
+        self.processing_queue_mut().overflow_policy = policy;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_policy_keeps_latest() {
+        assert_eq!(
+            QueueOverflowPolicy::default(),
+            QueueOverflowPolicy::KeepLatest
+        );
+    }
+}
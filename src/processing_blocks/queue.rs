@@ -0,0 +1,164 @@
+//! Plumbing shared by the processing blocks for the frame queues they deposit their results on.
+
+use crate::{
+    check_rs2_error,
+    processing_blocks::{
+        errors::{ProcessFrameError, ProcessingBlockConstructionError},
+        overflow::QueueOverflowPolicy,
+    },
+};
+use realsense_sys as sys;
+use std::{convert::TryFrom, fmt::Display, ptr::NonNull, time::Duration};
+
+/// The frame queue that a processing block deposits its results on.
+///
+/// The queue is deleted when this is dropped, so it must be dropped after the processing block
+/// that deposits on it has been deleted.
+#[derive(Debug)]
+pub struct ProcessingQueue {
+    /// The frame queue upon which the processing block deposits its results.
+    queue: NonNull<sys::rs2_frame_queue>,
+    /// The number of results the queue holds before it overflows.
+    capacity: i32,
+    /// What happens to a new frame when the queue is full.
+    pub(crate) overflow_policy: QueueOverflowPolicy,
+}
+
+impl Drop for ProcessingQueue {
+    fn drop(&mut self) {
+        unsafe {
+            sys::rs2_delete_frame_queue(self.queue.as_ptr());
+        }
+    }
+}
+
+impl ProcessingQueue {
+    /// Create a queue holding `capacity` results, and start `block` depositing its results on it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessingBlockConstructionError::CouldNotCreateProcessingQueue`] if the queue
+    /// cannot be created, and
+    /// [`ProcessingBlockConstructionError::CouldNotStartProcessingQueue`] if the block cannot be
+    /// started on it.
+    ///
+    /// # Safety
+    ///
+    /// `block` must point to a valid processing block.
+    pub(crate) unsafe fn start(
+        block: *mut sys::rs2_processing_block,
+        capacity: i32,
+    ) -> Result<Self, ProcessingBlockConstructionError> {
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        let queue_ptr = sys::rs2_create_frame_queue(capacity, &mut err);
+        check_rs2_error!(
+            err,
+            ProcessingBlockConstructionError::CouldNotCreateProcessingQueue
+        )?;
+
+        // From here on, dropping the queue deletes it.
+        let queue = Self {
+            queue: NonNull::new(queue_ptr).unwrap(),
+            capacity,
+            overflow_policy: QueueOverflowPolicy::default(),
+        };
+
+        sys::rs2_start_processing_queue(block, queue_ptr, &mut err);
+        check_rs2_error!(
+            err,
+            ProcessingBlockConstructionError::CouldNotStartProcessingQueue
+        )?;
+        Ok(queue)
+    }
+
+    /// Get the underlying frame queue pointer.
+    pub(crate) fn as_ptr(&self) -> *mut sys::rs2_frame_queue {
+        self.queue.as_ptr()
+    }
+
+    /// Wait for the next result on the queue, and hand ownership of it to the caller.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::CouldNotProcessFrame`] if no result arrives within `timeout`,
+    /// or if it cannot be taken off the queue.
+    pub(crate) fn wait(
+        &self,
+        timeout: Duration,
+    ) -> Result<NonNull<sys::rs2_frame>, ProcessFrameError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let timeout_millis = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+
+            let frame = sys::rs2_wait_for_frame(self.queue.as_ptr(), timeout_millis, &mut err);
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
+            Ok(NonNull::new(frame).unwrap())
+        }
+    }
+
+    /// Check whether a frame may be queued under the overflow policy of the queue.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::QueueFull`] if the policy is
+    /// [`Reject`](QueueOverflowPolicy::Reject) and the queue is full.
+    ///
+    /// Returns [`ProcessFrameError::CouldNotProcessFrame`] if the size of the queue cannot be read.
+    pub(crate) fn check_overflow(&self) -> Result<(), ProcessFrameError> {
+        if self.overflow_policy == QueueOverflowPolicy::KeepLatest {
+            return Ok(());
+        }
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let size = sys::rs2_frame_queue_size(self.queue.as_ptr(), &mut err);
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
+
+            if size >= self.capacity {
+                return Err(ProcessFrameError::QueueFull(self.capacity));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Convert a frame taken off a processing queue into the output frame type of a block.
+///
+/// Frame types do not take ownership of the frame when the conversion fails, so the frame is
+/// released here in that case.
+///
+/// # Errors
+///
+/// Returns [`ProcessFrameError::InvalidOutputFrame`] if the frame cannot be converted into `F`.
+///
+/// # Safety
+///
+/// `frame` must point to a valid frame that the caller owns.
+pub(crate) unsafe fn output_frame<F>(frame: NonNull<sys::rs2_frame>) -> Result<F, ProcessFrameError>
+where
+    F: TryFrom<NonNull<sys::rs2_frame>>,
+    F::Error: Display,
+{
+    F::try_from(frame).map_err(|e| {
+        sys::rs2_release_frame(frame.as_ptr());
+        ProcessFrameError::invalid_output(e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::ManuallyDrop;
+
+    #[test]
+    fn keep_latest_never_checks_the_queue() {
+        // The queue pointer is never dereferenced when frames may always be queued, and the queue
+        // is never dropped, so it is never deleted.
+        let queue = ManuallyDrop::new(ProcessingQueue {
+            queue: NonNull::dangling(),
+            capacity: 0,
+            overflow_policy: QueueOverflowPolicy::KeepLatest,
+        });
+        assert!(queue.check_overflow().is_ok());
+    }
+}
@@ -0,0 +1,140 @@
+//! Processing blocks recommended by a sensor
+//!
+//! Every sensor in librealsense2 advertises the post-processing blocks that are recommended for the
+//! streams it produces, in the order they should be applied. These are obtained through
+//! [`Sensor::recommended_filters`](crate::sensor::Sensor::recommended_filters).
+
+use crate::{
+    kind::Rs2Extension,
+    processing_blocks::{
+        decimation::Decimation,
+        errors::ProcessingBlockConstructionError,
+        hole_filling::HoleFilling,
+        options::{sealed::OptionsPtr, ProcessingBlockOptions},
+        spatial::Spatial,
+        temporal::Temporal,
+        threshold::Threshold,
+    },
+};
+use realsense_sys as sys;
+use std::{convert::TryInto, ptr::NonNull};
+
+/// The processing queue size used for every filter constructed from a sensor's recommendations.
+pub const RECOMMENDED_FILTER_QUEUE_SIZE: i32 = 1;
+
+/// A filter recommended by a sensor, constructed and ready to process frames.
+///
+/// Each filter is created with the option values of the block recommended by the sensor, so it can
+/// be used as-is or tuned further through [`ProcessingBlockOptions`].
+#[derive(Debug)]
+pub enum RecommendedFilter {
+    /// A recommended [`Decimation`] filter.
+    Decimation(Decimation),
+    /// A recommended [`Threshold`] filter.
+    Threshold(Threshold),
+    /// A recommended [`Spatial`] filter.
+    Spatial(Spatial),
+    /// A recommended [`Temporal`] filter.
+    Temporal(Temporal),
+    /// A recommended [`HoleFilling`] filter.
+    HoleFilling(HoleFilling),
+}
+
+/// A processing block obtained from a recommended processing block list.
+///
+/// This only exists so that the options of the recommended block can be read through
+/// [`ProcessingBlockOptions`]. The block is deleted once its options have been copied.
+struct RecommendedBlock(NonNull<sys::rs2_processing_block>);
+
+impl Drop for RecommendedBlock {
+    fn drop(&mut self) {
+        unsafe {
+            sys::rs2_delete_processing_block(self.0.as_ptr());
+        }
+    }
+}
+
+impl OptionsPtr for RecommendedBlock {
+    fn options_ptr(&self) -> NonNull<sys::rs2_options> {
+        self.0.cast::<sys::rs2_options>()
+    }
+}
+
+impl ProcessingBlockOptions for RecommendedBlock {}
+
+impl RecommendedBlock {
+    /// Predicate for whether the recommended block is of the given filter extension.
+    fn is_extendable_to(&self, extension: Rs2Extension) -> bool {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let is_extendable = sys::rs2_is_processing_block_extendable_to(
+                self.0.as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (extension as i32).try_into().unwrap(),
+                &mut err,
+            );
+
+            if err.as_ref().is_none() {
+                is_extendable != 0
+            } else {
+                sys::rs2_free_error(err);
+                false
+            }
+        }
+    }
+
+    /// Copy every writable option of the recommended block onto `filter`.
+    ///
+    /// Options that cannot be set on `filter` are skipped.
+    fn copy_options_to<B: ProcessingBlockOptions>(&self, filter: &mut B) {
+        for (option, value) in self.dump_options() {
+            if filter.supports_option(option) && !filter.is_option_read_only(option) {
+                let _ = filter.set_option(option, value);
+            }
+        }
+    }
+}
+
+impl RecommendedFilter {
+    /// Construct the filter corresponding to a block from a recommended processing block list.
+    ///
+    /// Takes ownership of `block_ptr` and deletes it before returning. Returns `None` if the block
+    /// is not one of the filters that this crate provides.
+    pub(crate) fn from_recommended(
+        block_ptr: NonNull<sys::rs2_processing_block>,
+    ) -> Option<Result<Self, ProcessingBlockConstructionError>> {
+        let block = RecommendedBlock(block_ptr);
+        let queue_size = RECOMMENDED_FILTER_QUEUE_SIZE;
+
+        let filter = if block.is_extendable_to(Rs2Extension::DecimationFilter) {
+            Decimation::new(queue_size).map(|mut filter| {
+                block.copy_options_to(&mut filter);
+                Self::Decimation(filter)
+            })
+        } else if block.is_extendable_to(Rs2Extension::ThresholdFilter) {
+            Threshold::new(queue_size).map(|mut filter| {
+                block.copy_options_to(&mut filter);
+                Self::Threshold(filter)
+            })
+        } else if block.is_extendable_to(Rs2Extension::SpatialFilter) {
+            Spatial::new(queue_size).map(|mut filter| {
+                block.copy_options_to(&mut filter);
+                Self::Spatial(filter)
+            })
+        } else if block.is_extendable_to(Rs2Extension::TemporalFilter) {
+            Temporal::new(queue_size).map(|mut filter| {
+                block.copy_options_to(&mut filter);
+                Self::Temporal(filter)
+            })
+        } else if block.is_extendable_to(Rs2Extension::HoleFillingFilter) {
+            HoleFilling::new(queue_size).map(|mut filter| {
+                block.copy_options_to(&mut filter);
+                Self::HoleFilling(filter)
+            })
+        } else {
+            return None;
+        };
+
+        Some(filter)
+    }
+}
@@ -0,0 +1,177 @@
+//! Processing block that passes through the frames of a single HDR sequence
+//!
+//! In HDR mode, D400 devices cycle the depth sensor through a sequence of exposures and tag each
+//! frame with its position in the sequence (see [`FrameEx::sequence_id`]). This block lets the
+//! frames with one sequence id through and drops the others, separating e.g. the short and long
+//! exposure frames into streams of their own.
+//!
+//! Based on an example here:
+//! https://github.com/IntelRealSense/librealsense/blob/4673a37d981164af8eeb8e296e430fc1427e008d/doc/post-processing-filters.md?plain=1#L111
+
+use crate::{
+    check_rs2_error,
+    frame::{DepthFrame, FrameEx},
+    kind::{OptionSetError, Rs2Option},
+    processing_blocks::{
+        errors::{ProcessFrameError, ProcessingBlockConstructionError},
+        options::{sealed::OptionsPtr, ProcessingBlockOptions},
+        overflow::{sealed::ProcessingQueueRef, QueueOverflow},
+        queue::{output_frame, ProcessingQueue},
+    },
+};
+use anyhow::Result;
+use realsense_sys as sys;
+use std::{convert::TryFrom, ptr::NonNull, task::Poll, time::Duration};
+
+/// Filters frames by their HDR sequence id.
+#[derive(Debug)]
+pub struct SequenceIdFilter {
+    /// The processing block for the "Sequence ID Filter" method
+    processing_block: NonNull<sys::rs2_processing_block>,
+    /// The frame queue upon which the processing block will deposit filtered frames. We check this
+    /// for completed block operations.
+    processing_queue: ProcessingQueue,
+}
+
+impl Drop for SequenceIdFilter {
+    fn drop(&mut self) {
+        unsafe {
+            sys::rs2_delete_processing_block(self.processing_block.as_ptr());
+        }
+    }
+}
+
+impl OptionsPtr for SequenceIdFilter {
+    fn options_ptr(&self) -> NonNull<sys::rs2_options> {
+        self.processing_block.cast::<sys::rs2_options>()
+    }
+}
+
+impl ProcessingBlockOptions for SequenceIdFilter {}
+
+impl ProcessingQueueRef for SequenceIdFilter {
+    fn processing_queue(&self) -> &ProcessingQueue {
+        &self.processing_queue
+    }
+
+    fn processing_queue_mut(&mut self) -> &mut ProcessingQueue {
+        &mut self.processing_queue
+    }
+}
+
+impl QueueOverflow for SequenceIdFilter {}
+
+impl SequenceIdFilter {
+    /// Create a new SequenceIdFilter object
+    pub fn new(processing_queue_size: i32) -> Result<Self, ProcessingBlockConstructionError> {
+        ProcessingBlockConstructionError::check_queue_size(processing_queue_size)?;
+
+        let (processing_block, processing_queue) = unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+
+            let ptr = sys::rs2_create_sequence_id_filter(&mut err);
+            check_rs2_error!(
+                err,
+                ProcessingBlockConstructionError::CouldNotCreateProcessingBlock
+            )?;
+
+            let processing_queue =
+                ProcessingQueue::start(ptr, processing_queue_size).map_err(|e| {
+                    sys::rs2_delete_processing_block(ptr);
+                    e
+                })?;
+            (NonNull::new(ptr).unwrap(), processing_queue)
+        };
+
+        Ok(Self {
+            processing_block,
+            processing_queue,
+        })
+    }
+
+    /// Own and process the depth frame, and pass it on if it has the selected sequence id.
+    ///
+    /// Frames of other sequence ids never appear in the processing queue, so
+    /// [`wait`](Self::wait) only returns frames of the selected sequence.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::QueueFull`] if the processing queue is full and the overflow
+    /// policy is [`Reject`](crate::processing_blocks::overflow::QueueOverflowPolicy::Reject).
+    pub fn queue(&mut self, frame: DepthFrame) -> Result<(), ProcessFrameError> {
+        self.processing_queue.check_overflow()?;
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_process_frame(
+                self.processing_block.as_ptr(),
+                frame.get_owned_raw().as_ptr(),
+                &mut err,
+            );
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
+            Ok(())
+        }
+    }
+
+    /// Wait to receive the results of the processing block
+    pub fn wait(&mut self, timeout: Duration) -> Result<DepthFrame, ProcessFrameError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let timeout_millis = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+            let filtered_frame =
+                sys::rs2_wait_for_frame(self.processing_queue.as_ptr(), timeout_millis, &mut err);
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
+            output_frame(NonNull::new(filtered_frame).unwrap())
+        }
+    }
+
+    /// Poll to receive the results of the processing block
+    pub fn poll(&mut self) -> Result<Poll<DepthFrame>, ProcessFrameError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let mut frame = std::ptr::null_mut::<sys::rs2_frame>();
+            let is_ready =
+                sys::rs2_poll_for_frame(self.processing_queue.as_ptr(), &mut frame, &mut err);
+
+            // Check for errors
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
+
+            // Check for queue readiness
+            if is_ready == 0 {
+                Ok(Poll::Pending)
+            } else {
+                let frame = output_frame(NonNull::new(frame).unwrap())?;
+                Ok(Poll::Ready(frame))
+            }
+        }
+    }
+
+    /// Get the sequence id of the frames that are let through, or `None` if it cannot be read.
+    pub fn sequence_id(&self) -> Option<u32> {
+        self.get_option(Rs2Option::SequenceId).map(|id| id as u32)
+    }
+
+    /// Set the sequence id of the frames that are let through.
+    ///
+    /// This maps to [`Rs2Option::SequenceId`] on the underlying processing block. HDR sequence
+    /// ids start from 1.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error that [`ProcessingBlockOptions::set_option`] can return, e.g.
+    /// [`OptionSetError::OptionValueOutOfRange`] if `id` is not a sequence id the block accepts.
+    pub fn set_sequence_id(&mut self, id: u32) -> Result<(), OptionSetError> {
+        self.set_option(Rs2Option::SequenceId, id as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sequence_id_can_be_selected() {
+        let mut filter = SequenceIdFilter::new(1).unwrap();
+        filter.set_sequence_id(2).unwrap();
+        assert_eq!(filter.sequence_id(), Some(2));
+    }
+}
@@ -6,40 +6,60 @@
 use crate::{
     check_rs2_error,
     frame::{DepthFrame, FrameEx},
-    kind::{OptionSetError, Rs2Option},
-    processing_blocks::errors::{ProcessFrameError, ProcessingBlockConstructionError},
+    processing_blocks::{
+        errors::{ProcessFrameError, ProcessingBlockConstructionError},
+        options::{sealed::OptionsPtr, ProcessingBlockOptions},
+        overflow::{sealed::ProcessingQueueRef, QueueOverflow},
+        queue::{output_frame, ProcessingQueue},
+    },
 };
 use anyhow::Result;
 use realsense_sys as sys;
-use std::{
-    convert::{TryFrom, TryInto},
-    ptr::NonNull,
-    task::Poll,
-    time::Duration,
-};
+use std::{convert::TryFrom, ptr::NonNull, task::Poll, time::Duration};
 
 /// Processing Block and Frame Queue for spatial filtering a stream to a certain [StreamKind]
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct Spatial {
     /// The processing block for the "Spatial" method
     processing_block: NonNull<sys::rs2_processing_block>,
-    /// The frame queue upon which the processing block will deposit filled frames. We check this
+    /// The frame queue upon which the processing block will deposit filtered frames. We check this
     /// for completed block operations.
-    processing_queue: NonNull<sys::rs2_frame_queue>,
+    processing_queue: ProcessingQueue,
 }
 
 impl Drop for Spatial {
     fn drop(&mut self) {
         unsafe {
-            sys::rs2_delete_frame_queue(self.processing_queue.as_ptr());
             sys::rs2_delete_processing_block(self.processing_block.as_ptr());
         }
     }
 }
 
+impl OptionsPtr for Spatial {
+    fn options_ptr(&self) -> NonNull<sys::rs2_options> {
+        self.processing_block.cast::<sys::rs2_options>()
+    }
+}
+
+impl ProcessingBlockOptions for Spatial {}
+
+impl ProcessingQueueRef for Spatial {
+    fn processing_queue(&self) -> &ProcessingQueue {
+        &self.processing_queue
+    }
+
+    fn processing_queue_mut(&mut self) -> &mut ProcessingQueue {
+        &mut self.processing_queue
+    }
+}
+
+impl QueueOverflow for Spatial {}
+
 impl Spatial {
     /// Create a new Spatial object
     pub fn new(processing_queue_size: i32) -> Result<Self, ProcessingBlockConstructionError> {
+        ProcessingBlockConstructionError::check_queue_size(processing_queue_size)?;
+
         let (processing_block, processing_queue) = unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
 
@@ -49,18 +69,12 @@ impl Spatial {
                 ProcessingBlockConstructionError::CouldNotCreateProcessingBlock
             )?;
 
-            let queue_ptr = sys::rs2_create_frame_queue(processing_queue_size, &mut err);
-            check_rs2_error!(
-                err,
-                ProcessingBlockConstructionError::CouldNotCreateProcessingQueue
-            )?;
-
-            sys::rs2_start_processing_queue(ptr, queue_ptr, &mut err);
-            check_rs2_error!(
-                err,
-                ProcessingBlockConstructionError::CouldNotStartProcessingQueue
-            )?;
-            (NonNull::new(ptr).unwrap(), NonNull::new(queue_ptr).unwrap())
+            let processing_queue =
+                ProcessingQueue::start(ptr, processing_queue_size).map_err(|e| {
+                    sys::rs2_delete_processing_block(ptr);
+                    e
+                })?;
+            (NonNull::new(ptr).unwrap(), processing_queue)
         };
 
         Ok(Self {
@@ -69,8 +83,14 @@ impl Spatial {
         })
     }
 
-    /// Own and process the depth frame and return the filled frames.
+    /// Own the depth frame and queue it to be smoothed by the spatial filter.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::QueueFull`] if the processing queue is full and the overflow
+    /// policy is [`Reject`](crate::processing_blocks::overflow::QueueOverflowPolicy::Reject).
     pub fn queue(&mut self, frame: DepthFrame) -> Result<(), ProcessFrameError> {
+        self.processing_queue.check_overflow()?;
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
             sys::rs2_process_frame(
@@ -78,7 +98,7 @@ impl Spatial {
                 frame.get_owned_raw().as_ptr(),
                 &mut err,
             );
-            check_rs2_error!(err, |kind, context| { ProcessFrameError { kind, context } })?;
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
             Ok(())
         }
     }
@@ -91,8 +111,8 @@ impl Spatial {
 
             let processed_frame =
                 sys::rs2_wait_for_frame(self.processing_queue.as_ptr(), timeout_millis, &mut err);
-            check_rs2_error!(err, |kind, context| { ProcessFrameError { kind, context } })?;
-            Ok(DepthFrame::try_from(NonNull::new(processed_frame).unwrap()).unwrap())
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
+            output_frame(NonNull::new(processed_frame).unwrap())
         }
     }
 
@@ -105,106 +125,16 @@ impl Spatial {
                 sys::rs2_poll_for_frame(self.processing_queue.as_ptr(), &mut frame, &mut err);
 
             // Check for errors
-            check_rs2_error!(err, |kind, context| { ProcessFrameError { kind, context } })?;
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
 
             // Check for queue readiness
             if is_ready == 0 {
                 Ok(Poll::Pending)
             } else {
-                Ok(Poll::Ready(DepthFrame::try_from(
-                    NonNull::new(frame).unwrap(),
-                )))
-            }
-        }
-    }
-
-    /// Predicate for determining if this processing block supports a given option
-    ///
-    /// Returns true iff the option is supported by this sensor.
-    pub fn supports_option(&self, option: Rs2Option) -> bool {
-        let mut err = std::ptr::null_mut::<sys::rs2_error>();
-        let val = unsafe {
-            sys::rs2_supports_option(
-                self.processing_block.as_ptr().cast::<sys::rs2_options>(),
-                #[allow(clippy::useless_conversion)]
-                (option as i32).try_into().unwrap(),
-                &mut err,
-            )
-        };
-
-        if err.is_null() {
-            val != 0
-        } else {
-            unsafe {
-                sys::rs2_free_error(err);
+                Ok(Poll::Ready(
+                    output_frame(NonNull::new(frame).unwrap()).map_err(Into::into),
+                ))
             }
-            false
-        }
-    }
-
-    /// Predicate for determining if the provided option is immutable or not.
-    ///
-    /// Returns true if the option is supported and can be mutated, otherwise false.
-    pub fn is_option_read_only(&self, option: Rs2Option) -> bool {
-        if !self.supports_option(option) {
-            return false;
-        }
-
-        let mut err = std::ptr::null_mut::<sys::rs2_error>();
-        let val = unsafe {
-            sys::rs2_is_option_read_only(
-                self.processing_block.as_ptr().cast::<sys::rs2_options>(),
-                #[allow(clippy::useless_conversion)]
-                (option as i32).try_into().unwrap(),
-                &mut err,
-            )
-        };
-
-        if err.is_null() {
-            val != 0
-        } else {
-            unsafe {
-                sys::rs2_free_error(err);
-            }
-            false
-        }
-    }
-
-    /// Sets the `value` associated with the provided `option` for the sensor.
-    ///
-    /// Returns null tuple if the option can be successfully set on the sensor, otherwise an error.
-    ///
-    /// # Errors
-    ///
-    /// Returns [`OptionSetError::OptionNotSupported`] if the option is not supported on this
-    /// sensor.
-    ///
-    /// Returns [`OptionSetError::OptionIsReadOnly`] if the option is supported but cannot be set
-    /// on this sensor.
-    ///
-    /// Returns [`OptionSetError::CouldNotSetOption`] if the option is supported and not read-only,
-    /// but could not be set for another reason (invalid value, internal exception, etc.).
-    pub fn set_option(&mut self, option: Rs2Option, value: f32) -> Result<(), OptionSetError> {
-        if !self.supports_option(option) {
-            return Err(OptionSetError::OptionNotSupported);
-        }
-
-        if self.is_option_read_only(option) {
-            return Err(OptionSetError::OptionIsReadOnly);
-        }
-
-        let mut err = std::ptr::null_mut::<sys::rs2_error>();
-        unsafe {
-            sys::rs2_set_option(
-                self.processing_block.as_ptr().cast::<sys::rs2_options>(),
-                #[allow(clippy::useless_conversion)]
-                (option as i32).try_into().unwrap(),
-                value,
-                &mut err,
-            );
-            check_rs2_error!(err, OptionSetError::CouldNotSetOption)?;
-
-            Ok(())
         }
     }
 }
@@ -0,0 +1,159 @@
+//! Processing block that filters the depth stream over time
+//!
+//! Based on an example here:
+//! https://github.com/IntelRealSense/librealsense/blob/4673a37d981164af8eeb8e296e430fc1427e008d/doc/post-processing-filters.md?plain=1#L111
+
+use crate::{
+    check_rs2_error,
+    frame::{DepthFrame, FrameEx},
+    kind::{OptionSetError, PersistenceControl, Rs2Option},
+    processing_blocks::{
+        errors::{ProcessFrameError, ProcessingBlockConstructionError},
+        options::{sealed::OptionsPtr, ProcessingBlockOptions},
+        overflow::{sealed::ProcessingQueueRef, QueueOverflow},
+        queue::{output_frame, ProcessingQueue},
+    },
+};
+use anyhow::Result;
+use num_traits::ToPrimitive;
+use realsense_sys as sys;
+use std::{convert::TryFrom, ptr::NonNull, task::Poll, time::Duration};
+
+/// Applies a temporal filter, smoothing depth values using the history of previous frames.
+///
+/// Since the filter depends on the frames that came before, it should be fed consecutive frames
+/// from the same stream.
+#[derive(Debug)]
+pub struct Temporal {
+    /// The processing block for the "Temporal" method
+    processing_block: NonNull<sys::rs2_processing_block>,
+    /// The frame queue upon which the processing block will deposit filtered frames. We check this
+    /// for completed block operations.
+    processing_queue: ProcessingQueue,
+}
+
+impl Drop for Temporal {
+    fn drop(&mut self) {
+        unsafe {
+            sys::rs2_delete_processing_block(self.processing_block.as_ptr());
+        }
+    }
+}
+
+impl OptionsPtr for Temporal {
+    fn options_ptr(&self) -> NonNull<sys::rs2_options> {
+        self.processing_block.cast::<sys::rs2_options>()
+    }
+}
+
+impl ProcessingBlockOptions for Temporal {}
+
+impl ProcessingQueueRef for Temporal {
+    fn processing_queue(&self) -> &ProcessingQueue {
+        &self.processing_queue
+    }
+
+    fn processing_queue_mut(&mut self) -> &mut ProcessingQueue {
+        &mut self.processing_queue
+    }
+}
+
+impl QueueOverflow for Temporal {}
+
+impl Temporal {
+    /// Create a new Temporal object
+    pub fn new(processing_queue_size: i32) -> Result<Self, ProcessingBlockConstructionError> {
+        ProcessingBlockConstructionError::check_queue_size(processing_queue_size)?;
+
+        let (processing_block, processing_queue) = unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+
+            let ptr = sys::rs2_create_temporal_filter_block(&mut err);
+            check_rs2_error!(
+                err,
+                ProcessingBlockConstructionError::CouldNotCreateProcessingBlock
+            )?;
+
+            let processing_queue =
+                ProcessingQueue::start(ptr, processing_queue_size).map_err(|e| {
+                    sys::rs2_delete_processing_block(ptr);
+                    e
+                })?;
+            (NonNull::new(ptr).unwrap(), processing_queue)
+        };
+
+        Ok(Self {
+            processing_block,
+            processing_queue,
+        })
+    }
+
+    /// Own the depth frame and queue it to be smoothed with the history of previous frames.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::QueueFull`] if the processing queue is full and the overflow
+    /// policy is [`Reject`](crate::processing_blocks::overflow::QueueOverflowPolicy::Reject).
+    pub fn queue(&mut self, frame: DepthFrame) -> Result<(), ProcessFrameError> {
+        self.processing_queue.check_overflow()?;
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_process_frame(
+                self.processing_block.as_ptr(),
+                frame.get_owned_raw().as_ptr(),
+                &mut err,
+            );
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
+            Ok(())
+        }
+    }
+
+    /// Wait to receive the results of the processing block
+    pub fn wait(&mut self, timeout: Duration) -> Result<DepthFrame, ProcessFrameError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let timeout_millis = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+            let filtered_frame =
+                sys::rs2_wait_for_frame(self.processing_queue.as_ptr(), timeout_millis, &mut err);
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
+            output_frame(NonNull::new(filtered_frame).unwrap())
+        }
+    }
+
+    /// Poll to receive the results of the processing block
+    pub fn poll(&mut self) -> Result<Poll<DepthFrame>, ProcessFrameError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let mut frame = std::ptr::null_mut::<sys::rs2_frame>();
+            let is_ready =
+                sys::rs2_poll_for_frame(self.processing_queue.as_ptr(), &mut frame, &mut err);
+
+            // Check for errors
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
+
+            // Check for queue readiness
+            if is_ready == 0 {
+                Ok(Poll::Pending)
+            } else {
+                let frame = output_frame(NonNull::new(frame).unwrap())?;
+                Ok(Poll::Ready(frame))
+            }
+        }
+    }
+
+    /// Sets the persistence control used to fill in invalid pixels from previous frames.
+    ///
+    /// For the temporal filter, [`Rs2Option::HolesFill`] is interpreted as a persistence index
+    /// rather than a hole-filling mode. Taking a [`PersistenceControl`] means the index is always
+    /// one that the filter understands.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error that [`ProcessingBlockOptions::set_option`] can return.
+    pub fn set_persistence(
+        &mut self,
+        persistence: PersistenceControl,
+    ) -> Result<(), OptionSetError> {
+        self.set_option(Rs2Option::HolesFill, persistence.to_f32().unwrap())
+    }
+}
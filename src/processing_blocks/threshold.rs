@@ -1,4 +1,4 @@
-//! Processing block that decimates the depth stream
+//! Processing block that removes depths outside of a range from the depth stream
 //!
 //! Based on an example here:
 //! https://github.com/IntelRealSense/librealsense/blob/4673a37d981164af8eeb8e296e430fc1427e008d/doc/post-processing-filters.md?plain=1#L111
@@ -6,34 +6,60 @@
 use crate::{
     check_rs2_error,
     frame::{DepthFrame, FrameEx},
-    processing_blocks::errors::{ProcessFrameError, ProcessingBlockConstructionError},
+    processing_blocks::{
+        errors::{ProcessFrameError, ProcessingBlockConstructionError},
+        options::{sealed::OptionsPtr, ProcessingBlockOptions},
+        overflow::{sealed::ProcessingQueueRef, QueueOverflow},
+        queue::{output_frame, ProcessingQueue},
+    },
 };
 use anyhow::Result;
 use realsense_sys as sys;
 use std::{convert::TryFrom, ptr::NonNull, task::Poll, time::Duration};
 
 /// Applies threshold filter and plots modified params.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct Threshold {
     /// The processing block for the "Threshold" method
     processing_block: NonNull<sys::rs2_processing_block>,
-    /// The frame queue upon which the processing block will deposit decimated frames. We check this
-    /// for completed block operations.
-    processing_queue: NonNull<sys::rs2_frame_queue>,
+    /// The frame queue upon which the processing block will deposit thresholded frames. We check
+    /// this for completed block operations.
+    processing_queue: ProcessingQueue,
 }
 
 impl Drop for Threshold {
     fn drop(&mut self) {
         unsafe {
-            sys::rs2_delete_frame_queue(self.processing_queue.as_ptr());
             sys::rs2_delete_processing_block(self.processing_block.as_ptr());
         }
     }
 }
 
+impl OptionsPtr for Threshold {
+    fn options_ptr(&self) -> NonNull<sys::rs2_options> {
+        self.processing_block.cast::<sys::rs2_options>()
+    }
+}
+
+impl ProcessingBlockOptions for Threshold {}
+
+impl ProcessingQueueRef for Threshold {
+    fn processing_queue(&self) -> &ProcessingQueue {
+        &self.processing_queue
+    }
+
+    fn processing_queue_mut(&mut self) -> &mut ProcessingQueue {
+        &mut self.processing_queue
+    }
+}
+
+impl QueueOverflow for Threshold {}
+
 impl Threshold {
     /// Create a new Threshold object
     pub fn new(processing_queue_size: i32) -> Result<Self, ProcessingBlockConstructionError> {
+        ProcessingBlockConstructionError::check_queue_size(processing_queue_size)?;
+
         let (processing_block, processing_queue) = unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
 
@@ -43,18 +69,12 @@ impl Threshold {
                 ProcessingBlockConstructionError::CouldNotCreateProcessingBlock
             )?;
 
-            let queue_ptr = sys::rs2_create_frame_queue(processing_queue_size, &mut err);
-            check_rs2_error!(
-                err,
-                ProcessingBlockConstructionError::CouldNotCreateProcessingQueue
-            )?;
-
-            sys::rs2_start_processing_queue(ptr, queue_ptr, &mut err);
-            check_rs2_error!(
-                err,
-                ProcessingBlockConstructionError::CouldNotStartProcessingQueue
-            )?;
-            (NonNull::new(ptr).unwrap(), NonNull::new(queue_ptr).unwrap())
+            let processing_queue =
+                ProcessingQueue::start(ptr, processing_queue_size).map_err(|e| {
+                    sys::rs2_delete_processing_block(ptr);
+                    e
+                })?;
+            (NonNull::new(ptr).unwrap(), processing_queue)
         };
 
         Ok(Self {
@@ -63,8 +83,14 @@ impl Threshold {
         })
     }
 
-    /// Own and process the composite frame and return the decimated frames.
+    /// Own the depth frame and queue it to have depths outside of the threshold range removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProcessFrameError::QueueFull`] if the processing queue is full and the overflow
+    /// policy is [`Reject`](crate::processing_blocks::overflow::QueueOverflowPolicy::Reject).
     pub fn queue(&mut self, frame: DepthFrame) -> Result<(), ProcessFrameError> {
+        self.processing_queue.check_overflow()?;
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
             sys::rs2_process_frame(
@@ -72,7 +98,7 @@ impl Threshold {
                 frame.get_owned_raw().as_ptr(),
                 &mut err,
             );
-            check_rs2_error!(err, |kind, context| { ProcessFrameError { kind, context } })?;
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
             Ok(())
         }
     }
@@ -82,10 +108,10 @@ impl Threshold {
         unsafe {
             let mut err = std::ptr::null_mut::<sys::rs2_error>();
             let timeout_millis = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
-            let decimated_frame =
+            let thresholded_frame =
                 sys::rs2_wait_for_frame(self.processing_queue.as_ptr(), timeout_millis, &mut err);
-            check_rs2_error!(err, |kind, context| { ProcessFrameError { kind, context } })?;
-            Ok(DepthFrame::try_from(NonNull::new(decimated_frame).unwrap()).unwrap())
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
+            output_frame(NonNull::new(thresholded_frame).unwrap())
         }
     }
 
@@ -98,15 +124,14 @@ impl Threshold {
                 sys::rs2_poll_for_frame(self.processing_queue.as_ptr(), &mut frame, &mut err);
 
             // Check for errors
-            check_rs2_error!(err, |kind, context| { ProcessFrameError { kind, context } })?;
+            check_rs2_error!(err, ProcessFrameError::CouldNotProcessFrame)?;
 
             // Check for queue readiness
             if is_ready == 0 {
                 Ok(Poll::Pending)
             } else {
-                Ok(Poll::Ready(
-                    DepthFrame::try_from(NonNull::new(frame).unwrap()).unwrap(),
-                ))
+                let frame = output_frame(NonNull::new(frame).unwrap())?;
+                Ok(Poll::Ready(frame))
             }
         }
     }
@@ -17,8 +17,9 @@ use crate::{
     device::{Device, DeviceConstructionError},
     kind::{
         OptionSetError, Rs2CameraInfo, Rs2Exception, Rs2Extension, Rs2Option, Rs2OptionRange,
-        SENSOR_EXTENSIONS,
+        Rs2StreamKind, SENSOR_EXTENSIONS,
     },
+    processing_blocks::recommended::RecommendedFilter,
     stream_profile::StreamProfile,
 };
 use anyhow::Result;
@@ -31,6 +32,23 @@ use std::{
 };
 use thiserror::Error;
 
+mod color;
+mod depth;
+mod l500;
+mod motion;
+mod pose;
+mod stream;
+
+pub use color::ColorSensor;
+pub use depth::DepthSensor;
+pub use l500::L500DepthSensor;
+pub use motion::MotionSensor;
+pub use pose::{
+    LocalizationMapError, PoseEvent, PoseEventError, PoseSensor, StaticNodeError,
+    WheelOdometryError,
+};
+pub use stream::{SensorStream, SensorStreamError};
+
 /// Type describing errors that can occur when trying to construct a sensor.
 ///
 /// Follows the standard pattern of errors where the enum variant describes what the low-level code
@@ -180,6 +198,30 @@ impl Sensor {
         *ext
     }
 
+    /// Predicate for determining if this sensor extends to the provided extension.
+    ///
+    /// Unlike [`Sensor::extension`], which reports a single extension, this is true for every
+    /// extension the sensor supports. e.g. an L500 depth sensor extends to both
+    /// [`Rs2Extension::DepthSensor`] and [`Rs2Extension::L500DepthSensor`].
+    pub fn is_extendable_to(&self, extension: Rs2Extension) -> bool {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let is_extendable = sys::rs2_is_sensor_extendable_to(
+                self.sensor_ptr.as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (extension as i32).try_into().unwrap(),
+                &mut err,
+            );
+
+            if err.as_ref().is_none() {
+                is_extendable != 0
+            } else {
+                sys::rs2_free_error(err);
+                false
+            }
+        }
+    }
+
     /// Get the value associated with the provided Rs2Option for the sensor.
     ///
     /// Returns An `f32` value corresponding to that option within the librealsense2 library, or None
@@ -245,6 +287,23 @@ impl Sensor {
         }
     }
 
+    /// Sets the `value` of the option with the human-readable `name`.
+    ///
+    /// The name is looked up with [`Rs2Option::from_name`], so it matches the names librealsense2
+    /// reports for options (e.g. `"Laser Power"`), ignoring case. This is useful when options come
+    /// from configuration files or command line arguments.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptionSetError::UnknownOptionName`] if no option has the given name.
+    ///
+    /// Otherwise returns any error that [`Sensor::set_option`] can return.
+    pub fn set_option_by_name(&mut self, name: &str, value: f32) -> Result<(), OptionSetError> {
+        let option = Rs2Option::from_name(name)
+            .ok_or_else(|| OptionSetError::UnknownOptionName(name.to_owned()))?;
+        self.set_option(option, value)
+    }
+
     /// Gets the range for a given option.
     ///
     /// Returns some option range if the sensor supports the option, else `None`.
@@ -286,6 +345,78 @@ impl Sensor {
         }
     }
 
+    /// Sets the laser power of the sensor as a percentage of its supported range.
+    ///
+    /// Absolute laser power values differ between device models. This maps `percent` in
+    /// `[0, 100]` onto the range reported for [`Rs2Option::LaserPower`], rounding to the nearest
+    /// step the sensor accepts.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptionSetError::OptionValueOutOfRange`] if `percent` is not in `[0, 100]`.
+    ///
+    /// Returns [`OptionSetError::OptionNotSupported`] if the sensor has no laser power option, or
+    /// its range cannot be queried.
+    ///
+    /// Returns any other error that [`Sensor::set_option`] can return.
+    pub fn set_laser_power_percent(&mut self, percent: f32) -> Result<(), OptionSetError> {
+        if !(0.0..=100.0).contains(&percent) {
+            return Err(OptionSetError::OptionValueOutOfRange {
+                value: percent,
+                min: 0.0,
+                max: 100.0,
+            });
+        }
+
+        let range = self
+            .get_option_range(Rs2Option::LaserPower)
+            .ok_or(OptionSetError::OptionNotSupported)?;
+
+        let mut value = range.min + (range.max - range.min) * percent / 100.0;
+        if range.step > 0.0 {
+            value = range.min + ((value - range.min) / range.step).round() * range.step;
+        }
+
+        self.set_option(Rs2Option::LaserPower, value.clamp(range.min, range.max))
+    }
+
+    /// Put the sensor in low-latency mode, delivering only the most recent frame.
+    ///
+    /// This sets [`Rs2Option::FramesQueueSize`] to zero, so that frames are not buffered by the
+    /// sensor and are instead dropped if the consumer falls behind. This is useful for control
+    /// loops that always want the freshest frame, at the cost of skipping frames under load.
+    ///
+    /// The pipeline keeps its own small queue of composite frames on top of this, so frames may
+    /// still be up to one composite frame behind. Use [`ActivePipeline::poll`] and keep only the
+    /// last ready frame if that matters.
+    ///
+    /// [`ActivePipeline::poll`]: crate::pipeline::ActivePipeline::poll
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptionSetError::OptionNotSupported`] if the sensor does not support setting its
+    /// frame queue size.
+    ///
+    /// Returns [`OptionSetError::OptionValueOutOfRange`] if the sensor does not accept a queue size
+    /// of zero.
+    ///
+    /// Returns any other error that [`Sensor::set_option`] can return.
+    pub fn set_low_latency_mode(&mut self) -> Result<(), OptionSetError> {
+        let range = self
+            .get_option_range(Rs2Option::FramesQueueSize)
+            .ok_or(OptionSetError::OptionNotSupported)?;
+
+        if range.min > 0.0 {
+            return Err(OptionSetError::OptionValueOutOfRange {
+                value: 0.0,
+                min: range.min,
+                max: range.max,
+            });
+        }
+
+        self.set_option(Rs2Option::FramesQueueSize, 0.0)
+    }
+
     /// Predicate for determining if this sensor supports a given option
     ///
     /// Returns true iff the option is supported by this sensor.
@@ -372,7 +503,73 @@ impl Sensor {
         profiles
     }
 
-    // fn recommended_processing_blocks(&self) -> Vec<ProcessingBlock>{}
+    /// Get the framerates the sensor supports for streams of the given kind.
+    ///
+    /// This is most useful for motion streams, where the valid sample rates (e.g. 63 / 250 Hz for
+    /// the accelerometer, 200 / 400 Hz for the gyroscope) vary between devices. The returned rates
+    /// are sorted in ascending order and contain no duplicates. The vector will have a length of
+    /// zero if the sensor has no streams of the given kind.
+    pub fn supported_framerates(&self, kind: Rs2StreamKind) -> Vec<i32> {
+        let mut framerates: Vec<i32> = self
+            .stream_profiles()
+            .iter()
+            .filter(|profile| profile.kind() == kind)
+            .map(|profile| profile.framerate())
+            .collect();
+        framerates.sort_unstable();
+        framerates.dedup();
+        framerates
+    }
+
+    /// Get the processing blocks recommended for the streams of this sensor.
+    ///
+    /// Returns the filters in the order in which they should be applied, each constructed with the
+    /// option values recommended by the sensor. Recommended blocks that this crate does not provide
+    /// a filter for, or that fail to construct, are skipped. The vector will have a length of zero
+    /// if an error occurs while getting the recommendations.
+    pub fn recommended_filters(&self) -> Vec<RecommendedFilter> {
+        let mut filters = Vec::new();
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let blocks_ptr =
+                sys::rs2_get_recommended_processing_blocks(self.sensor_ptr.as_ptr(), &mut err);
+            if err.as_ref().is_some() {
+                sys::rs2_free_error(err);
+                return filters;
+            }
+
+            let nonnull_blocks_ptr = NonNull::new(blocks_ptr).unwrap();
+            let len = sys::rs2_get_recommended_processing_blocks_count(
+                nonnull_blocks_ptr.as_ptr(),
+                &mut err,
+            );
+
+            if err.as_ref().is_some() {
+                sys::rs2_free_error(err);
+                sys::rs2_delete_recommended_processing_blocks(nonnull_blocks_ptr.as_ptr());
+                return filters;
+            }
+
+            for i in 0..len {
+                let block_ptr =
+                    sys::rs2_get_processing_block(nonnull_blocks_ptr.as_ptr(), i, &mut err);
+
+                if err.as_ref().is_some() {
+                    sys::rs2_free_error(err);
+                    err = std::ptr::null_mut();
+                    continue;
+                }
+
+                if let Some(Ok(filter)) =
+                    RecommendedFilter::from_recommended(NonNull::new(block_ptr).unwrap())
+                {
+                    filters.push(filter);
+                }
+            }
+            sys::rs2_delete_recommended_processing_blocks(nonnull_blocks_ptr.as_ptr());
+        }
+        filters
+    }
 
     /// Gets the value associated with the provided camera info key from the sensor.
     ///
@@ -482,4 +679,34 @@ impl Sensor {
             check_rs2_error!(err, RoiSetError::CouldNotSetRoi)
         }
     }
+
+    /// Open the sensor with the given stream profiles, and start streaming from it directly.
+    ///
+    /// This bypasses the pipeline, so frames of different streams are delivered as the sensor
+    /// produces them, without being synchronized. Frames are buffered in a queue holding up to
+    /// `queue_size` frames; older frames are dropped when the queue is full.
+    ///
+    /// `profiles` must be profiles of this sensor, e.g. from
+    /// [`stream_profiles`](Sensor::stream_profiles). The returned [`SensorStream`] takes ownership
+    /// of the sensor, and stops and closes it when dropped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SensorStreamError::NoStreamProfiles`] if `profiles` is empty.
+    ///
+    /// Returns [`SensorStreamError::InvalidQueueSize`] if `queue_size` is zero.
+    ///
+    /// Returns [`SensorStreamError::CouldNotOpenSensor`] if the sensor cannot be opened with
+    /// `profiles`, e.g. because it is already streaming as part of a pipeline.
+    ///
+    /// Returns [`SensorStreamError::CouldNotCreateFrameQueue`] or
+    /// [`SensorStreamError::CouldNotStartSensor`] if streaming cannot be started. The sensor is
+    /// closed again in that case.
+    pub fn open(
+        self,
+        profiles: &[&StreamProfile],
+        queue_size: usize,
+    ) -> Result<SensorStream, SensorStreamError> {
+        SensorStream::open(self, profiles, queue_size)
+    }
 }
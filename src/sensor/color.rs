@@ -0,0 +1,107 @@
+//! Type for the color sensor of a device, e.g. the RGB camera of a D435.
+//!
+//! This wraps a [`Sensor`] so that color-specific options can be set through typed, validated
+//! values instead of raw floats.
+
+use super::Sensor;
+use crate::kind::{OptionSetError, Rs2Extension, Rs2Option};
+use std::convert::TryFrom;
+
+/// The color sensor of a device.
+///
+/// Constructed from a [`Sensor`] that extends to [`Rs2Extension::ColorSensor`]. All of the generic
+/// sensor APIs remain available through [`sensor`](ColorSensor::sensor) and
+/// [`sensor_mut`](ColorSensor::sensor_mut).
+pub struct ColorSensor {
+    /// The underlying sensor.
+    sensor: Sensor,
+}
+
+impl TryFrom<Sensor> for ColorSensor {
+    type Error = Sensor;
+
+    /// Attempt to construct a color sensor from a generic sensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns the sensor back if it does not extend to [`Rs2Extension::ColorSensor`].
+    fn try_from(sensor: Sensor) -> Result<Self, Self::Error> {
+        if sensor.is_extendable_to(Rs2Extension::ColorSensor) {
+            Ok(Self { sensor })
+        } else {
+            Err(sensor)
+        }
+    }
+}
+
+impl ColorSensor {
+    /// Get a reference to the underlying sensor.
+    pub fn sensor(&self) -> &Sensor {
+        &self.sensor
+    }
+
+    /// Get a mutable reference to the underlying sensor.
+    pub fn sensor_mut(&mut self) -> &mut Sensor {
+        &mut self.sensor
+    }
+
+    /// Consume the color sensor, returning the underlying sensor.
+    pub fn into_inner(self) -> Sensor {
+        self.sensor
+    }
+
+    /// Get the current white balance in Kelvin, or `None` if it cannot be read.
+    pub fn white_balance(&self) -> Option<f32> {
+        self.sensor.get_option(Rs2Option::WhiteBalance)
+    }
+
+    /// Set a manual white balance, in Kelvin.
+    ///
+    /// Manual white balance values are ignored by the sensor while auto white balance is enabled,
+    /// so this disables auto white balance first.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptionSetError::OptionNotSupported`] if the sensor does not support setting the
+    /// white balance.
+    ///
+    /// Returns [`OptionSetError::OptionValueOutOfRange`] if `kelvin` is outside of the range
+    /// supported by the sensor.
+    ///
+    /// Returns any other error that [`Sensor::set_option`] can return.
+    pub fn set_white_balance(&mut self, kelvin: f32) -> Result<(), OptionSetError> {
+        let range = self
+            .sensor
+            .get_option_range(Rs2Option::WhiteBalance)
+            .ok_or(OptionSetError::OptionNotSupported)?;
+
+        if !(range.min..=range.max).contains(&kelvin) {
+            return Err(OptionSetError::OptionValueOutOfRange {
+                value: kelvin,
+                min: range.min,
+                max: range.max,
+            });
+        }
+
+        self.set_auto_white_balance(false)?;
+        self.sensor.set_option(Rs2Option::WhiteBalance, kelvin)
+    }
+
+    /// Get whether auto white balance is enabled, or `None` if it cannot be read.
+    pub fn auto_white_balance(&self) -> Option<bool> {
+        self.sensor
+            .get_option(Rs2Option::EnableAutoWhiteBalance)
+            .map(|val| val != 0.0)
+    }
+
+    /// Enable or disable auto white balance.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error that [`Sensor::set_option`] can return.
+    pub fn set_auto_white_balance(&mut self, enabled: bool) -> Result<(), OptionSetError> {
+        let val = if enabled { 1.0 } else { 0.0 };
+        self.sensor
+            .set_option(Rs2Option::EnableAutoWhiteBalance, val)
+    }
+}
@@ -0,0 +1,199 @@
+//! Type for the depth sensor of a device, e.g. the stereo module of a D435.
+//!
+//! This wraps a [`Sensor`] so that depth-specific options can be accessed through typed values
+//! instead of raw floats.
+
+use super::Sensor;
+use crate::kind::{
+    DepthCameraModel, InterCamSyncMode, OptionSetError, Rs2CameraInfo, Rs2EmitterFrequency,
+    Rs2Extension, Rs2Option, Rs2Rs400VisualPreset,
+};
+use num_traits::{FromPrimitive, ToPrimitive};
+use std::convert::TryFrom;
+
+/// The depth sensor of a device.
+///
+/// Constructed from a [`Sensor`] that extends to [`Rs2Extension::DepthSensor`]. All of the generic
+/// sensor APIs remain available through [`sensor`](DepthSensor::sensor) and
+/// [`sensor_mut`](DepthSensor::sensor_mut).
+pub struct DepthSensor {
+    /// The underlying sensor.
+    sensor: Sensor,
+}
+
+impl TryFrom<Sensor> for DepthSensor {
+    type Error = Sensor;
+
+    /// Attempt to construct a depth sensor from a generic sensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns the sensor back if it does not extend to [`Rs2Extension::DepthSensor`].
+    fn try_from(sensor: Sensor) -> Result<Self, Self::Error> {
+        if sensor.is_extendable_to(Rs2Extension::DepthSensor) {
+            Ok(Self { sensor })
+        } else {
+            Err(sensor)
+        }
+    }
+}
+
+impl DepthSensor {
+    /// Get a reference to the underlying sensor.
+    pub fn sensor(&self) -> &Sensor {
+        &self.sensor
+    }
+
+    /// Get a mutable reference to the underlying sensor.
+    pub fn sensor_mut(&mut self) -> &mut Sensor {
+        &mut self.sensor
+    }
+
+    /// Consume the depth sensor, returning the underlying sensor.
+    pub fn into_inner(self) -> Sensor {
+        self.sensor
+    }
+
+    /// Get the size of one unit of depth in meters, or `None` if it cannot be read.
+    ///
+    /// Multiplying a raw depth value by this gives the distance in meters.
+    pub fn depth_units(&self) -> Option<f32> {
+        self.sensor.get_option(Rs2Option::DepthUnits)
+    }
+
+    /// Set the size of one unit of depth in meters.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error that [`Sensor::set_option`] can return.
+    pub fn set_depth_units(&mut self, meters: f32) -> Result<(), OptionSetError> {
+        self.sensor.set_option(Rs2Option::DepthUnits, meters)
+    }
+
+    /// Get the model of the camera that the sensor belongs to, or `None` if it is not one of the
+    /// models in [`DepthCameraModel`] or its name cannot be read.
+    pub fn model(&self) -> Option<DepthCameraModel> {
+        let name = self.sensor.info(Rs2CameraInfo::Name)?;
+        DepthCameraModel::from_name(&name.to_string_lossy())
+    }
+
+    /// Apply the recommended depth settings for `model` to the sensor.
+    ///
+    /// This sets the depth units to [`DepthCameraModel::depth_units`], so that e.g. a D405 keeps
+    /// its sub-millimeter precision while a D435 keeps its range. The working range of the model,
+    /// [`DepthCameraModel::ideal_range`], is not a sensor option; apply it to a
+    /// [`Threshold`](crate::processing_blocks::threshold::Threshold) filter through
+    /// [`Rs2Option::MinDistance`] and [`Rs2Option::MaxDistance`].
+    ///
+    /// Pass [`model`](DepthSensor::model) to apply the settings for the detected camera.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error that [`Sensor::set_option`] can return.
+    pub fn apply_defaults_for(&mut self, model: DepthCameraModel) -> Result<(), OptionSetError> {
+        self.set_depth_units(model.depth_units())
+    }
+
+    /// Get the distance between the two cameras of a stereo depth sensor in millimeters, or `None`
+    /// if it cannot be read, e.g. because the sensor is not a stereo sensor.
+    pub fn stereo_baseline(&self) -> Option<f32> {
+        self.sensor.get_option(Rs2Option::StereoBaseline)
+    }
+
+    /// Get the hardware synchronization mode of the sensor, or `None` if it cannot be read.
+    ///
+    /// Also returns `None` for the genlock modes that some firmware supports beyond
+    /// [`InterCamSyncMode`].
+    pub fn inter_cam_sync_mode(&self) -> Option<InterCamSyncMode> {
+        let val = self.sensor.get_option(Rs2Option::InterCamSyncMode)?;
+        InterCamSyncMode::from_f32(val)
+    }
+
+    /// Set the hardware synchronization mode of the sensor.
+    ///
+    /// Only D400 devices with a sync connector and L500 devices support this; see
+    /// [`InterCamSyncMode`].
+    ///
+    /// # Errors
+    ///
+    /// Returns any error that [`Sensor::set_option`] can return, e.g.
+    /// [`OptionSetError::OptionNotSupported`] if the device has no sync connector.
+    pub fn set_inter_cam_sync_mode(
+        &mut self,
+        mode: InterCamSyncMode,
+    ) -> Result<(), OptionSetError> {
+        self.sensor
+            .set_option(Rs2Option::InterCamSyncMode, mode.to_f32().unwrap())
+    }
+
+    /// Get the frequency of the IR emitter, or `None` if it cannot be read, e.g. because the
+    /// camera does not support changing it.
+    pub fn emitter_frequency(&self) -> Option<Rs2EmitterFrequency> {
+        let val = self.sensor.get_option(Rs2Option::EmitterFrequency)?;
+        Rs2EmitterFrequency::from_f32(val)
+    }
+
+    /// Set the frequency of the IR emitter.
+    ///
+    /// Giving neighbouring cameras of a rig different frequencies reduces the interference between
+    /// their projected patterns. Only some cameras support this, e.g. the D455.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error that [`Sensor::set_option`] can return, e.g.
+    /// [`OptionSetError::OptionNotSupported`] if the emitter frequency cannot be changed.
+    pub fn set_emitter_frequency(
+        &mut self,
+        frequency: Rs2EmitterFrequency,
+    ) -> Result<(), OptionSetError> {
+        self.sensor
+            .set_option(Rs2Option::EmitterFrequency, frequency.to_f32().unwrap())
+    }
+
+    /// Get the visual preset that is currently applied, or `None` if it cannot be read.
+    ///
+    /// Some firmware versions silently fall back to another preset if the one that was set is not
+    /// supported, so reading the preset back after [`set_visual_preset`](Self::set_visual_preset)
+    /// is the way to verify that it was applied. Changing any option that a preset controls turns
+    /// the preset into [`Rs2Rs400VisualPreset::Custom`].
+    pub fn current_visual_preset(&self) -> Option<Rs2Rs400VisualPreset> {
+        let val = self.sensor.get_option(Rs2Option::VisualPreset)?;
+        Rs2Rs400VisualPreset::from_f32(val)
+    }
+
+    /// Set the visual preset of the sensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error that [`Sensor::set_option`] can return, e.g.
+    /// [`OptionSetError::OptionNotSupported`] if the sensor has no visual presets.
+    pub fn set_visual_preset(
+        &mut self,
+        preset: Rs2Rs400VisualPreset,
+    ) -> Result<(), OptionSetError> {
+        self.sensor
+            .set_option(Rs2Option::VisualPreset, preset.to_f32().unwrap())
+    }
+
+    /// Get whether the sensor outputs a trigger on every depth frame, or `None` if it cannot be
+    /// read.
+    pub fn output_trigger_enabled(&self) -> Option<bool> {
+        self.sensor
+            .get_option(Rs2Option::OutputTriggerEnabled)
+            .map(|val| val != 0.0)
+    }
+
+    /// Enable or disable the trigger output on every depth frame.
+    ///
+    /// The trigger is output on the GPIO / sync connector of the device, and can drive other
+    /// cameras or external hardware. This is supported on D400 devices with a sync connector.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error that [`Sensor::set_option`] can return, e.g.
+    /// [`OptionSetError::OptionNotSupported`] if the device has no trigger output.
+    pub fn set_output_trigger_enabled(&mut self, enabled: bool) -> Result<(), OptionSetError> {
+        let val = if enabled { 1.0 } else { 0.0 };
+        self.sensor.set_option(Rs2Option::OutputTriggerEnabled, val)
+    }
+}
@@ -0,0 +1,144 @@
+//! Type for the depth sensor of an L500 device, e.g. an L515.
+//!
+//! L500 depth sensors have a number of options that do not exist on other devices. This wraps a
+//! [`Sensor`] so that these options can be accessed through typed values instead of raw floats.
+
+use super::Sensor;
+use crate::kind::{
+    OptionSetError, Rs2DigitalGain, Rs2Extension, Rs2HostPerfMode, Rs2L500VisualPreset, Rs2Option,
+};
+use num_traits::{FromPrimitive, ToPrimitive};
+use std::convert::TryFrom;
+
+/// The depth sensor of an L500 device.
+///
+/// Constructed from a [`Sensor`] that extends to [`Rs2Extension::L500DepthSensor`]. All of the
+/// generic sensor APIs remain available through [`sensor`](L500DepthSensor::sensor) and
+/// [`sensor_mut`](L500DepthSensor::sensor_mut).
+pub struct L500DepthSensor {
+    /// The underlying sensor.
+    sensor: Sensor,
+}
+
+impl TryFrom<Sensor> for L500DepthSensor {
+    type Error = Sensor;
+
+    /// Attempt to construct an L500 depth sensor from a generic sensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns the sensor back if it does not extend to [`Rs2Extension::L500DepthSensor`].
+    fn try_from(sensor: Sensor) -> Result<Self, Self::Error> {
+        if sensor.is_extendable_to(Rs2Extension::L500DepthSensor) {
+            Ok(Self { sensor })
+        } else {
+            Err(sensor)
+        }
+    }
+}
+
+impl L500DepthSensor {
+    /// Get a reference to the underlying sensor.
+    pub fn sensor(&self) -> &Sensor {
+        &self.sensor
+    }
+
+    /// Get a mutable reference to the underlying sensor.
+    pub fn sensor_mut(&mut self) -> &mut Sensor {
+        &mut self.sensor
+    }
+
+    /// Consume the L500 depth sensor, returning the underlying sensor.
+    pub fn into_inner(self) -> Sensor {
+        self.sensor
+    }
+
+    /// Get the current visual preset, or `None` if it cannot be read.
+    pub fn visual_preset(&self) -> Option<Rs2L500VisualPreset> {
+        let val = self.sensor.get_option(Rs2Option::VisualPreset)?;
+        Rs2L500VisualPreset::from_f32(val)
+    }
+
+    /// Set the visual preset of the sensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error that [`Sensor::set_option`] can return.
+    pub fn set_visual_preset(&mut self, preset: Rs2L500VisualPreset) -> Result<(), OptionSetError> {
+        self.sensor
+            .set_option(Rs2Option::VisualPreset, preset.to_f32().unwrap())
+    }
+
+    /// Get the current digital gain, or `None` if it cannot be read.
+    pub fn digital_gain(&self) -> Option<Rs2DigitalGain> {
+        let val = self.sensor.get_option(Rs2Option::DigitalGain)?;
+        Rs2DigitalGain::from_f32(val)
+    }
+
+    /// Set the digital gain of the sensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error that [`Sensor::set_option`] can return.
+    pub fn set_digital_gain(&mut self, gain: Rs2DigitalGain) -> Result<(), OptionSetError> {
+        self.sensor
+            .set_option(Rs2Option::DigitalGain, gain.to_f32().unwrap())
+    }
+
+    /// Get the current host performance mode, or `None` if it cannot be read.
+    pub fn host_performance(&self) -> Option<Rs2HostPerfMode> {
+        let val = self.sensor.get_option(Rs2Option::HostPerformance)?;
+        Rs2HostPerfMode::from_f32(val)
+    }
+
+    /// Set the host performance mode of the sensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error that [`Sensor::set_option`] can return.
+    pub fn set_host_performance(&mut self, mode: Rs2HostPerfMode) -> Result<(), OptionSetError> {
+        self.sensor
+            .set_option(Rs2Option::HostPerformance, mode.to_f32().unwrap())
+    }
+
+    /// Get the current confidence threshold, or `None` if it cannot be read.
+    pub fn confidence_threshold(&self) -> Option<u8> {
+        let val = self.sensor.get_option(Rs2Option::ConfidenceThreshold)?;
+        Some(val as u8)
+    }
+
+    /// Set the confidence threshold of the sensor.
+    ///
+    /// Depth pixels with a confidence below the threshold are invalidated. Raising the threshold
+    /// trades depth coverage for fewer false depth values; on the L515 it ranges from 0 to 3.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`OptionSetError::OptionNotSupported`] if the sensor does not support setting the
+    /// confidence threshold.
+    ///
+    /// Returns [`OptionSetError::OptionValueOutOfRange`] if `threshold` is outside of the range
+    /// supported by the sensor.
+    ///
+    /// Returns any other error that [`Sensor::set_option`] can return.
+    pub fn set_confidence_threshold(&mut self, threshold: u8) -> Result<(), OptionSetError> {
+        let value = f32::from(threshold);
+        self.sensor
+            .get_option_range(Rs2Option::ConfidenceThreshold)
+            .ok_or(OptionSetError::OptionNotSupported)?
+            .validate(value)?;
+
+        self.sensor
+            .set_option(Rs2Option::ConfidenceThreshold, value)
+    }
+
+    /// Get the temperature of the laser diode (LLD) in Celsius, or `None` if it cannot be read.
+    pub fn lld_temperature(&self) -> Option<f32> {
+        self.sensor.get_option(Rs2Option::LldTemperature)
+    }
+
+    /// Get an estimation of the noise on the IR image, or `None` if it cannot be read.
+    pub fn noise_estimation(&self) -> Option<f32> {
+        self.sensor.get_option(Rs2Option::NoiseEstimation)
+    }
+}
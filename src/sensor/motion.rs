@@ -0,0 +1,72 @@
+//! Type for the motion sensor of a device, e.g. the IMU of a D435i.
+//!
+//! This wraps a [`Sensor`] so that motion-specific options can be accessed through typed values
+//! instead of raw floats.
+
+use super::Sensor;
+use crate::kind::{OptionSetError, Rs2Extension, Rs2Option};
+use std::convert::TryFrom;
+
+/// The motion sensor of a device.
+///
+/// Constructed from a [`Sensor`] that extends to [`Rs2Extension::MotionSensor`]. All of the
+/// generic sensor APIs remain available through [`sensor`](MotionSensor::sensor) and
+/// [`sensor_mut`](MotionSensor::sensor_mut).
+pub struct MotionSensor {
+    /// The underlying sensor.
+    sensor: Sensor,
+}
+
+impl TryFrom<Sensor> for MotionSensor {
+    type Error = Sensor;
+
+    /// Attempt to construct a motion sensor from a generic sensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns the sensor back if it does not extend to [`Rs2Extension::MotionSensor`].
+    fn try_from(sensor: Sensor) -> Result<Self, Self::Error> {
+        if sensor.is_extendable_to(Rs2Extension::MotionSensor) {
+            Ok(Self { sensor })
+        } else {
+            Err(sensor)
+        }
+    }
+}
+
+impl MotionSensor {
+    /// Get a reference to the underlying sensor.
+    pub fn sensor(&self) -> &Sensor {
+        &self.sensor
+    }
+
+    /// Get a mutable reference to the underlying sensor.
+    pub fn sensor_mut(&mut self) -> &mut Sensor {
+        &mut self.sensor
+    }
+
+    /// Consume the motion sensor, returning the underlying sensor.
+    pub fn into_inner(self) -> Sensor {
+        self.sensor
+    }
+
+    /// Get whether motion correction is enabled, or `None` if it cannot be read.
+    ///
+    /// Motion correction applies the intrinsic calibration of the IMU to the accel and gyro data.
+    pub fn motion_correction(&self) -> Option<bool> {
+        self.sensor
+            .get_option(Rs2Option::EnableMotionCorrection)
+            .map(|val| val != 0.0)
+    }
+
+    /// Enable or disable motion correction.
+    ///
+    /// # Errors
+    ///
+    /// Returns any error that [`Sensor::set_option`] can return.
+    pub fn set_motion_correction(&mut self, enabled: bool) -> Result<(), OptionSetError> {
+        let val = if enabled { 1.0 } else { 0.0 };
+        self.sensor
+            .set_option(Rs2Option::EnableMotionCorrection, val)
+    }
+}
@@ -0,0 +1,632 @@
+//! Type for the pose sensor of a tracking device, e.g. the T265.
+//!
+//! This wraps a [`Sensor`] so that tracking-specific APIs, such as feeding wheel odometry into the
+//! pose estimate, reusing localization maps, tagging static nodes or listening for relocalization
+//! events, can be used.
+
+use super::Sensor;
+use crate::{
+    check_rs2_error,
+    kind::{Rs2Exception, Rs2Extension, Rs2LogSeverity, Rs2NotificationCategory},
+};
+use num_traits::FromPrimitive;
+use realsense_sys as sys;
+use std::{
+    convert::TryFrom,
+    ffi::{CStr, CString},
+    os::raw::{c_char, c_uint, c_void},
+    panic::{self, AssertUnwindSafe},
+    sync::Mutex,
+};
+use thiserror::Error;
+
+/// Type describing errors that can occur when feeding wheel odometry to a pose sensor.
+///
+/// Follows the standard pattern of errors where the enum variant describes what the low-level code
+/// was attempting to do while the string carried alongside describes the underlying error message
+/// from any C++ exceptions that occur.
+#[derive(Error, Debug)]
+pub enum WheelOdometryError {
+    /// Could not load the wheel odometry calibration.
+    #[error("Could not load wheel odometry config. Type: {0}; Reason: {1}")]
+    CouldNotLoadConfig(Rs2Exception, String),
+    /// The sensor did not accept the wheel odometry calibration.
+    #[error("Wheel odometry config was rejected by the sensor.")]
+    ConfigRejected,
+    /// The wheel odometry calibration is too large to be sent to the sensor.
+    #[error("Wheel odometry config is too large: {0} bytes.")]
+    ConfigTooLarge(usize),
+    /// Could not send the wheel odometry measurement.
+    #[error("Could not send wheel odometry. Type: {0}; Reason: {1}")]
+    CouldNotSendOdometry(Rs2Exception, String),
+    /// The sensor did not accept the wheel odometry measurement.
+    #[error("Wheel odometry was rejected by the sensor.")]
+    OdometryRejected,
+}
+
+/// Type describing errors that can occur when exporting or importing a localization map.
+///
+/// Follows the standard pattern of errors where the enum variant describes what the low-level code
+/// was attempting to do while the string carried alongside describes the underlying error message
+/// from any C++ exceptions that occur.
+#[derive(Error, Debug)]
+pub enum LocalizationMapError {
+    /// Could not export the localization map from the sensor.
+    #[error("Could not export localization map. Type: {0}; Reason: {1}")]
+    CouldNotExportMap(Rs2Exception, String),
+    /// Could not read the exported localization map.
+    #[error("Could not read exported localization map. Type: {0}; Reason: {1}")]
+    CouldNotReadMap(Rs2Exception, String),
+    /// Could not import the localization map into the sensor.
+    #[error("Could not import localization map. Type: {0}; Reason: {1}")]
+    CouldNotImportMap(Rs2Exception, String),
+    /// The sensor did not accept the localization map.
+    #[error("Localization map was rejected by the sensor.")]
+    MapRejected,
+    /// The localization map is too large to be sent to the sensor.
+    #[error("Localization map is too large: {0} bytes.")]
+    MapTooLarge(usize),
+}
+
+/// The maximum length of the identifier of a static node, in bytes.
+const MAX_STATIC_NODE_GUID_LEN: usize = 127;
+
+/// Type describing errors that can occur when setting, getting or removing a static node.
+///
+/// Follows the standard pattern of errors where the enum variant describes what the low-level code
+/// was attempting to do while the string carried alongside describes the underlying error message
+/// from any C++ exceptions that occur.
+#[derive(Error, Debug)]
+pub enum StaticNodeError {
+    /// The identifier is longer than 127 bytes, or contains a nul byte.
+    #[error("Invalid static node identifier: {0:?}.")]
+    InvalidGuid(String),
+    /// Could not set the static node.
+    #[error("Could not set static node. Type: {0}; Reason: {1}")]
+    CouldNotSetNode(Rs2Exception, String),
+    /// The sensor did not accept the static node.
+    #[error("Static node was rejected by the sensor.")]
+    NodeRejected,
+    /// Could not get the static node.
+    #[error("Could not get static node. Type: {0}; Reason: {1}")]
+    CouldNotGetNode(Rs2Exception, String),
+    /// Could not remove the static node.
+    #[error("Could not remove static node. Type: {0}; Reason: {1}")]
+    CouldNotRemoveNode(Rs2Exception, String),
+}
+
+/// Convert the identifier of a static node to the null-terminated form librealsense2 expects.
+///
+/// # Errors
+///
+/// Returns [`StaticNodeError::InvalidGuid`] if `guid` is longer than 127 bytes, or contains a nul
+/// byte.
+fn static_node_guid(guid: &str) -> Result<CString, StaticNodeError> {
+    if guid.len() > MAX_STATIC_NODE_GUID_LEN {
+        return Err(StaticNodeError::InvalidGuid(guid.to_owned()));
+    }
+    CString::new(guid).map_err(|_| StaticNodeError::InvalidGuid(guid.to_owned()))
+}
+
+/// Type describing errors that can occur when listening for events of a pose sensor.
+///
+/// Follows the standard pattern of errors where the enum variant describes what the low-level code
+/// was attempting to do while the string carried alongside describes the underlying error message
+/// from any C++ exceptions that occur.
+#[derive(Error, Debug)]
+pub enum PoseEventError {
+    /// Could not register a callback for the events of the sensor.
+    #[error("Could not set pose event callback. Type: {0}; Reason: {1}")]
+    CouldNotSetEventCallback(Rs2Exception, String),
+}
+
+/// An event reported by a pose sensor while tracking, e.g. a relocalization.
+///
+/// Relocalizations are reported with the category
+/// [`Rs2NotificationCategory::PoseRelocalization`]. Loss of tracking is not an event, but shows in
+/// the [`tracker_confidence`](crate::frame::PoseFrame::tracker_confidence) of the pose frames.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoseEvent {
+    /// The category of the event.
+    ///
+    /// `None` if librealsense2 reports a category that this crate does not know.
+    pub category: Option<Rs2NotificationCategory>,
+    /// The severity of the event.
+    ///
+    /// `None` if librealsense2 reports a severity that this crate does not know.
+    pub severity: Option<Rs2LogSeverity>,
+    /// The time at which the event occurred, in milliseconds.
+    pub timestamp: f64,
+    /// A human-readable description of the event.
+    pub description: String,
+    /// Additional data about the event as JSON, which is empty if there is none.
+    pub serialized_data: String,
+}
+
+impl PoseEvent {
+    /// Whether the event is a relocalization, after which poses jump to correct the accumulated
+    /// drift.
+    pub fn is_relocalization(&self) -> bool {
+        self.category == Some(Rs2NotificationCategory::PoseRelocalization)
+    }
+
+    /// Read an event from a notification handed to a callback by librealsense2.
+    ///
+    /// Fields that cannot be read are left empty.
+    ///
+    /// # Safety
+    ///
+    /// `notification` must point to a valid notification.
+    unsafe fn from_raw(notification: *mut sys::rs2_notification) -> Self {
+        /// Read a string field of the notification, where null reads as an empty string.
+        unsafe fn string(ptr: *const c_char) -> String {
+            if ptr.is_null() {
+                String::new()
+            } else {
+                CStr::from_ptr(ptr).to_string_lossy().into_owned()
+            }
+        }
+
+        let category = sys::rs2_get_notification_category(notification, std::ptr::null_mut());
+        let severity = sys::rs2_get_notification_severity(notification, std::ptr::null_mut());
+        Self {
+            category: Rs2NotificationCategory::from_i32(category as i32),
+            severity: Rs2LogSeverity::from_i32(severity as i32),
+            timestamp: sys::rs2_get_notification_timestamp(notification, std::ptr::null_mut()),
+            description: string(sys::rs2_get_notification_description(
+                notification,
+                std::ptr::null_mut(),
+            )),
+            serialized_data: string(sys::rs2_get_notification_serialized_data(
+                notification,
+                std::ptr::null_mut(),
+            )),
+        }
+    }
+}
+
+/// The user callback invoked for every event of a pose sensor.
+///
+/// librealsense2 invokes callbacks from its own threads, so access is synchronized.
+type PoseEventCallback = Mutex<Box<dyn FnMut(PoseEvent) + Send>>;
+
+/// Forward a notification from librealsense2 to the [`PoseEventCallback`] at `user`.
+unsafe extern "C" fn on_notification(notification: *mut sys::rs2_notification, user: *mut c_void) {
+    let callback = &*(user as *const PoseEventCallback);
+
+    // Unwinding into librealsense2 is undefined behaviour, so a panicking callback is contained
+    // here. The panic poisons the lock, but the callback is still invoked for later events.
+    let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+        let event = PoseEvent::from_raw(notification);
+        let mut callback = callback.lock().unwrap_or_else(|e| e.into_inner());
+        callback(event);
+    }));
+}
+
+/// Drop a notification from librealsense2, for when no callback is registered.
+unsafe extern "C" fn ignore_notification(
+    _notification: *mut sys::rs2_notification,
+    _user: *mut c_void,
+) {
+}
+
+/// The pose sensor of a tracking device.
+///
+/// Constructed from a [`Sensor`] that extends to [`Rs2Extension::PoseSensor`]. All of the generic
+/// sensor APIs remain available through [`sensor`](PoseSensor::sensor) and
+/// [`sensor_mut`](PoseSensor::sensor_mut).
+pub struct PoseSensor {
+    /// The underlying sensor.
+    sensor: Sensor,
+    /// The callback registered with [`set_event_callback`](PoseSensor::set_event_callback), if
+    /// any.
+    ///
+    /// librealsense2 only holds a pointer to it, so it is kept here until it is unregistered.
+    event_callback: Option<Box<PoseEventCallback>>,
+}
+
+impl Drop for PoseSensor {
+    fn drop(&mut self) {
+        self.clear_event_callback();
+    }
+}
+
+impl TryFrom<Sensor> for PoseSensor {
+    type Error = Sensor;
+
+    /// Attempt to construct a pose sensor from a generic sensor.
+    ///
+    /// # Errors
+    ///
+    /// Returns the sensor back if it does not extend to [`Rs2Extension::PoseSensor`].
+    fn try_from(sensor: Sensor) -> Result<Self, Self::Error> {
+        if sensor.is_extendable_to(Rs2Extension::PoseSensor) {
+            Ok(Self {
+                sensor,
+                event_callback: None,
+            })
+        } else {
+            Err(sensor)
+        }
+    }
+}
+
+impl PoseSensor {
+    /// Get a reference to the underlying sensor.
+    pub fn sensor(&self) -> &Sensor {
+        &self.sensor
+    }
+
+    /// Get a mutable reference to the underlying sensor.
+    pub fn sensor_mut(&mut self) -> &mut Sensor {
+        &mut self.sensor
+    }
+
+    /// Consume the pose sensor, returning the underlying sensor.
+    ///
+    /// Any callback registered with [`set_event_callback`](PoseSensor::set_event_callback) is
+    /// unregistered.
+    pub fn into_inner(mut self) -> Sensor {
+        self.clear_event_callback();
+        unsafe {
+            // The callback is gone, so all that is left to do is to hand out the sensor without
+            // running `drop`.
+            let sensor = std::ptr::read(&self.sensor);
+            std::mem::forget(self);
+            sensor
+        }
+    }
+
+    /// Load the wheel odometry calibration for the sensor.
+    ///
+    /// `json` is the calibration describing the extrinsics and noise model of each wheel odometer,
+    /// in the format documented by librealsense2. This must be loaded before streaming is started,
+    /// and before any odometry is sent with
+    /// [`send_wheel_odometry`](PoseSensor::send_wheel_odometry).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WheelOdometryError::ConfigTooLarge`] if `json` does not fit in the size accepted
+    /// by librealsense2.
+    ///
+    /// Returns [`WheelOdometryError::CouldNotLoadConfig`] if the call fails.
+    ///
+    /// Returns [`WheelOdometryError::ConfigRejected`] if the sensor did not accept the calibration.
+    pub fn load_wheel_odometry_config(&self, json: &str) -> Result<(), WheelOdometryError> {
+        let blob_size = c_uint::try_from(json.len())
+            .map_err(|_| WheelOdometryError::ConfigTooLarge(json.len()))?;
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let loaded = sys::rs2_load_wheel_odometry_config(
+                self.sensor.sensor_ptr.as_ptr(),
+                json.as_ptr(),
+                blob_size,
+                &mut err,
+            );
+            check_rs2_error!(err, WheelOdometryError::CouldNotLoadConfig)?;
+
+            if loaded == 0 {
+                return Err(WheelOdometryError::ConfigRejected);
+            }
+            Ok(())
+        }
+    }
+
+    /// Send a wheel odometry measurement to be fused into the pose estimate.
+    ///
+    /// `sensor_id` is the index of the wheel odometer in the calibration loaded with
+    /// [`load_wheel_odometry_config`](PoseSensor::load_wheel_odometry_config), `frame_num` is a
+    /// monotonically increasing counter of the measurements, and `translational_velocity` is the
+    /// measured velocity `[x, y, z]` in meters per second, in the odometer's frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`WheelOdometryError::CouldNotSendOdometry`] if the call fails.
+    ///
+    /// Returns [`WheelOdometryError::OdometryRejected`] if the sensor did not accept the
+    /// measurement.
+    pub fn send_wheel_odometry(
+        &self,
+        sensor_id: u8,
+        frame_num: u32,
+        translational_velocity: [f32; 3],
+    ) -> Result<(), WheelOdometryError> {
+        let [x, y, z] = translational_velocity;
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let sent = sys::rs2_send_wheel_odometry(
+                self.sensor.sensor_ptr.as_ptr(),
+                sensor_id as c_char,
+                frame_num as c_uint,
+                sys::rs2_vector { x, y, z },
+                &mut err,
+            );
+            check_rs2_error!(err, WheelOdometryError::CouldNotSendOdometry)?;
+
+            if sent == 0 {
+                return Err(WheelOdometryError::OdometryRejected);
+            }
+            Ok(())
+        }
+    }
+
+    /// Export the localization map that the sensor has built while tracking.
+    ///
+    /// The map is an opaque, serialized blob that can be saved and later handed to
+    /// [`import_localization_map`](PoseSensor::import_localization_map), so that a new session
+    /// can relocalize against the places seen in this one. The sensor only finalizes the map once
+    /// it stops streaming, so export it after stopping the sensor or pipeline.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LocalizationMapError::CouldNotExportMap`] if the map cannot be exported.
+    ///
+    /// Returns [`LocalizationMapError::CouldNotReadMap`] if the exported map cannot be read.
+    pub fn export_localization_map(&self) -> Result<Vec<u8>, LocalizationMapError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let buffer =
+                sys::rs2_export_localization_map(self.sensor.sensor_ptr.as_ptr(), &mut err);
+            check_rs2_error!(err, LocalizationMapError::CouldNotExportMap)?;
+
+            let size = sys::rs2_get_raw_data_size(buffer, &mut err);
+            let map = check_rs2_error!(err, LocalizationMapError::CouldNotReadMap).and_then(|_| {
+                let data = sys::rs2_get_raw_data(buffer, &mut err);
+                check_rs2_error!(err, LocalizationMapError::CouldNotReadMap)?;
+                let size = usize::try_from(size).unwrap_or(0);
+                if data.is_null() || size == 0 {
+                    Ok(Vec::new())
+                } else {
+                    Ok(std::slice::from_raw_parts(data, size).to_vec())
+                }
+            });
+            sys::rs2_delete_raw_data(buffer);
+            map
+        }
+    }
+
+    /// Import a localization map exported with
+    /// [`export_localization_map`](PoseSensor::export_localization_map).
+    ///
+    /// The sensor relocalizes against the map once it recognizes a place in it, which is reported
+    /// through [`set_event_callback`](PoseSensor::set_event_callback). The map must be imported
+    /// before streaming is started.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LocalizationMapError::MapTooLarge`] if `map` does not fit in the size accepted by
+    /// librealsense2.
+    ///
+    /// Returns [`LocalizationMapError::CouldNotImportMap`] if the call fails.
+    ///
+    /// Returns [`LocalizationMapError::MapRejected`] if the sensor did not accept the map.
+    pub fn import_localization_map(&self, map: &[u8]) -> Result<(), LocalizationMapError> {
+        let blob_size = c_uint::try_from(map.len())
+            .map_err(|_| LocalizationMapError::MapTooLarge(map.len()))?;
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let imported = sys::rs2_import_localization_map(
+                self.sensor.sensor_ptr.as_ptr(),
+                map.as_ptr(),
+                blob_size,
+                &mut err,
+            );
+            check_rs2_error!(err, LocalizationMapError::CouldNotImportMap)?;
+
+            if imported == 0 {
+                return Err(LocalizationMapError::MapRejected);
+            }
+            Ok(())
+        }
+    }
+
+    /// Tag the location `pos` with orientation `orient` as a static node named `guid`.
+    ///
+    /// `pos` is `[x, y, z]` in meters and `orient` is a quaternion `[x, y, z, w]`, both relative to
+    /// the current tracking session, as in [`PoseFrame`](crate::frame::PoseFrame). Static nodes
+    /// are stored in the localization map, and follow it across relocalizations, so that content
+    /// anchored to them stays at the same physical location. A node with the same name is
+    /// replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StaticNodeError::InvalidGuid`] if `guid` is longer than 127 bytes, or contains a
+    /// nul byte.
+    ///
+    /// Returns [`StaticNodeError::CouldNotSetNode`] if the call fails.
+    ///
+    /// Returns [`StaticNodeError::NodeRejected`] if the sensor did not accept the node, e.g.
+    /// because it is not tracking.
+    pub fn set_static_node(
+        &self,
+        guid: &str,
+        pos: [f32; 3],
+        orient: [f32; 4],
+    ) -> Result<(), StaticNodeError> {
+        let guid = static_node_guid(guid)?;
+        let [x, y, z] = pos;
+        let [qx, qy, qz, qw] = orient;
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let set = sys::rs2_set_static_node(
+                self.sensor.sensor_ptr.as_ptr(),
+                guid.as_ptr(),
+                sys::rs2_vector { x, y, z },
+                sys::rs2_quaternion {
+                    x: qx,
+                    y: qy,
+                    z: qz,
+                    w: qw,
+                },
+                &mut err,
+            );
+            check_rs2_error!(err, StaticNodeError::CouldNotSetNode)?;
+
+            if set == 0 {
+                return Err(StaticNodeError::NodeRejected);
+            }
+            Ok(())
+        }
+    }
+
+    /// Get the position and orientation of the static node named `guid`.
+    ///
+    /// Returns the position `[x, y, z]` in meters and the orientation as a quaternion
+    /// `[x, y, z, w]`, in the coordinates of the current tracking session, or `None` if the sensor
+    /// does not know the node.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StaticNodeError::InvalidGuid`] if `guid` is longer than 127 bytes, or contains a
+    /// nul byte.
+    ///
+    /// Returns [`StaticNodeError::CouldNotGetNode`] if the call fails.
+    pub fn static_node(&self, guid: &str) -> Result<Option<([f32; 3], [f32; 4])>, StaticNodeError> {
+        let guid = static_node_guid(guid)?;
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let mut pos = sys::rs2_vector {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            };
+            let mut orient = sys::rs2_quaternion {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+                w: 0.0,
+            };
+            let found = sys::rs2_get_static_node(
+                self.sensor.sensor_ptr.as_ptr(),
+                guid.as_ptr(),
+                &mut pos,
+                &mut orient,
+                &mut err,
+            );
+            check_rs2_error!(err, StaticNodeError::CouldNotGetNode)?;
+
+            if found == 0 {
+                return Ok(None);
+            }
+            Ok(Some((
+                [pos.x, pos.y, pos.z],
+                [orient.x, orient.y, orient.z, orient.w],
+            )))
+        }
+    }
+
+    /// Remove the static node named `guid`.
+    ///
+    /// Returns whether the sensor removed the node, which is `false` if it did not know the node.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StaticNodeError::InvalidGuid`] if `guid` is longer than 127 bytes, or contains a
+    /// nul byte.
+    ///
+    /// Returns [`StaticNodeError::CouldNotRemoveNode`] if the call fails.
+    pub fn remove_static_node(&self, guid: &str) -> Result<bool, StaticNodeError> {
+        let guid = static_node_guid(guid)?;
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let removed = sys::rs2_remove_static_node(
+                self.sensor.sensor_ptr.as_ptr(),
+                guid.as_ptr(),
+                &mut err,
+            );
+            check_rs2_error!(err, StaticNodeError::CouldNotRemoveNode)?;
+            Ok(removed != 0)
+        }
+    }
+
+    /// Register a callback that is invoked for every event the sensor reports, such as
+    /// relocalizations.
+    ///
+    /// The callback runs on a thread owned by librealsense2, and replaces any callback registered
+    /// before. It stays registered until it is replaced or the pose sensor is dropped. Use
+    /// [`PoseEvent::is_relocalization`] to pick out relocalizations, after which the reported
+    /// poses jump.
+    ///
+    /// A panic in the callback is contained, and the callback keeps being invoked for later
+    /// events.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`PoseEventError::CouldNotSetEventCallback`] if the callback cannot be registered.
+    pub fn set_event_callback<F>(&mut self, callback: F) -> Result<(), PoseEventError>
+    where
+        F: FnMut(PoseEvent) + Send + 'static,
+    {
+        let callback: Box<PoseEventCallback> = Box::new(Mutex::new(Box::new(callback)));
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_set_notifications_callback(
+                self.sensor.sensor_ptr.as_ptr(),
+                Some(on_notification),
+                &*callback as *const PoseEventCallback as *mut c_void,
+                &mut err,
+            );
+            if let Err(e) = check_rs2_error!(err, PoseEventError::CouldNotSetEventCallback) {
+                // It is not known whether librealsense2 kept the pointer, so the callback is
+                // leaked rather than risking it being invoked after it is freed.
+                std::mem::forget(callback);
+                return Err(e);
+            }
+        }
+
+        // The previous callback has been replaced, so librealsense2 no longer invokes it.
+        self.event_callback = Some(callback);
+        Ok(())
+    }
+
+    /// Unregister the callback registered with
+    /// [`set_event_callback`](PoseSensor::set_event_callback), if any, and free it.
+    ///
+    /// If the callback cannot be unregistered, it is leaked instead.
+    fn clear_event_callback(&mut self) {
+        if let Some(callback) = self.event_callback.take() {
+            unsafe {
+                let mut err = std::ptr::null_mut::<sys::rs2_error>();
+                sys::rs2_set_notifications_callback(
+                    self.sensor.sensor_ptr.as_ptr(),
+                    Some(ignore_notification),
+                    std::ptr::null_mut(),
+                    &mut err,
+                );
+                if err.as_ref().is_some() {
+                    sys::rs2_free_error(err);
+                    std::mem::forget(callback);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn static_node_guids_are_validated() {
+        assert_eq!(
+            static_node_guid("charging dock").unwrap().as_bytes(),
+            b"charging dock"
+        );
+        assert!(static_node_guid(&"x".repeat(MAX_STATIC_NODE_GUID_LEN)).is_ok());
+        assert!(matches!(
+            static_node_guid(&"x".repeat(MAX_STATIC_NODE_GUID_LEN + 1)),
+            Err(StaticNodeError::InvalidGuid(_))
+        ));
+        assert!(matches!(
+            static_node_guid("dock\0"),
+            Err(StaticNodeError::InvalidGuid(_))
+        ));
+    }
+}
@@ -0,0 +1,269 @@
+//! Type for streaming directly from a sensor, without a pipeline.
+//!
+//! A [`Pipeline`](crate::pipeline::InactivePipeline) opens and starts the sensors of a device on
+//! our behalf, and closes them again when it is stopped. Streaming from a sensor directly skips
+//! the pipeline's synchronization, but leaves opening, starting, stopping and closing the sensor
+//! to the caller. librealsense2 does not tolerate a sensor being released while it is still open,
+//! so [`SensorStream`] ties these steps to its own lifetime: the sensor is stopped and closed when
+//! the stream is dropped, before the sensor itself is released.
+
+use super::Sensor;
+use crate::{check_rs2_error, frame::AnyFrame, kind::Rs2Exception, stream_profile::StreamProfile};
+use realsense_sys as sys;
+use std::{convert::TryFrom, ptr::NonNull, task::Poll, time::Duration};
+use thiserror::Error;
+
+/// Enumeration of possible errors that can occur when streaming directly from a sensor.
+#[derive(Error, Debug)]
+pub enum SensorStreamError {
+    /// No stream profiles were given to open the sensor with.
+    #[error("Cannot open a sensor without any stream profiles.")]
+    NoStreamProfiles,
+    /// The frame queue size is zero, so the queue could not hold any frames.
+    #[error("Invalid frame queue size: {0}. The size must be at least 1.")]
+    InvalidQueueSize(usize),
+    /// Could not open the sensor with the given stream profiles.
+    #[error("Could not open sensor. Type: {0}; Reason: {1}")]
+    CouldNotOpenSensor(Rs2Exception, String),
+    /// Could not create the frame queue that receives frames from the sensor.
+    #[error("Could not create frame queue. Type: {0}; Reason: {1}")]
+    CouldNotCreateFrameQueue(Rs2Exception, String),
+    /// Could not start streaming from the sensor.
+    #[error("Could not start sensor. Type: {0}; Reason: {1}")]
+    CouldNotStartSensor(Rs2Exception, String),
+    /// Could not stop streaming from the sensor.
+    #[error("Could not stop sensor. Type: {0}; Reason: {1}")]
+    CouldNotStopSensor(Rs2Exception, String),
+    /// Could not close the sensor.
+    #[error("Could not close sensor. Type: {0}; Reason: {1}")]
+    CouldNotCloseSensor(Rs2Exception, String),
+    /// An internal error occurred while waiting for or polling for a frame.
+    #[error("An internal error occurred while waiting for a frame. Type: {0}; Reason: {1}")]
+    DidErrorDuringFrameWait(Rs2Exception, String),
+    /// The stream timed out while waiting for a frame.
+    #[error("Timed out while waiting for frame.")]
+    DidTimeoutBeforeFrameArrival,
+    /// The sensor produced a frame that does not correspond to any frame type in this crate.
+    #[error("Received a frame that does not correspond to any frame type.")]
+    UnsupportedFrame,
+}
+
+/// A sensor that has been opened and started, and delivers frames until it is dropped.
+///
+/// Constructed with [`Sensor::open`]. The stream owns the sensor, and stops and closes it when
+/// dropped, in that order, before releasing it. Use [`close`](SensorStream::close) instead of
+/// dropping the stream to get the sensor back, or to find out whether closing it failed.
+///
+/// ```no_run
+/// use realsense_rust::context::Context;
+/// use std::{collections::HashSet, time::Duration};
+///
+/// let context = Context::new().unwrap();
+/// let device = context.query_devices(HashSet::new()).pop().unwrap();
+/// let sensor = device.sensors().pop().unwrap();
+/// let profiles = sensor.stream_profiles();
+/// let profile = profiles.iter().find(|p| p.is_default()).unwrap();
+///
+/// let mut stream = sensor.open(&[profile], 1).unwrap();
+/// let frame = stream.wait(Duration::from_secs(1)).unwrap();
+/// // The sensor is stopped and closed here.
+/// drop(stream);
+/// ```
+pub struct SensorStream {
+    /// The sensor that is streaming.
+    sensor: Sensor,
+    /// The frame queue that the sensor deposits its frames into.
+    queue_ptr: NonNull<sys::rs2_frame_queue>,
+}
+
+impl Drop for SensorStream {
+    fn drop(&mut self) {
+        unsafe {
+            // Errors are ignored, since the sensor is released regardless.
+            let _ = self.shutdown();
+        }
+    }
+}
+
+unsafe impl Send for SensorStream {}
+
+impl SensorStream {
+    /// Open `sensor` with `profiles`, and start streaming into a frame queue of `queue_size`.
+    ///
+    /// # Errors
+    ///
+    /// See [`Sensor::open`].
+    pub(crate) fn open(
+        sensor: Sensor,
+        profiles: &[&StreamProfile],
+        queue_size: usize,
+    ) -> Result<Self, SensorStreamError> {
+        if profiles.is_empty() {
+            return Err(SensorStreamError::NoStreamProfiles);
+        }
+        // librealsense2 accepts a queue that cannot hold anything, from which no frame would ever
+        // arrive.
+        if queue_size == 0 {
+            return Err(SensorStreamError::InvalidQueueSize(queue_size));
+        }
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let sensor_ptr = sensor.sensor_ptr.as_ptr();
+
+            let mut profile_ptrs: Vec<*const sys::rs2_stream_profile> = profiles
+                .iter()
+                .map(|profile| profile.get_raw().as_ptr() as *const _)
+                .collect();
+            sys::rs2_open_multiple(
+                sensor_ptr,
+                profile_ptrs.as_mut_ptr(),
+                profile_ptrs.len() as i32,
+                &mut err,
+            );
+            check_rs2_error!(err, SensorStreamError::CouldNotOpenSensor)?;
+
+            let queue_ptr = sys::rs2_create_frame_queue(
+                i32::try_from(queue_size).unwrap_or(i32::MAX),
+                &mut err,
+            );
+            if let Err(e) = check_rs2_error!(err, SensorStreamError::CouldNotCreateFrameQueue) {
+                sys::rs2_close(sensor_ptr, std::ptr::null_mut());
+                return Err(e);
+            }
+
+            // From here on, dropping the stream stops and closes the sensor.
+            let stream = Self {
+                sensor,
+                queue_ptr: NonNull::new(queue_ptr).unwrap(),
+            };
+
+            sys::rs2_start_queue(sensor_ptr, stream.queue_ptr.as_ptr(), &mut err);
+            check_rs2_error!(err, SensorStreamError::CouldNotStartSensor)?;
+
+            Ok(stream)
+        }
+    }
+
+    /// Get the sensor that is streaming.
+    pub fn sensor(&self) -> &Sensor {
+        &self.sensor
+    }
+
+    /// Waits for the next frame from the sensor, blocking the calling thread.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SensorStreamError::DidErrorDuringFrameWait`] if an internal error occurs while
+    /// waiting for the frame.
+    ///
+    /// Returns [`SensorStreamError::DidTimeoutBeforeFrameArrival`] if no frame arrives within
+    /// `timeout`.
+    ///
+    /// Returns [`SensorStreamError::UnsupportedFrame`] if the frame does not correspond to any
+    /// frame type in this crate. The frame is released in that case.
+    pub fn wait(&mut self, timeout: Duration) -> Result<AnyFrame, SensorStreamError> {
+        let timeout_ms = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX);
+
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let mut frame_ptr = std::ptr::null_mut::<sys::rs2_frame>();
+            let did_get_frame = sys::rs2_try_wait_for_frame(
+                self.queue_ptr.as_ptr(),
+                timeout_ms,
+                &mut frame_ptr,
+                &mut err,
+            );
+            check_rs2_error!(err, SensorStreamError::DidErrorDuringFrameWait)?;
+
+            if did_get_frame != 0 {
+                Self::frame_from_raw(NonNull::new(frame_ptr).unwrap())
+            } else {
+                Err(SensorStreamError::DidTimeoutBeforeFrameArrival)
+            }
+        }
+    }
+
+    /// Poll if the next frame from the sensor is immediately available.
+    ///
+    /// Returns [`Poll::Pending`] if no frame is available yet, and [`Poll::Ready`] with the frame
+    /// otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SensorStreamError::DidErrorDuringFrameWait`] if an internal error occurs while
+    /// polling for the frame.
+    ///
+    /// Returns [`SensorStreamError::UnsupportedFrame`] if the frame does not correspond to any
+    /// frame type in this crate. The frame is released in that case.
+    pub fn poll(&mut self) -> Result<Poll<AnyFrame>, SensorStreamError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let mut frame_ptr = std::ptr::null_mut::<sys::rs2_frame>();
+            let did_get_frame =
+                sys::rs2_poll_for_frame(self.queue_ptr.as_ptr(), &mut frame_ptr, &mut err);
+            check_rs2_error!(err, SensorStreamError::DidErrorDuringFrameWait)?;
+
+            if did_get_frame != 0 {
+                Self::frame_from_raw(NonNull::new(frame_ptr).unwrap()).map(Poll::Ready)
+            } else {
+                Ok(Poll::Pending)
+            }
+        }
+    }
+
+    /// Stop and close the sensor, and return it.
+    ///
+    /// The sensor is closed even if it could not be stopped.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SensorStreamError::CouldNotStopSensor`] if the sensor cannot be stopped, and
+    /// [`SensorStreamError::CouldNotCloseSensor`] if it cannot be closed. The sensor is released
+    /// in that case.
+    pub fn close(mut self) -> Result<Sensor, SensorStreamError> {
+        unsafe {
+            let result = self.shutdown();
+            // The queue has been deleted, so all that is left to do is to hand out the sensor
+            // without running `drop`.
+            let sensor = std::ptr::read(&self.sensor);
+            std::mem::forget(self);
+            result.map(|_| sensor)
+        }
+    }
+
+    /// Stop and close the sensor, and delete the frame queue.
+    ///
+    /// # Safety
+    ///
+    /// Must be called at most once, since the frame queue is deleted.
+    unsafe fn shutdown(&mut self) -> Result<(), SensorStreamError> {
+        let sensor_ptr = self.sensor.sensor_ptr.as_ptr();
+
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        sys::rs2_stop(sensor_ptr, &mut err);
+        let stopped = check_rs2_error!(err, SensorStreamError::CouldNotStopSensor);
+
+        let mut err = std::ptr::null_mut::<sys::rs2_error>();
+        sys::rs2_close(sensor_ptr, &mut err);
+        let closed = check_rs2_error!(err, SensorStreamError::CouldNotCloseSensor);
+
+        // The sensor no longer deposits frames, so the queue can go.
+        sys::rs2_delete_frame_queue(self.queue_ptr.as_ptr());
+        stopped.and(closed)
+    }
+
+    /// Construct a frame from a frame taken off the queue, releasing it if it is not supported.
+    ///
+    /// # Safety
+    ///
+    /// `frame_ptr` must point to a valid frame that the caller owns.
+    unsafe fn frame_from_raw(
+        frame_ptr: NonNull<sys::rs2_frame>,
+    ) -> Result<AnyFrame, SensorStreamError> {
+        AnyFrame::from_raw(frame_ptr).ok_or_else(|| {
+            sys::rs2_release_frame(frame_ptr.as_ptr());
+            SensorStreamError::UnsupportedFrame
+        })
+    }
+}
@@ -0,0 +1,375 @@
+//! A type for constructing frames from in-memory pixel buffers.
+//!
+//! librealsense2 only creates frames internally, usually from data streamed by a physical device.
+//! A software device lets us inject our own data instead, which makes it possible to feed known
+//! images through e.g. [`processing_blocks`](crate::processing_blocks) without a camera attached.
+//!
+//! ```no_run
+//! use realsense_rust::{
+//!     base::Rs2Intrinsics,
+//!     frame::DepthFrame,
+//!     kind::{Rs2Format, Rs2StreamKind},
+//!     software_device::{SoftwareDevice, SoftwareVideoStream},
+//! };
+//! use realsense_sys as sys;
+//!
+//! let stream = SoftwareVideoStream {
+//!     kind: Rs2StreamKind::Depth,
+//!     index: 0,
+//!     uid: 0,
+//!     width: 4,
+//!     height: 2,
+//!     framerate: 30,
+//!     bytes_per_pixel: 2,
+//!     format: Rs2Format::Z16,
+//!     intrinsics: Rs2Intrinsics(sys::rs2_intrinsics {
+//!         width: 4,
+//!         height: 2,
+//!         ppx: 2.0,
+//!         ppy: 1.0,
+//!         fx: 1.0,
+//!         fy: 1.0,
+//!         model: sys::rs2_distortion_RS2_DISTORTION_NONE,
+//!         coeffs: [0.0; 5],
+//!     }),
+//!     depth_units: 0.001,
+//! };
+//!
+//! let mut device = SoftwareDevice::new(stream).unwrap();
+//! let frame: DepthFrame = device.frame(&[0u8; 16], 0.0).unwrap();
+//! ```
+
+use crate::{
+    base::Rs2Intrinsics,
+    check_rs2_error,
+    kind::{Rs2Exception, Rs2Format, Rs2StreamKind, Rs2TimestampDomain},
+};
+use anyhow::Result;
+use realsense_sys as sys;
+use std::{
+    alloc::{self, Layout},
+    convert::{TryFrom, TryInto},
+    ffi::CString,
+    os::raw::c_void,
+    ptr::NonNull,
+};
+use thiserror::Error;
+
+/// The size of the header used to store the length of pixel buffers handed to librealsense2.
+const BUFFER_HEADER_SIZE: usize = std::mem::size_of::<usize>();
+
+/// The time (in milliseconds) to wait for an injected frame to be returned from the sensor.
+const FRAME_TIMEOUT_MILLIS: u32 = 5000;
+
+/// Enumeration of possible errors that can occur when using a [`SoftwareDevice`].
+#[derive(Error, Debug)]
+pub enum SoftwareDeviceError {
+    /// Could not create the software device.
+    #[error("Could not create software device. Type: {0}; Reason: {1}")]
+    CouldNotCreateDevice(Rs2Exception, String),
+    /// Could not add a sensor to the software device.
+    #[error("Could not add sensor to software device. Type: {0}; Reason: {1}")]
+    CouldNotAddSensor(Rs2Exception, String),
+    /// Could not add the video stream to the software sensor.
+    #[error("Could not add video stream to software sensor. Type: {0}; Reason: {1}")]
+    CouldNotAddVideoStream(Rs2Exception, String),
+    /// Could not open the software sensor with the video stream.
+    #[error("Could not open software sensor. Type: {0}; Reason: {1}")]
+    CouldNotOpenSensor(Rs2Exception, String),
+    /// Could not create the frame queue that receives injected frames.
+    #[error("Could not create frame queue. Type: {0}; Reason: {1}")]
+    CouldNotCreateFrameQueue(Rs2Exception, String),
+    /// Could not start the software sensor.
+    #[error("Could not start software sensor. Type: {0}; Reason: {1}")]
+    CouldNotStartSensor(Rs2Exception, String),
+    /// Could not inject the frame into the software sensor.
+    #[error("Could not inject video frame. Type: {0}; Reason: {1}")]
+    CouldNotInjectFrame(Rs2Exception, String),
+    /// The injected frame was not returned by the software sensor.
+    #[error("Could not receive injected frame. Type: {0}; Reason: {1}")]
+    CouldNotReceiveFrame(Rs2Exception, String),
+    /// The injected frame did not arrive in the frame queue before the timeout.
+    #[error("Timed out while waiting for the injected frame.")]
+    DidTimeoutBeforeFrameArrival,
+    /// The provided pixel buffer is smaller than the stream requires.
+    #[error("Pixel buffer is too small. Expected {expected} bytes, got {actual} bytes.")]
+    PixelBufferTooSmall {
+        /// The number of bytes required by the stream's dimensions.
+        expected: usize,
+        /// The number of bytes in the provided buffer.
+        actual: usize,
+    },
+}
+
+/// Describes the video stream that a [`SoftwareDevice`] produces frames for.
+#[derive(Debug)]
+pub struct SoftwareVideoStream {
+    /// The kind of stream, e.g. [`Rs2StreamKind::Depth`].
+    pub kind: Rs2StreamKind,
+    /// The index of the stream.
+    pub index: i32,
+    /// The unique identifier of the stream.
+    pub uid: i32,
+    /// The width of each frame, in pixels.
+    pub width: usize,
+    /// The height of each frame, in pixels.
+    pub height: usize,
+    /// The framerate of the stream, in frames per second.
+    pub framerate: usize,
+    /// The number of bytes used to represent each pixel.
+    pub bytes_per_pixel: usize,
+    /// The pixel format of the stream.
+    pub format: Rs2Format,
+    /// The intrinsics of the stream.
+    pub intrinsics: Rs2Intrinsics,
+    /// The depth units attached to each frame, in meters. Only used for depth streams.
+    pub depth_units: f32,
+}
+
+/// A device that produces frames from in-memory pixel buffers instead of a camera.
+///
+/// Each software device holds a single sensor streaming a single [`SoftwareVideoStream`]. Frames
+/// injected with [`frame`](SoftwareDevice::frame) are returned as regular frame types, and can be
+/// used anywhere a frame from a physical device can.
+#[derive(Debug)]
+pub struct SoftwareDevice {
+    /// A non-null pointer to the underlying software device.
+    device_ptr: NonNull<sys::rs2_device>,
+    /// A non-null pointer to the software sensor of the device.
+    sensor_ptr: NonNull<sys::rs2_sensor>,
+    /// A non-null pointer to the stream profile of the video stream. This is owned by the sensor.
+    profile_ptr: NonNull<sys::rs2_stream_profile>,
+    /// The frame queue that the sensor deposits injected frames into.
+    queue_ptr: NonNull<sys::rs2_frame_queue>,
+    /// The stream that frames are produced for.
+    stream: SoftwareVideoStream,
+    /// The frame number of the next injected frame.
+    frame_number: i32,
+}
+
+impl Drop for SoftwareDevice {
+    fn drop(&mut self) {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_stop(self.sensor_ptr.as_ptr(), &mut err);
+            if err.as_ref().is_some() {
+                sys::rs2_free_error(err);
+                err = std::ptr::null_mut();
+            }
+            sys::rs2_close(self.sensor_ptr.as_ptr(), &mut err);
+            if err.as_ref().is_some() {
+                sys::rs2_free_error(err);
+            }
+            sys::rs2_delete_frame_queue(self.queue_ptr.as_ptr());
+            sys::rs2_delete_sensor(self.sensor_ptr.as_ptr());
+            sys::rs2_delete_device(self.device_ptr.as_ptr());
+        }
+    }
+}
+
+unsafe impl Send for SoftwareDevice {}
+
+/// Frees a pixel buffer allocated by [`SoftwareDevice::frame`] once librealsense2 is done with it.
+///
+/// # Safety
+///
+/// `pixels` must point just past the length header of a buffer allocated in
+/// [`SoftwareDevice::frame`].
+unsafe extern "C" fn delete_pixels(pixels: *mut c_void) {
+    let buffer = pixels.cast::<u8>().sub(BUFFER_HEADER_SIZE);
+    let len = buffer.cast::<usize>().read();
+    alloc::dealloc(buffer, buffer_layout(len));
+}
+
+/// The layout of a pixel buffer of `len` bytes, including its length header.
+fn buffer_layout(len: usize) -> Layout {
+    Layout::from_size_align(BUFFER_HEADER_SIZE + len, std::mem::align_of::<usize>()).unwrap()
+}
+
+impl SoftwareDevice {
+    /// Create a new software device streaming the provided video stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`SoftwareDeviceError`] describing the step that failed if the device, sensor or
+    /// stream cannot be set up.
+    ///
+    pub fn new(stream: SoftwareVideoStream) -> Result<Self, SoftwareDeviceError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let device_ptr = sys::rs2_create_software_device(&mut err);
+            check_rs2_error!(err, SoftwareDeviceError::CouldNotCreateDevice)?;
+            let device_ptr = NonNull::new(device_ptr).unwrap();
+
+            // Construct the device first so that it is cleaned up if anything below fails.
+            let sensor_name = CString::new("Software Sensor").unwrap();
+            let sensor_ptr = sys::rs2_software_device_add_sensor(
+                device_ptr.as_ptr(),
+                sensor_name.as_ptr(),
+                &mut err,
+            );
+            if let Err(e) = check_rs2_error!(err, SoftwareDeviceError::CouldNotAddSensor) {
+                sys::rs2_delete_device(device_ptr.as_ptr());
+                return Err(e);
+            }
+            let sensor_ptr = NonNull::new(sensor_ptr).unwrap();
+
+            let cleanup = |sensor_ptr: NonNull<sys::rs2_sensor>| {
+                sys::rs2_delete_sensor(sensor_ptr.as_ptr());
+                sys::rs2_delete_device(device_ptr.as_ptr());
+            };
+
+            let video_stream = sys::rs2_video_stream {
+                #[allow(clippy::useless_conversion)]
+                type_: (stream.kind as i32).try_into().unwrap(),
+                index: stream.index,
+                uid: stream.uid,
+                width: stream.width as i32,
+                height: stream.height as i32,
+                fps: stream.framerate as i32,
+                bpp: stream.bytes_per_pixel as i32,
+                #[allow(clippy::useless_conversion)]
+                fmt: (stream.format as i32).try_into().unwrap(),
+                intrinsics: stream.intrinsics.0,
+            };
+            let profile_ptr = sys::rs2_software_sensor_add_video_stream_ex(
+                sensor_ptr.as_ptr(),
+                video_stream,
+                1,
+                &mut err,
+            );
+            if let Err(e) = check_rs2_error!(err, SoftwareDeviceError::CouldNotAddVideoStream) {
+                cleanup(sensor_ptr);
+                return Err(e);
+            }
+            let profile_ptr = NonNull::new(profile_ptr).unwrap();
+
+            sys::rs2_open(sensor_ptr.as_ptr(), profile_ptr.as_ptr(), &mut err);
+            if let Err(e) = check_rs2_error!(err, SoftwareDeviceError::CouldNotOpenSensor) {
+                cleanup(sensor_ptr);
+                return Err(e);
+            }
+
+            let queue_ptr = sys::rs2_create_frame_queue(1, &mut err);
+            if let Err(e) = check_rs2_error!(err, SoftwareDeviceError::CouldNotCreateFrameQueue) {
+                sys::rs2_close(sensor_ptr.as_ptr(), std::ptr::null_mut());
+                cleanup(sensor_ptr);
+                return Err(e);
+            }
+            let queue_ptr = NonNull::new(queue_ptr).unwrap();
+
+            sys::rs2_start_queue(sensor_ptr.as_ptr(), queue_ptr.as_ptr(), &mut err);
+            if let Err(e) = check_rs2_error!(err, SoftwareDeviceError::CouldNotStartSensor) {
+                sys::rs2_delete_frame_queue(queue_ptr.as_ptr());
+                sys::rs2_close(sensor_ptr.as_ptr(), std::ptr::null_mut());
+                cleanup(sensor_ptr);
+                return Err(e);
+            }
+
+            Ok(Self {
+                device_ptr,
+                sensor_ptr,
+                profile_ptr,
+                queue_ptr,
+                stream,
+                frame_number: 0,
+            })
+        }
+    }
+
+    /// Get the stream that this device produces frames for.
+    pub fn stream(&self) -> &SoftwareVideoStream {
+        &self.stream
+    }
+
+    /// Construct a frame from the provided pixel buffer.
+    ///
+    /// Pixels are copied row by row with no padding, so `pixels` must hold at least
+    /// `width * height * bytes_per_pixel` bytes. The timestamp is in milliseconds and is reported
+    /// in the [`Rs2TimestampDomain::SystemTime`] domain.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SoftwareDeviceError::PixelBufferTooSmall`] if `pixels` is too small for the
+    /// stream.
+    ///
+    /// Returns [`SoftwareDeviceError::CouldNotInjectFrame`] or
+    /// [`SoftwareDeviceError::CouldNotReceiveFrame`] if librealsense2 fails to produce the frame,
+    /// and [`SoftwareDeviceError::DidTimeoutBeforeFrameArrival`] if the frame does not arrive.
+    ///
+    /// Returns any error that `F::try_from` returns, e.g. if `F` does not match the kind of the
+    /// stream.
+    ///
+    pub fn frame<F>(&mut self, pixels: &[u8], timestamp: f64) -> Result<F>
+    where
+        F: TryFrom<NonNull<sys::rs2_frame>, Error = anyhow::Error>,
+    {
+        let stride = self.stream.width * self.stream.bytes_per_pixel;
+        let expected = stride * self.stream.height;
+        if pixels.len() < expected {
+            return Err(SoftwareDeviceError::PixelBufferTooSmall {
+                expected,
+                actual: pixels.len(),
+            }
+            .into());
+        }
+
+        unsafe {
+            // librealsense2 takes ownership of the pixels and frees them through `delete_pixels`,
+            // which only receives the data pointer. The length is stored just before the data so
+            // that the buffer can be deallocated with the layout it was allocated with.
+            let buffer = alloc::alloc(buffer_layout(expected));
+            if buffer.is_null() {
+                alloc::handle_alloc_error(buffer_layout(expected));
+            }
+            buffer.cast::<usize>().write(expected);
+            let data = buffer.add(BUFFER_HEADER_SIZE);
+            std::ptr::copy_nonoverlapping(pixels.as_ptr(), data, expected);
+
+            let software_frame = sys::rs2_software_video_frame {
+                pixels: data.cast::<c_void>(),
+                deleter: Some(delete_pixels),
+                stride: stride as i32,
+                bpp: self.stream.bytes_per_pixel as i32,
+                timestamp,
+                #[allow(clippy::useless_conversion)]
+                domain: (Rs2TimestampDomain::SystemTime as i32).try_into().unwrap(),
+                frame_number: self.frame_number,
+                profile: self.profile_ptr.as_ptr(),
+                depth_units: self.stream.depth_units,
+            };
+            self.frame_number = self.frame_number.wrapping_add(1);
+
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            sys::rs2_software_sensor_on_video_frame(
+                self.sensor_ptr.as_ptr(),
+                software_frame,
+                &mut err,
+            );
+            if let Err(e) = check_rs2_error!(err, SoftwareDeviceError::CouldNotInjectFrame) {
+                // librealsense2 only reports errors before it takes ownership of the pixels, so
+                // they are still ours to free.
+                delete_pixels(data.cast::<c_void>());
+                return Err(e.into());
+            }
+
+            let mut frame_ptr = std::ptr::null_mut::<sys::rs2_frame>();
+            let did_get_frame = sys::rs2_try_wait_for_frame(
+                self.queue_ptr.as_ptr(),
+                FRAME_TIMEOUT_MILLIS,
+                &mut frame_ptr,
+                &mut err,
+            );
+            check_rs2_error!(err, SoftwareDeviceError::CouldNotReceiveFrame)?;
+            if did_get_frame == 0 {
+                return Err(SoftwareDeviceError::DidTimeoutBeforeFrameArrival.into());
+            }
+            let frame_ptr = NonNull::new(frame_ptr).unwrap();
+
+            F::try_from(frame_ptr).map_err(|e| {
+                sys::rs2_release_frame(frame_ptr.as_ptr());
+                e
+            })
+        }
+    }
+}
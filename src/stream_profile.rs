@@ -44,7 +44,11 @@ use crate::{
 use anyhow::Result;
 use num_traits::FromPrimitive;
 use realsense_sys as sys;
-use std::{convert::TryFrom, mem::MaybeUninit, ptr::NonNull};
+use std::{
+    convert::{TryFrom, TryInto},
+    mem::MaybeUninit,
+    ptr::NonNull,
+};
 use thiserror::Error;
 
 /// Type describing errors that can occur when trying to construct a stream profile.
@@ -95,6 +99,9 @@ pub enum DataError {
     /// Could not get motion intrinsics from the requested stream.
     #[error("Could not get motion intrinsics. Type: {0}; Reason: {1}")]
     CouldNotGetMotionIntrinsics(Rs2Exception, String),
+    /// Could not get the resolution of the requested stream.
+    #[error("Could not get video stream resolution. Type: {0}; Reason: {1}")]
+    CouldNotGetResolution(Rs2Exception, String),
 }
 
 /// Type for holding the stream profile information.
@@ -210,6 +217,8 @@ impl Drop for StreamProfile {
     }
 }
 
+unsafe impl Send for StreamProfile {}
+
 impl StreamProfile {
     /// Attempt to construct a stream profile from a profile list and index.
     ///
@@ -269,6 +278,41 @@ impl StreamProfile {
         }
     }
 
+    /// Attempt to create an owned copy of the stream profile.
+    ///
+    /// Unlike stream profiles obtained from a frame, the copy is owned by the Rust API, and so
+    /// remains valid regardless of the lifetime of the frame it was copied from.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`StreamConstructionError::CouldNotCloneProfile`] if the stream profile cannot be
+    /// cloned.
+    ///
+    /// Returns [`StreamConstructionError::CouldNotRetrieveStreamData`] or
+    /// [`StreamConstructionError::CouldNotDetermineIsDefault`] if the cloned stream profile is
+    /// invalid.
+    pub fn try_clone(&self) -> Result<Self, StreamConstructionError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let profile_ptr = sys::rs2_clone_stream_profile(
+                self.ptr.as_ptr(),
+                #[allow(clippy::useless_conversion)]
+                (self.stream as i32).try_into().unwrap(),
+                self.index as i32,
+                #[allow(clippy::useless_conversion)]
+                (self.format as i32).try_into().unwrap(),
+                &mut err,
+            );
+            check_rs2_error!(err, StreamConstructionError::CouldNotCloneProfile)?;
+
+            let nonnull_profile_ptr = NonNull::new(profile_ptr).unwrap();
+            let mut stream_profile = Self::try_from(nonnull_profile_ptr)?;
+            stream_profile.should_drop = true;
+
+            Ok(stream_profile)
+        }
+    }
+
     /// Predicate for whether or not the stream is a default stream.
     #[inline]
     pub fn is_default(&self) -> bool {
@@ -313,6 +357,51 @@ impl StreamProfile {
         self.framerate
     }
 
+    /// Predicate for whether the stream is a motion (IMU) stream.
+    ///
+    /// Motion streams are either [`Accel`](Rs2StreamKind::Accel) or [`Gyro`](Rs2StreamKind::Gyro)
+    /// streams; use [`kind`](StreamProfile::kind) to tell them apart.
+    #[inline]
+    pub fn is_motion(&self) -> bool {
+        matches!(self.stream, Rs2StreamKind::Accel | Rs2StreamKind::Gyro)
+    }
+
+    /// Gets the sample rate of a motion stream, in Hz.
+    ///
+    /// Returns `None` if the stream is not a [motion stream](StreamProfile::is_motion).
+    #[inline]
+    pub fn motion_framerate(&self) -> Option<i32> {
+        if self.is_motion() {
+            Some(self.framerate)
+        } else {
+            None
+        }
+    }
+
+    /// Get the resolution of a video stream as `(width, height)` in pixels.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DataError::CouldNotGetResolution`] if the stream is not a video stream or the
+    /// stream pointer is no longer valid.
+    pub fn resolution(&self) -> Result<(usize, usize), DataError> {
+        unsafe {
+            let mut err = std::ptr::null_mut::<sys::rs2_error>();
+            let mut width = 0;
+            let mut height = 0;
+
+            sys::rs2_get_video_stream_resolution(
+                self.ptr.as_ptr(),
+                &mut width,
+                &mut height,
+                &mut err,
+            );
+            check_rs2_error!(err, DataError::CouldNotGetResolution)?;
+
+            Ok((width as usize, height as usize))
+        }
+    }
+
     /// Get extrinsics between the origin stream (`self`) and target stream (`to_profile`).
     ///
     /// Returns the extrinsics between the origin and target streams from the underlying realsense
@@ -433,4 +522,15 @@ impl StreamProfile {
             Ok(Rs2MotionDeviceIntrinsics(intrinsics.assume_init()))
         }
     }
+
+    /// Get the underlying low-level pointer to the stream profile.
+    ///
+    /// # Safety
+    ///
+    /// This method is not intended to be called or used outside of the crate itself. Be warned, it
+    /// is _undefined behaviour_ to delete or try to drop this pointer in any context. If you do,
+    /// you risk a double-free or use-after-free error.
+    pub(crate) unsafe fn get_raw(&self) -> NonNull<sys::rs2_stream_profile> {
+        self.ptr
+    }
 }
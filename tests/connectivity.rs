@@ -46,8 +46,8 @@ fn cannot_resolve_bad_config() {
         .enable_stream(
             Rs2StreamKind::Depth,
             Some(0),
-            0,
-            0,
+            None,
+            None,
             // Depth should not be able to provide motion data!
             Rs2Format::MotionXyz32F,
             100,
@@ -6,9 +6,15 @@ use realsense_rust::{
     base::Rs2Roi,
     config::Config,
     context::Context,
-    frame::{ColorFrame, DepthFrame, FrameEx, InfraredFrame},
-    kind::{Rs2CameraInfo, Rs2Extension, Rs2Format, Rs2Option, Rs2ProductLine, Rs2StreamKind},
+    frame::{
+        AnyFrame, ColorFrame, DepthFrame, FrameEx, InfraredFrame, PoseFrame, RollingFrameBuffer,
+    },
+    kind::{
+        Rs2CameraInfo, Rs2Extension, Rs2Format, Rs2Option, Rs2ProductLine, Rs2Rs400VisualPreset,
+        Rs2StreamKind,
+    },
     pipeline::InactivePipeline,
+    processing_blocks::align::Align,
 };
 use std::{
     collections::{HashMap, HashSet},
@@ -37,17 +43,45 @@ fn d400_can_resolve_color_and_depth_and_infrared() {
                 .unwrap()
                 .disable_all_streams()
                 .unwrap()
-                .enable_stream(Rs2StreamKind::Color, Some(0), 0, 0, Rs2Format::Rgba8, 30)
+                .enable_stream(
+                    Rs2StreamKind::Color,
+                    Some(0),
+                    None,
+                    None,
+                    Rs2Format::Rgba8,
+                    30,
+                )
                 .unwrap()
-                .enable_stream(Rs2StreamKind::Depth, Some(0), 0, 0, Rs2Format::Z16, 30)
+                .enable_stream(
+                    Rs2StreamKind::Depth,
+                    Some(0),
+                    None,
+                    None,
+                    Rs2Format::Z16,
+                    30,
+                )
                 .unwrap()
                 // RealSense doesn't seem to like index zero for the IR cameras
                 //
                 // Really not sure why? This seems like an implementation issue, but in practice most
                 // won't be after the IR image directly (I think?).
-                .enable_stream(Rs2StreamKind::Infrared, Some(1), 0, 0, Rs2Format::Y8, 30)
+                .enable_stream(
+                    Rs2StreamKind::Infrared,
+                    Some(1),
+                    None,
+                    None,
+                    Rs2Format::Y8,
+                    30,
+                )
                 .unwrap()
-                .enable_stream(Rs2StreamKind::Infrared, Some(2), 0, 0, Rs2Format::Any, 30)
+                .enable_stream(
+                    Rs2StreamKind::Infrared,
+                    Some(2),
+                    None,
+                    None,
+                    Rs2Format::Any,
+                    30,
+                )
                 .unwrap();
         } else {
             config
@@ -55,11 +89,32 @@ fn d400_can_resolve_color_and_depth_and_infrared() {
                 .unwrap()
                 .disable_all_streams()
                 .unwrap()
-                .enable_stream(Rs2StreamKind::Color, Some(0), 0, 0, Rs2Format::Rgba8, 30)
+                .enable_stream(
+                    Rs2StreamKind::Color,
+                    Some(0),
+                    None,
+                    None,
+                    Rs2Format::Rgba8,
+                    30,
+                )
                 .unwrap()
-                .enable_stream(Rs2StreamKind::Depth, Some(0), 0, 0, Rs2Format::Z16, 30)
+                .enable_stream(
+                    Rs2StreamKind::Depth,
+                    Some(0),
+                    None,
+                    None,
+                    Rs2Format::Z16,
+                    30,
+                )
                 .unwrap()
-                .enable_stream(Rs2StreamKind::Infrared, Some(1), 0, 0, Rs2Format::Y8, 30)
+                .enable_stream(
+                    Rs2StreamKind::Infrared,
+                    Some(1),
+                    None,
+                    None,
+                    Rs2Format::Y8,
+                    30,
+                )
                 .unwrap();
         }
 
@@ -90,9 +145,23 @@ fn d400_streams_at_expected_framerate() {
             .unwrap()
             .disable_all_streams()
             .unwrap()
-            .enable_stream(Rs2StreamKind::Color, None, 0, 0, Rs2Format::Rgb8, framerate)
+            .enable_stream(
+                Rs2StreamKind::Color,
+                None,
+                None,
+                None,
+                Rs2Format::Rgb8,
+                framerate,
+            )
             .unwrap()
-            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, framerate)
+            .enable_stream(
+                Rs2StreamKind::Depth,
+                None,
+                None,
+                None,
+                Rs2Format::Z16,
+                framerate,
+            )
             .unwrap();
 
         let pipeline = InactivePipeline::try_from(&context).unwrap();
@@ -165,13 +234,27 @@ fn d400_streams_are_distinct() {
                 .unwrap()
                 .disable_all_streams()
                 .unwrap()
-                .enable_stream(Rs2StreamKind::Color, None, 0, 0, Rs2Format::Rgba8, 30)
+                .enable_stream(Rs2StreamKind::Color, None, None, None, Rs2Format::Rgba8, 30)
                 .unwrap()
-                .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+                .enable_stream(Rs2StreamKind::Depth, None, None, None, Rs2Format::Z16, 30)
                 .unwrap()
-                .enable_stream(Rs2StreamKind::Infrared, Some(1), 0, 0, Rs2Format::Y8, 30)
+                .enable_stream(
+                    Rs2StreamKind::Infrared,
+                    Some(1),
+                    None,
+                    None,
+                    Rs2Format::Y8,
+                    30,
+                )
                 .unwrap()
-                .enable_stream(Rs2StreamKind::Infrared, Some(2), 0, 0, Rs2Format::Y8, 30)
+                .enable_stream(
+                    Rs2StreamKind::Infrared,
+                    Some(2),
+                    None,
+                    None,
+                    Rs2Format::Y8,
+                    30,
+                )
                 .unwrap();
         } else {
             expected_frame_count = 2;
@@ -180,9 +263,9 @@ fn d400_streams_are_distinct() {
                 .unwrap()
                 .disable_all_streams()
                 .unwrap()
-                .enable_stream(Rs2StreamKind::Color, None, 0, 0, Rs2Format::Rgba8, 30)
+                .enable_stream(Rs2StreamKind::Color, None, None, None, Rs2Format::Rgba8, 30)
                 .unwrap()
-                .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+                .enable_stream(Rs2StreamKind::Depth, None, None, None, Rs2Format::Z16, 30)
                 .unwrap();
         }
 
@@ -198,6 +281,20 @@ fn d400_streams_are_distinct() {
             frames.frames_of_type::<InfraredFrame>().len(),
             expected_frame_count - 2
         );
+        assert!(frames.first_of_type::<ColorFrame>().is_some());
+        assert!(frames.first_of_type::<PoseFrame>().is_none());
+
+        let frames_with_profiles: Vec<_> = frames.iter_with_profiles().collect();
+        assert_eq!(frames_with_profiles.len(), expected_frame_count);
+        for (frame, profile) in &frames_with_profiles {
+            assert_eq!(frame.stream_profile().unique_id(), profile.unique_id());
+            match frame {
+                AnyFrame::Color(_) => assert_eq!(profile.kind(), Rs2StreamKind::Color),
+                AnyFrame::Depth(_) => assert_eq!(profile.kind(), Rs2StreamKind::Depth),
+                AnyFrame::Infrared(_) => assert_eq!(profile.kind(), Rs2StreamKind::Infrared),
+                _ => panic!("Unexpected frame: {:?}", frame),
+            }
+        }
     }
 }
 
@@ -255,11 +352,11 @@ fn d400_streams_check_supported_but_ignored_sensor_options() {
             .unwrap()
             .disable_all_streams()
             .unwrap()
-            .enable_stream(Rs2StreamKind::Color, None, 0, 0, Rs2Format::Yuyv, 30)
+            .enable_stream(Rs2StreamKind::Color, None, None, None, Rs2Format::Yuyv, 30)
             .unwrap()
-            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .enable_stream(Rs2StreamKind::Depth, None, None, None, Rs2Format::Z16, 30)
             .unwrap()
-            .enable_stream(Rs2StreamKind::Infrared, None, 0, 0, Rs2Format::Y8, 30)
+            .enable_stream(Rs2StreamKind::Infrared, None, None, None, Rs2Format::Y8, 30)
             .unwrap();
 
         let pipeline = InactivePipeline::try_from(&context).unwrap();
@@ -307,7 +404,7 @@ fn d400_frame_numbers_increase() {
             .unwrap()
             .disable_all_streams()
             .unwrap()
-            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .enable_stream(Rs2StreamKind::Depth, None, None, None, Rs2Format::Z16, 30)
             .unwrap();
 
         let pipeline = InactivePipeline::try_from(&context).unwrap();
@@ -350,7 +447,7 @@ fn d400_region_of_interest_accessible() {
             .unwrap()
             .disable_all_streams()
             .unwrap()
-            .enable_stream(Rs2StreamKind::Color, None, 0, 0, Rs2Format::Rgba8, 30)
+            .enable_stream(Rs2StreamKind::Color, None, None, None, Rs2Format::Rgba8, 30)
             .unwrap();
 
         let pipeline = InactivePipeline::try_from(&context).unwrap();
@@ -386,3 +483,268 @@ fn d400_region_of_interest_accessible() {
         color_sensor.set_region_of_interest(roi).unwrap();
     }
 }
+
+#[test]
+fn d400_reports_d400_product_line() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let product_line = device.product_line().unwrap();
+        assert_eq!(product_line, Rs2ProductLine::D400);
+        assert!(Rs2ProductLine::Depth.contains(product_line));
+    }
+}
+
+#[test]
+fn d400_frames_remain_usable_after_being_aligned() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Color, None, None, None, Rs2Format::Rgba8, 30)
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, None, None, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        let mut align = Align::new(Rs2StreamKind::Color, 1).unwrap();
+        let frames = pipeline.wait(None).unwrap();
+        frames.process_into(&mut align).unwrap();
+        let aligned = align.wait(Duration::from_secs(5)).unwrap();
+
+        assert_eq!(frames.frames_of_type::<DepthFrame>().len(), 1);
+        assert_eq!(aligned.frames_of_type::<DepthFrame>().len(), 1);
+    }
+}
+
+#[test]
+fn d400_has_typed_depth_and_color_sensors() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let depth_sensor = device.depth_sensor().unwrap();
+        assert!(depth_sensor.depth_units().unwrap() > 0.0);
+        assert!(device.color_sensor().is_some());
+        assert!(device.pose_sensor().is_none());
+    }
+}
+
+#[test]
+fn d400_rolling_buffer_keeps_latest_frames() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, None, None, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        let mut buffer = RollingFrameBuffer::<DepthFrame>::new(3);
+        for _ in 0..20 {
+            let frames = pipeline.wait(None).unwrap();
+            for frame in frames.frames_of_type::<DepthFrame>() {
+                buffer.push(frame);
+            }
+        }
+
+        assert!(buffer.is_full());
+        let numbers: Vec<u64> = buffer.iter().map(|f| f.frame_number()).collect();
+        assert!(numbers.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(
+            buffer.latest().unwrap().frame_number(),
+            buffer.get(0).unwrap().frame_number()
+        );
+    }
+}
+
+#[test]
+fn d400_frames_can_be_extracted_by_stream_kind() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Color, None, None, None, Rs2Format::Rgba8, 30)
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, None, None, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        let frames = pipeline.wait(None).unwrap();
+        let depth_frames = frames.frames_of_stream(Rs2StreamKind::Depth);
+        assert_eq!(depth_frames.len(), 1);
+        assert!(matches!(depth_frames[0], AnyFrame::Depth(_)));
+        assert!(frames.frames_of_stream(Rs2StreamKind::Gyro).is_empty());
+    }
+}
+
+#[test]
+fn d400_depth_sensor_can_be_streamed_directly_and_reopened() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let sensor = device
+            .sensors()
+            .into_iter()
+            .find(|s| s.is_extendable_to(Rs2Extension::DepthSensor))
+            .unwrap();
+        let profiles = sensor.stream_profiles();
+        let profile = profiles
+            .iter()
+            .find(|p| p.kind() == Rs2StreamKind::Depth && p.format() == Rs2Format::Z16)
+            .unwrap();
+
+        let mut stream = sensor.open(&[profile], 1).unwrap();
+        let frame = stream.wait(Duration::from_secs(5)).unwrap();
+        assert!(matches!(frame, AnyFrame::Depth(_)));
+        drop(frame);
+
+        // Closing the stream releases the sensor, so it can be opened again.
+        let sensor = stream.close().unwrap();
+        let stream = sensor.open(&[profile], 1).unwrap();
+        drop(stream);
+    }
+}
+
+#[test]
+fn d400_depth_to_imu_extrinsics_require_an_imu() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let extrinsics = device.depth_to_imu_extrinsics();
+        if device.motion_sensor().is_some() {
+            let extrinsics = extrinsics.unwrap();
+            // The IMU sits within a few centimeters of the depth camera.
+            let distance: f32 = extrinsics
+                .translation()
+                .iter()
+                .map(|t| t * t)
+                .sum::<f32>()
+                .sqrt();
+            assert!(distance < 0.1);
+        } else {
+            assert!(extrinsics.is_err());
+        }
+    }
+}
+
+#[test]
+fn d400_visual_preset_can_be_read_back() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let mut depth_sensor = device.depth_sensor().unwrap();
+        depth_sensor
+            .set_visual_preset(Rs2Rs400VisualPreset::HighAccuracy)
+            .unwrap();
+        assert_eq!(
+            depth_sensor.current_visual_preset(),
+            Some(Rs2Rs400VisualPreset::HighAccuracy)
+        );
+
+        depth_sensor
+            .set_visual_preset(Rs2Rs400VisualPreset::Default)
+            .unwrap();
+        assert_eq!(
+            depth_sensor.current_visual_preset(),
+            Some(Rs2Rs400VisualPreset::Default)
+        );
+    }
+}
+
+#[test]
+fn d400_try_frames_of_type_reports_no_errors_for_healthy_frames() {
+    let context = Context::new().unwrap();
+
+    let mut queryable_set = HashSet::new();
+    queryable_set.insert(Rs2ProductLine::D400);
+
+    let devices = context.query_devices(queryable_set);
+
+    if let Some(device) = devices.first() {
+        let serial = device.info(Rs2CameraInfo::SerialNumber).unwrap();
+        let mut config = Config::new();
+        config
+            .enable_device_from_serial(serial)
+            .unwrap()
+            .disable_all_streams()
+            .unwrap()
+            .enable_stream(Rs2StreamKind::Depth, None, None, None, Rs2Format::Z16, 30)
+            .unwrap();
+
+        let pipeline = InactivePipeline::try_from(&context).unwrap();
+        let mut pipeline = pipeline.start(Some(config)).unwrap();
+
+        let frames = pipeline.wait(None).unwrap();
+        let (depth_frames, errors) = frames.try_frames_of_type::<DepthFrame>();
+        assert_eq!(depth_frames.len(), 1);
+        assert!(errors.is_empty());
+
+        let (color_frames, errors) = frames.try_frames_of_type::<ColorFrame>();
+        assert!(color_frames.is_empty());
+        assert!(errors.is_empty());
+    }
+}
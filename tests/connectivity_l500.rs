@@ -33,11 +33,25 @@ fn l500_can_resolve_color_and_depth_and_infrared() {
             .unwrap()
             .disable_all_streams()
             .unwrap()
-            .enable_stream(Rs2StreamKind::Color, None, 0, 0, Rs2Format::Rgba8, 30)
+            .enable_stream(Rs2StreamKind::Color, None, None, None, Rs2Format::Rgba8, 30)
             .unwrap()
-            .enable_stream(Rs2StreamKind::Depth, Some(0), 0, 0, Rs2Format::Z16, 30)
+            .enable_stream(
+                Rs2StreamKind::Depth,
+                Some(0),
+                None,
+                None,
+                Rs2Format::Z16,
+                30,
+            )
             .unwrap()
-            .enable_stream(Rs2StreamKind::Infrared, Some(0), 0, 0, Rs2Format::Y8, 30)
+            .enable_stream(
+                Rs2StreamKind::Infrared,
+                Some(0),
+                None,
+                None,
+                Rs2Format::Y8,
+                30,
+            )
             .unwrap();
 
         let pipeline = InactivePipeline::try_from(&context).unwrap();
@@ -71,13 +85,20 @@ fn l500_streams_at_expected_framerate() {
                 .unwrap()
                 .disable_all_streams()
                 .unwrap()
-                .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, framerate)
+                .enable_stream(
+                    Rs2StreamKind::Depth,
+                    None,
+                    None,
+                    None,
+                    Rs2Format::Z16,
+                    framerate,
+                )
                 .unwrap()
                 .enable_stream(
                     Rs2StreamKind::Infrared,
                     None,
-                    0,
-                    0,
+                    None,
+                    None,
                     Rs2Format::Y8,
                     framerate,
                 )
@@ -89,7 +110,14 @@ fn l500_streams_at_expected_framerate() {
                 .unwrap()
                 .disable_all_streams()
                 .unwrap()
-                .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, framerate)
+                .enable_stream(
+                    Rs2StreamKind::Depth,
+                    None,
+                    None,
+                    None,
+                    Rs2Format::Z16,
+                    framerate,
+                )
                 .unwrap();
         }
 
@@ -157,11 +185,11 @@ fn l500_streams_are_distinct() {
             .unwrap()
             .disable_all_streams()
             .unwrap()
-            .enable_stream(Rs2StreamKind::Color, None, 0, 0, Rs2Format::Yuyv, 30)
+            .enable_stream(Rs2StreamKind::Color, None, None, None, Rs2Format::Yuyv, 30)
             .unwrap()
-            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .enable_stream(Rs2StreamKind::Depth, None, None, None, Rs2Format::Z16, 30)
             .unwrap()
-            .enable_stream(Rs2StreamKind::Infrared, None, 0, 0, Rs2Format::Y8, 30)
+            .enable_stream(Rs2StreamKind::Infrared, None, None, None, Rs2Format::Y8, 30)
             .unwrap();
 
         let pipeline = InactivePipeline::try_from(&context).unwrap();
@@ -233,11 +261,11 @@ fn l500_streams_check_supported_but_ignored_sensor_options() {
             .unwrap()
             .disable_all_streams()
             .unwrap()
-            .enable_stream(Rs2StreamKind::Color, None, 0, 0, Rs2Format::Yuyv, 30)
+            .enable_stream(Rs2StreamKind::Color, None, None, None, Rs2Format::Yuyv, 30)
             .unwrap()
-            .enable_stream(Rs2StreamKind::Depth, None, 0, 0, Rs2Format::Z16, 30)
+            .enable_stream(Rs2StreamKind::Depth, None, None, None, Rs2Format::Z16, 30)
             .unwrap()
-            .enable_stream(Rs2StreamKind::Infrared, None, 0, 0, Rs2Format::Y8, 30)
+            .enable_stream(Rs2StreamKind::Infrared, None, None, None, Rs2Format::Y8, 30)
             .unwrap();
 
         let pipeline = InactivePipeline::try_from(&context).unwrap();
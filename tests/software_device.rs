@@ -0,0 +1,75 @@
+//! Tests for constructing frames from in-memory pixel buffers
+//!
+//! These do not require a device to be connected.
+
+use realsense_rust::{
+    base::Rs2Intrinsics,
+    frame::{DepthFrame, PixelKind},
+    kind::{Rs2Format, Rs2StreamKind},
+    software_device::{SoftwareDevice, SoftwareDeviceError, SoftwareVideoStream},
+};
+use realsense_sys as sys;
+
+/// Width of the test depth stream, in pixels.
+const WIDTH: usize = 4;
+/// Height of the test depth stream, in pixels.
+const HEIGHT: usize = 2;
+
+/// A small Z16 depth stream used by all tests in this file.
+fn depth_stream() -> SoftwareVideoStream {
+    SoftwareVideoStream {
+        kind: Rs2StreamKind::Depth,
+        index: 0,
+        uid: 0,
+        width: WIDTH,
+        height: HEIGHT,
+        framerate: 30,
+        bytes_per_pixel: 2,
+        format: Rs2Format::Z16,
+        intrinsics: Rs2Intrinsics(sys::rs2_intrinsics {
+            width: WIDTH as i32,
+            height: HEIGHT as i32,
+            ppx: WIDTH as f32 / 2.0,
+            ppy: HEIGHT as f32 / 2.0,
+            fx: 1.0,
+            fy: 1.0,
+            model: sys::rs2_distortion_RS2_DISTORTION_NONE,
+            coeffs: [0.0; 5],
+        }),
+        depth_units: 0.001,
+    }
+}
+
+#[test]
+fn software_device_produces_depth_frame_from_pixels() {
+    let mut device = SoftwareDevice::new(depth_stream()).unwrap();
+
+    let depths: Vec<u16> = (0..(WIDTH * HEIGHT) as u16).map(|d| d * 100).collect();
+    let pixels: Vec<u8> = depths.iter().flat_map(|d| d.to_ne_bytes()).collect();
+
+    let frame: DepthFrame = device.frame(&pixels, 0.0).unwrap();
+
+    assert_eq!(frame.width(), WIDTH);
+    assert_eq!(frame.height(), HEIGHT);
+    for (pixel, expected) in frame.iter().zip(depths.iter()) {
+        match pixel {
+            PixelKind::Z16 { depth } => assert_eq!(depth, expected),
+            _ => panic!("Expected a Z16 pixel"),
+        }
+    }
+}
+
+#[test]
+fn software_device_rejects_short_buffers() {
+    let mut device = SoftwareDevice::new(depth_stream()).unwrap();
+
+    let err = device.frame::<DepthFrame>(&[0u8; 3], 0.0).unwrap_err();
+
+    assert!(matches!(
+        err.downcast_ref::<SoftwareDeviceError>(),
+        Some(SoftwareDeviceError::PixelBufferTooSmall {
+            expected: 16,
+            actual: 3
+        })
+    ));
+}